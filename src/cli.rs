@@ -0,0 +1,492 @@
+use anyhow::{anyhow, Context, Result};
+use clap::Subcommand;
+use serde::Serialize;
+
+use crate::{
+    config::Settings,
+    daemon::{self, Request},
+    ex::ExportFormat,
+    execute_action,
+    github::fetch_notifications_and_my_prs_cached,
+    ignore::{append_ignore_rule, load_ignored_prs},
+    pins::{load_pinned_urls, retain_unread_or_pinned},
+    status::{
+        read_status_cache, render_status, validate_format, write_status_cache, StatusSummary,
+        DEFAULT_STATUS_FORMAT,
+    },
+    types::{Action, MyPullRequest, Notification},
+    ui::{self, DisplayEntryKey, NotificationBucket},
+    util::gh_auth_token,
+    AppState, EntrySnapshot,
+};
+
+#[derive(Subcommand, Debug)]
+pub enum CliCommand {
+    /// Print the inbox in display order and exit
+    List {
+        #[arg(long, help = "Print JSON instead of text")]
+        json: bool,
+        #[arg(
+            long = "bucket",
+            value_name = "BUCKET",
+            help = "Only show these buckets (repeatable): pinned, ready_to_merge, needs_action, waiting_on_ci, needs_review, other, draft"
+        )]
+        buckets: Vec<String>,
+    },
+    /// Mark notifications as read
+    Read {
+        #[arg(required = true, value_name = "URL|ID")]
+        targets: Vec<String>,
+    },
+    /// Mark notifications as done
+    Done {
+        #[arg(required = true, value_name = "URL|ID")]
+        targets: Vec<String>,
+    },
+    /// Print a one-line summary for status bars and exit
+    Status {
+        #[arg(
+            long,
+            value_name = "FORMAT",
+            help = "Format string using {unread}, {total}, {failing_ci}, {review_requests} and bucket names like {needs_review}"
+        )]
+        format: Option<String>,
+        #[arg(
+            long,
+            help = "Use the summary cached by the last fetch when it is fresh enough"
+        )]
+        cached: bool,
+        #[arg(
+            long,
+            value_name = "SECS",
+            default_value_t = 300,
+            help = "Maximum cache age for --cached"
+        )]
+        max_age: u64,
+    },
+    /// Add a PR URL or rule (e.g. `repo:acme/*`) to the ignore list
+    Ignore {
+        #[arg(required = true, num_args = 1.., value_name = "RULE")]
+        rule: Vec<String>,
+    },
+    /// Poll in the background and share the inbox with other ghn processes over a socket
+    Daemon,
+}
+
+/// One row of `ghn list`; `index` matches the number shown in the TUI.
+#[derive(Debug, Serialize)]
+struct ListEntry<'a> {
+    index: usize,
+    bucket: &'static str,
+    source: &'static str,
+    id: Option<&'a str>,
+    repo: &'a str,
+    kind: &'a str,
+    title: &'a str,
+    url: &'a str,
+    author: Option<&'a str>,
+    unread: bool,
+    pinned: bool,
+    reason: Option<&'a str>,
+    updated_at: &'a str,
+}
+
+pub async fn run(command: CliCommand, settings: &Settings) -> Result<()> {
+    match command {
+        CliCommand::List { json, buckets } => {
+            let buckets = parse_buckets(&buckets)?;
+            let app = load_inbox(settings).await?;
+            let entries = list_entries(&app, &buckets);
+            if json {
+                println!("{}", serde_json::to_string_pretty(&entries)?);
+            } else {
+                print_entries(&entries);
+            }
+        }
+        CliCommand::Read { targets } => {
+            let request = Request::Read {
+                targets: targets.clone(),
+            };
+            mark_notifications(settings, &targets, request, Action::Read, "read").await?;
+        }
+        CliCommand::Done { targets } => {
+            let request = Request::Done {
+                targets: targets.clone(),
+            };
+            mark_notifications(settings, &targets, request, Action::Done, "done").await?;
+        }
+        CliCommand::Status {
+            format,
+            cached,
+            max_age,
+        } => {
+            let format = format.as_deref().unwrap_or(DEFAULT_STATUS_FORMAT);
+            validate_format(format)?;
+            let now = chrono::Utc::now().timestamp();
+            let cached = if cached {
+                read_status_cache(max_age, now)?
+            } else {
+                None
+            };
+            let summary = match cached {
+                Some(summary) => summary,
+                None => {
+                    let app = load_inbox(settings).await?;
+                    let summary = StatusSummary::new(&app.notifications, &app.my_prs, now);
+                    // Refresh the cache for the next --cached run; failing to write is harmless.
+                    let _ = write_status_cache(&summary);
+                    summary
+                }
+            };
+            println!("{}", render_status(format, &summary)?);
+        }
+        CliCommand::Ignore { rule } => {
+            let rule = rule.join(" ");
+            if append_ignore_rule(&rule)? {
+                println!("Ignoring {}", rule.trim());
+            } else {
+                println!("Already ignored: {}", rule.trim());
+            }
+        }
+        CliCommand::Daemon => daemon::run(settings).await?,
+    }
+    Ok(())
+}
+
+fn connect() -> Result<(reqwest::Client, String)> {
+    Ok((reqwest::Client::new(), gh_auth_token()?))
+}
+
+/// Loads the inbox from the daemon if one is running, otherwise fetches it once.
+async fn load_inbox(settings: &Settings) -> Result<AppState> {
+    let include_read = !settings.unread_only;
+    let pinned = load_pinned_urls()?;
+    let response = daemon::request(&daemon::socket_path(settings)?, &Request::State).await?;
+    let (notifications, my_prs) = match response {
+        Some(response) => response
+            .snapshot
+            .ok_or_else(|| anyhow!("daemon sent no inbox"))?
+            .into_inbox(include_read, &pinned),
+        None => {
+            let (client, token) = connect()?;
+            let fetch_read = include_read || !pinned.is_empty();
+            let mut payload =
+                fetch_notifications_and_my_prs_cached(&client, &token, fetch_read, None).await?;
+            if !include_read {
+                retain_unread_or_pinned(&mut payload.notifications, &pinned);
+            }
+            (payload.notifications, payload.my_prs)
+        }
+    };
+    build_inbox(notifications, my_prs, include_read)
+}
+
+/// Applies ignore rules, pins and sorting exactly as the TUI does.
+pub(crate) fn build_inbox(
+    notifications: Vec<Notification>,
+    my_prs: Vec<MyPullRequest>,
+    include_read: bool,
+) -> Result<AppState> {
+    let mut app = AppState::new(include_read, load_ignored_prs()?);
+    app.pinned_urls = load_pinned_urls()?;
+    app.set_data(notifications, my_prs);
+    Ok(app)
+}
+
+fn parse_buckets(names: &[String]) -> Result<Vec<NotificationBucket>> {
+    names
+        .iter()
+        .flat_map(|names| names.split(','))
+        .map(|name| {
+            NotificationBucket::from_name(name.trim()).ok_or_else(|| {
+                let known: Vec<&str> = NotificationBucket::ALL.iter().map(|b| b.name()).collect();
+                anyhow!(
+                    "unknown bucket '{}' (expected one of: {})",
+                    name,
+                    known.join(", ")
+                )
+            })
+        })
+        .collect()
+}
+
+fn list_entries<'a>(app: &'a AppState, buckets: &[NotificationBucket]) -> Vec<ListEntry<'a>> {
+    let mut entries = Vec::new();
+    let mut index = 0usize;
+    for (bucket, keys) in ui::build_bucket_key_sections(&app.notifications, &app.my_prs) {
+        for key in keys {
+            // Count every entry so numbers stay the same as in the TUI when filtering.
+            index += 1;
+            if !buckets.is_empty() && !buckets.contains(&bucket) {
+                continue;
+            }
+            let entry = match key {
+                DisplayEntryKey::Notification(idx) => {
+                    let notification = &app.notifications[idx];
+                    ListEntry {
+                        index,
+                        bucket: bucket.name(),
+                        source: "notification",
+                        id: Some(&notification.id),
+                        repo: &notification.repository.full_name,
+                        kind: &notification.subject.kind,
+                        title: &notification.subject.title,
+                        url: &notification.subject.url,
+                        author: notification.subject.author.as_deref(),
+                        unread: notification.unread,
+                        pinned: notification.pinned,
+                        reason: Some(&notification.reason),
+                        updated_at: &notification.updated_at,
+                    }
+                }
+                DisplayEntryKey::MyPullRequest(idx) => {
+                    let pr = &app.my_prs[idx];
+                    ListEntry {
+                        index,
+                        bucket: bucket.name(),
+                        source: "my_pr",
+                        id: None,
+                        repo: &pr.repository.full_name,
+                        kind: &pr.subject.kind,
+                        title: &pr.subject.title,
+                        url: &pr.subject.url,
+                        author: pr.subject.author.as_deref(),
+                        unread: false,
+                        pinned: pr.pinned,
+                        reason: None,
+                        updated_at: &pr.updated_at,
+                    }
+                }
+            };
+            entries.push(entry);
+        }
+    }
+    entries
+}
+
+fn print_entries(entries: &[ListEntry<'_>]) {
+    let mut current = None;
+    for entry in entries {
+        if current != Some(entry.bucket) {
+            if current.is_some() {
+                println!();
+            }
+            let title = NotificationBucket::from_name(entry.bucket)
+                .map(NotificationBucket::title)
+                .unwrap_or(entry.bucket);
+            println!("{}", title);
+            current = Some(entry.bucket);
+        }
+        println!(
+            "{:>3} {} {}  {}  {}",
+            entry.index,
+            if entry.unread { "*" } else { " " },
+            entry.repo,
+            entry.title,
+            entry.url
+        );
+    }
+}
+
+/// The listed items as Markdown or JSON for `:export`, keeping only `visible` indices.
+pub(crate) fn render_export(
+    app: &AppState,
+    format: ExportFormat,
+    visible: &[usize],
+) -> Result<(String, usize)> {
+    let entries: Vec<ListEntry<'_>> = list_entries(app, &[])
+        .into_iter()
+        .filter(|entry| visible.contains(&entry.index))
+        .collect();
+    let text = match format {
+        ExportFormat::Json => serde_json::to_string_pretty(&entries)?,
+        ExportFormat::Markdown => {
+            let mut lines = Vec::new();
+            let mut current = None;
+            for entry in &entries {
+                if current != Some(entry.bucket) {
+                    if current.is_some() {
+                        lines.push(String::new());
+                    }
+                    let title = NotificationBucket::from_name(entry.bucket)
+                        .map(NotificationBucket::title)
+                        .unwrap_or(entry.bucket);
+                    lines.push(format!("## {}", title));
+                    lines.push(String::new());
+                    current = Some(entry.bucket);
+                }
+                lines.push(format!(
+                    "- [{}]({}) ({})",
+                    entry.title.replace('[', "\\[").replace(']', "\\]"),
+                    entry.url,
+                    entry.repo
+                ));
+            }
+            lines.join("\n")
+        }
+    };
+    Ok((text, entries.len()))
+}
+
+pub(crate) fn find_notification<'a>(
+    notifications: &'a [Notification],
+    target: &str,
+) -> Option<&'a Notification> {
+    let target = target.trim();
+    notifications.iter().find(|notification| {
+        notification.id == target
+            || notification.subject.url == target
+            || notification.url == target
+    })
+}
+
+async fn mark_notifications(
+    settings: &Settings,
+    targets: &[String],
+    request: Request,
+    action: Action,
+    label: &str,
+) -> Result<()> {
+    // Going through the daemon updates every connected TUI straight away.
+    if let Some(response) = daemon::request(&daemon::socket_path(settings)?, &request).await? {
+        for url in response.marked {
+            println!("Marked {} as {}", url, label);
+        }
+        return Ok(());
+    }
+
+    let (client, token) = connect()?;
+    let app = load_inbox(settings).await?;
+
+    // Resolve everything first so a typo doesn't leave the batch half applied.
+    let notifications = targets
+        .iter()
+        .map(|target| {
+            find_notification(&app.notifications, target)
+                .ok_or_else(|| anyhow!("no notification matches '{}'", target))
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    for notification in notifications {
+        let entry = EntrySnapshot::Notification(notification.clone());
+        execute_action(&client, &token, action, &entry, entry.url(), None)
+            .await
+            .with_context(|| format!("failed to mark {} as {}", notification.subject.url, label))?;
+        println!("Marked {} as {}", notification.subject.url, label);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{find_notification, list_entries, parse_buckets, render_export};
+    use crate::ex::ExportFormat;
+    use crate::ignore::IgnoreList;
+    use crate::types::{Notification, Repository, Subject};
+    use crate::ui::NotificationBucket;
+    use crate::AppState;
+
+    fn sample_notification(id: &str, kind: &str) -> Notification {
+        Notification {
+            id: id.to_string(),
+            node_id: format!("node-{id}"),
+            subject_id: None,
+            unread: true,
+            pinned: false,
+            reason: "mention".to_string(),
+            updated_at: "2024-01-01T00:00:00Z".to_string(),
+            subject: Subject {
+                title: format!("Item {id}"),
+                url: format!("https://github.com/acme/widgets/issues/{id}"),
+                kind: kind.to_string(),
+                author: None,
+                status: Vec::new(),
+                ci_status: None,
+                review_status: None,
+                merge_state_status: None,
+                head_ref: None,
+            },
+            repository: Repository {
+                name: "widgets".to_string(),
+                full_name: "acme/widgets".to_string(),
+                merge_settings: None,
+            },
+            url: format!("https://api.github.com/notifications/threads/{id}"),
+        }
+    }
+
+    #[test]
+    fn parse_buckets_accepts_lists_and_rejects_unknown() {
+        assert_eq!(
+            parse_buckets(&["needs_review,draft".to_string(), "pinned".to_string()]).unwrap(),
+            vec![
+                NotificationBucket::NeedsReview,
+                NotificationBucket::Draft,
+                NotificationBucket::Pinned
+            ]
+        );
+        let err = parse_buckets(&["soon".to_string()]).unwrap_err();
+        assert!(err.to_string().contains("ready_to_merge"));
+    }
+
+    #[test]
+    fn list_entries_keep_tui_numbers_when_filtered() {
+        let mut app = AppState::new(true, IgnoreList::default());
+        app.set_data(
+            vec![
+                sample_notification("1", "Issue"),
+                sample_notification("2", "PullRequest"),
+            ],
+            Vec::new(),
+        );
+
+        let all = list_entries(&app, &[]);
+        assert_eq!(all.len(), 2);
+        assert_eq!(all[0].bucket, "needs_review");
+        assert_eq!(all[1].bucket, "other");
+
+        let other = list_entries(&app, &[NotificationBucket::Other]);
+        assert_eq!(other.len(), 1);
+        assert_eq!(other[0].index, 2);
+        assert_eq!(other[0].id, Some("1"));
+    }
+
+    #[test]
+    fn render_export_groups_visible_items_as_markdown() {
+        let mut app = AppState::new(true, IgnoreList::default());
+        app.set_data(
+            vec![
+                sample_notification("1", "Issue"),
+                sample_notification("2", "PullRequest"),
+                sample_notification("3", "PullRequest"),
+            ],
+            Vec::new(),
+        );
+
+        let (text, count) = render_export(&app, ExportFormat::Markdown, &[1, 3]).unwrap();
+        assert_eq!(count, 2);
+        assert_eq!(
+            text,
+            "## Needs Review\n\n- [Item 2](https://github.com/acme/widgets/issues/2) (acme/widgets)\n\n\
+             ## Other\n\n- [Item 1](https://github.com/acme/widgets/issues/1) (acme/widgets)"
+        );
+        let (json, _) = render_export(&app, ExportFormat::Json, &[2]).unwrap();
+        assert!(json.contains("\"index\": 2"));
+    }
+
+    #[test]
+    fn find_notification_matches_id_or_url() {
+        let notifications = vec![
+            sample_notification("1", "Issue"),
+            sample_notification("2", "Issue"),
+        ];
+        assert_eq!(find_notification(&notifications, "2").unwrap().id, "2");
+        assert_eq!(
+            find_notification(&notifications, "https://github.com/acme/widgets/issues/1")
+                .unwrap()
+                .id,
+            "1"
+        );
+        assert!(find_notification(&notifications, "3").is_none());
+    }
+}
@@ -1,9 +1,219 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 
+use anyhow::{anyhow, Result};
+
+use crate::keymap::Keymap;
 use crate::types::Action;
 
-pub fn is_target_char(ch: char) -> bool {
-    matches!(ch, 'm' | 'c' | 'f' | '?' | 'a' | 'x' | '!' | 'u' | 'w')
+/// Prefix for notification reason targets such as `#mention`.
+pub const REASON_SIGIL: char = '#';
+/// Prefix for repository targets: `@owner/repo`, or `@repo` for that name under any owner.
+pub const REPO_SIGIL: char = '@';
+/// Prefix for author targets such as `~renovate[bot]`.
+pub const AUTHOR_SIGIL: char = '~';
+/// Prefix for section targets: `%ready` for the whole section, `%ready2` for its second item.
+pub const SECTION_SIGIL: char = '%';
+/// Items not updated for longer than an age, e.g. `>7d`.
+pub const OLDER_THAN: char = '>';
+/// Items updated within an age, e.g. `<1h`.
+pub const NEWER_THAN: char = '<';
+
+/// Prefix for a saved macro from `[macros]`, e.g. `:triage`.
+pub const MACRO_SIGIL: char = ':';
+
+/// Every visible item.
+pub const ALL_TARGET: char = '*';
+/// Joins two targets so only items in both are kept, e.g. `m&u`.
+pub const INTERSECT: char = '&';
+/// Flips the next target, e.g. `^a` for everything not approved.
+pub const NEGATE: char = '^';
+
+const NAMED_TARGET_SIGILS: [char; 6] = [
+    REASON_SIGIL,
+    REPO_SIGIL,
+    AUTHOR_SIGIL,
+    SECTION_SIGIL,
+    OLDER_THAN,
+    NEWER_THAN,
+];
+
+/// Target syntax beyond single keys, for the help overlay; tests run every example through the parser.
+pub const TARGET_SYNTAX: [(&str, &str); 9] = [
+    ("1-3, 1 2 3, 1,4", "Numbers, ranges and lists"),
+    ("#mention", "Notifications with that reason"),
+    ("@acme/widgets, @widgets", "Items in a repository"),
+    ("~octocat", "Items by an author"),
+    (
+        "%ready, %ready2, %ready1-3",
+        "A section, or positions in it",
+    ),
+    (
+        ">7d, <1h",
+        "Not updated for longer than, or updated within, an age",
+    ),
+    ("m&u", "Items in both targets"),
+    ("^a", "Items not in the target"),
+    ("*", "Every visible item"),
+];
+
+/// Whether a command-line word is a named target, where any text is allowed up to the next separator.
+pub fn is_named_target(word: &str) -> bool {
+    word.starts_with(NAMED_TARGET_SIGILS)
+}
+
+/// Display indices each target resolves to.
+#[derive(Debug, Default)]
+pub struct Targets {
+    /// Single-character groups from the keymap, e.g. `m` for merged.
+    pub groups: HashMap<char, Vec<usize>>,
+    /// Lowercased notification reasons, e.g. `mention` or `ci_activity`.
+    pub reasons: HashMap<String, Vec<usize>>,
+    /// Lowercased `owner/repo` names.
+    pub repos: HashMap<String, Vec<usize>>,
+    /// Lowercased author logins.
+    pub authors: HashMap<String, Vec<usize>>,
+    /// Every targetable index, for `*` and `^`.
+    pub all: Vec<usize>,
+    /// Every section by name, with its indices in display order, including hidden ones.
+    pub sections: Vec<(&'static str, Vec<usize>)>,
+    /// Seconds since each index was last updated; items without a valid timestamp are left out.
+    pub ages: Vec<(usize, i64)>,
+}
+
+impl Targets {
+    pub fn push_group(&mut self, ch: char, index: usize) {
+        self.groups.entry(ch).or_default().push(index);
+    }
+
+    pub fn push_reason(&mut self, reason: &str, index: usize) {
+        self.reasons
+            .entry(reason.to_lowercase())
+            .or_default()
+            .push(index);
+    }
+
+    pub fn push_repo(&mut self, full_name: &str, index: usize) {
+        self.repos
+            .entry(full_name.to_lowercase())
+            .or_default()
+            .push(index);
+    }
+
+    pub fn push_author(&mut self, login: &str, index: usize) {
+        self.authors
+            .entry(login.to_lowercase())
+            .or_default()
+            .push(index);
+    }
+
+    /// Indices for a sigil target; unknown names resolve to nothing, except for sections.
+    fn named(&self, sigil: char, name: &str) -> Result<Vec<usize>> {
+        let name = name.to_lowercase();
+        let mut indices: Vec<usize> = match sigil {
+            SECTION_SIGIL => return self.section(&name),
+            OLDER_THAN | NEWER_THAN => {
+                let limit = parse_age(&name)?;
+                self.ages
+                    .iter()
+                    .filter(|(_, age)| {
+                        if sigil == OLDER_THAN {
+                            *age > limit
+                        } else {
+                            *age < limit
+                        }
+                    })
+                    .map(|(index, _)| *index)
+                    .collect()
+            }
+            REASON_SIGIL => self.reasons.get(&name).cloned().unwrap_or_default(),
+            AUTHOR_SIGIL => self.authors.get(&name).cloned().unwrap_or_default(),
+            REPO_SIGIL if name.contains('/') => self.repos.get(&name).cloned().unwrap_or_default(),
+            REPO_SIGIL => self
+                .repos
+                .iter()
+                .filter(|(full_name, _)| {
+                    full_name
+                        .split_once('/')
+                        .is_some_and(|(_, repo)| repo == name)
+                })
+                .flat_map(|(_, indices)| indices.iter().copied())
+                .collect(),
+            _ => Vec::new(),
+        };
+        indices.sort_unstable();
+        Ok(indices)
+    }
+
+    // `spec` is a section name or a unique prefix of one of its words, optionally followed by a
+    // position or range within the section.
+    fn section(&self, spec: &str) -> Result<Vec<usize>> {
+        let (name, scope) = spec.split_at(
+            spec.find(|ch: char| ch.is_ascii_digit())
+                .unwrap_or(spec.len()),
+        );
+        if name.is_empty() {
+            return Err(anyhow!("'{}' needs a section name", SECTION_SIGIL));
+        }
+
+        let matches: Vec<&(&str, Vec<usize>)> = self
+            .sections
+            .iter()
+            .filter(|(section, _)| {
+                section.starts_with(name) || section.split('_').any(|word| word.starts_with(name))
+            })
+            .collect();
+        let members = match matches.as_slice() {
+            [(_, members)] => members,
+            [] => return Err(anyhow!("unknown section '{}'", name)),
+            _ => {
+                let names: Vec<&str> = matches.iter().map(|(section, _)| *section).collect();
+                return Err(anyhow!(
+                    "section '{}' is ambiguous: {}",
+                    name,
+                    names.join(", ")
+                ));
+            }
+        };
+
+        if scope.is_empty() {
+            return Ok(members.clone());
+        }
+        let position = |value: &str| {
+            value
+                .parse::<usize>()
+                .map_err(|_| anyhow!("invalid section position '{}'", scope))
+        };
+        let (start, end) = match scope.split_once('-') {
+            Some((start, end)) => (position(start)?, position(end)?),
+            None => (position(scope)?, position(scope)?),
+        };
+        let (low, high) = (start.min(end), start.max(end));
+        Ok(members
+            .iter()
+            .enumerate()
+            .filter(|(idx, _)| (low..=high).contains(&(idx + 1)))
+            .map(|(_, index)| *index)
+            .collect())
+    }
+}
+
+/// Parses ages like `90s`, `15m`, `1h`, `7d`, or `2w` into seconds.
+pub fn parse_age(value: &str) -> Result<i64> {
+    let invalid = || anyhow!("invalid age '{}' (expected e.g. 1h, 7d or 2w)", value);
+    let split = value
+        .find(|ch: char| !ch.is_ascii_digit())
+        .ok_or_else(invalid)?;
+    let (amount, unit) = value.split_at(split);
+    let amount: i64 = amount.parse().map_err(|_| invalid())?;
+    let seconds = match unit {
+        "s" => 1,
+        "m" => 60,
+        "h" => 60 * 60,
+        "d" => 24 * 60 * 60,
+        "w" => 7 * 24 * 60 * 60,
+        _ => return Err(invalid()),
+    };
+    amount.checked_mul(seconds).ok_or_else(invalid)
 }
 
 // Greedily split concatenated digits into the longest valid indices based on the list size.
@@ -122,27 +332,198 @@ fn finalize_range_start(
     *range_start = parsed.last().copied();
 }
 
+fn push_group<'a>(indices: &mut Vec<usize>, group: impl IntoIterator<Item = &'a usize>) {
+    for index in group {
+        if !indices.contains(index) {
+            indices.push(*index);
+        }
+    }
+}
+
+/// Targets collected for the next action: a union of terms, each narrowed by `&` and flipped by `^`.
+struct Selection<'a> {
+    all: &'a [usize],
+    indices: Vec<usize>,
+    term: Option<Vec<usize>>,
+    intersect: bool,
+    negate: bool,
+}
+
+impl<'a> Selection<'a> {
+    fn new(all: &'a [usize]) -> Self {
+        Self {
+            all,
+            indices: Vec::new(),
+            term: None,
+            intersect: false,
+            negate: false,
+        }
+    }
+
+    fn push_atom(&mut self, mut atom: Vec<usize>) {
+        if std::mem::take(&mut self.negate) {
+            atom = self
+                .all
+                .iter()
+                .copied()
+                .filter(|index| !atom.contains(index))
+                .collect();
+        }
+        match self.term.as_mut() {
+            Some(term) if std::mem::take(&mut self.intersect) => {
+                term.retain(|index| atom.contains(index));
+            }
+            _ => {
+                self.flush();
+                self.term = Some(atom);
+            }
+        }
+    }
+
+    fn flush(&mut self) {
+        if let Some(term) = self.term.take() {
+            push_group(&mut self.indices, &term);
+        }
+    }
+
+    fn resolve(&mut self) -> Result<&[usize]> {
+        self.check_operand()?;
+        self.flush();
+        Ok(&self.indices)
+    }
+
+    fn check_operand(&self) -> Result<()> {
+        if self.intersect {
+            return Err(anyhow!("'&' needs a target after it"));
+        }
+        if self.negate {
+            return Err(anyhow!("'^' needs a target after it"));
+        }
+        Ok(())
+    }
+
+    fn clear(&mut self) {
+        *self = Self::new(self.all);
+    }
+}
+
+fn flush_digits(
+    current_digits: &mut String,
+    range_start: &mut Option<usize>,
+    selection: &mut Selection<'_>,
+    notification_count: usize,
+) {
+    if current_digits.is_empty() && range_start.is_none() {
+        return;
+    }
+    let mut atom = Vec::new();
+    finalize_pending(current_digits, range_start, &mut atom, notification_count);
+    selection.push_atom(atom);
+}
+
+/// Replaces `:name` words with the saved macro they name.
+pub fn expand_macros(input: &str, macros: &BTreeMap<String, String>) -> Result<String> {
+    if !input
+        .split_whitespace()
+        .any(|word| word.starts_with(MACRO_SIGIL))
+    {
+        return Ok(input.to_string());
+    }
+    let words = input
+        .split_whitespace()
+        .map(|word| match word.strip_prefix(MACRO_SIGIL) {
+            Some(name) => macros
+                .get(name)
+                .map(String::as_str)
+                .ok_or_else(|| anyhow!("unknown macro '{}'", word)),
+            None => Ok(word),
+        })
+        .collect::<Result<Vec<_>>>()?;
+    Ok(words.join(" "))
+}
+
+/// Whether `ch` ends a named target; names may contain action letters but never these.
+pub fn ends_named_target(ch: char) -> bool {
+    ch.is_whitespace() || ch == ',' || ch == INTERSECT
+}
+
+// Named targets run until the next separator so they can contain letters that are also actions.
+fn take_word(chars: &mut std::iter::Peekable<std::str::Chars<'_>>) -> String {
+    let mut word = String::new();
+    while let Some(&ch) = chars.peek() {
+        if ends_named_target(ch) {
+            break;
+        }
+        word.push(ch);
+        chars.next();
+    }
+    word
+}
+
 pub fn parse_commands(
     input: &str,
     notification_count: usize,
-    targets: &HashMap<char, Vec<usize>>,
-) -> HashMap<usize, Vec<Action>> {
+    targets: &Targets,
+    keymap: &Keymap,
+) -> Result<HashMap<usize, Vec<Action>>> {
     let mut result: HashMap<usize, Vec<Action>> = HashMap::new();
+    parse_selection(
+        input,
+        notification_count,
+        targets,
+        keymap,
+        |action, indices| {
+            for index in indices {
+                result.entry(*index).or_default().push(action);
+            }
+            Ok(())
+        },
+    )?;
+    Ok(result)
+}
+
+/// Resolves a target expression with no actions, such as the `3` in `:snooze 3 2h`.
+pub fn parse_targets(
+    input: &str,
+    notification_count: usize,
+    targets: &Targets,
+    keymap: &Keymap,
+) -> Result<Vec<usize>> {
+    parse_selection(input, notification_count, targets, keymap, |action, _| {
+        Err(anyhow!(
+            "expected targets, found the {} action",
+            action.name()
+        ))
+    })
+}
 
+// Walks targets and actions, handing each action its targets; returns the targets left after the last action.
+fn parse_selection(
+    input: &str,
+    notification_count: usize,
+    targets: &Targets,
+    keymap: &Keymap,
+    mut on_action: impl FnMut(Action, &[usize]) -> Result<()>,
+) -> Result<Vec<usize>> {
     let mut current_digits = String::new();
     let mut range_start: Option<usize> = None;
-    let mut indices: Vec<usize> = Vec::new();
+    let mut selection = Selection::new(&targets.all);
     let mut after_action = false;
 
-    for ch in input.chars() {
-        if ch.is_ascii_digit() {
-            if after_action {
-                indices.clear();
-                range_start = None;
-                current_digits.clear();
-                after_action = false;
-            }
+    let mut chars = input.chars().peekable();
+    while let Some(ch) = chars.next() {
+        let is_target = keymap.is_target_char(ch)
+            || NAMED_TARGET_SIGILS.contains(&ch)
+            || ch == ALL_TARGET
+            || ch == NEGATE;
+        if (ch.is_ascii_digit() || is_target) && after_action {
+            selection.clear();
+            range_start = None;
+            current_digits.clear();
+            after_action = false;
+        }
 
+        if ch.is_ascii_digit() {
             current_digits.push(ch);
             continue;
         }
@@ -151,128 +532,153 @@ pub fn parse_commands(
             if after_action {
                 continue;
             }
+            let mut leading = Vec::new();
             finalize_range_start(
                 &mut current_digits,
                 &mut range_start,
-                &mut indices,
+                &mut leading,
                 notification_count,
             );
+            if !leading.is_empty() {
+                selection.push_atom(leading);
+            }
             continue;
         }
 
         if ch == ' ' || ch == ',' {
-            finalize_pending(
+            flush_digits(
                 &mut current_digits,
                 &mut range_start,
-                &mut indices,
+                &mut selection,
                 notification_count,
             );
             continue;
         }
 
-        if is_target_char(ch) {
-            if after_action {
-                indices.clear();
-                range_start = None;
-                current_digits.clear();
-                after_action = false;
+        if ch == INTERSECT {
+            flush_digits(
+                &mut current_digits,
+                &mut range_start,
+                &mut selection,
+                notification_count,
+            );
+            if after_action || selection.term.is_none() {
+                return Err(anyhow!("'&' needs a target before it"));
             }
+            selection.check_operand()?;
+            selection.intersect = true;
+            continue;
+        }
 
-            finalize_pending(
+        if is_target {
+            flush_digits(
                 &mut current_digits,
                 &mut range_start,
-                &mut indices,
+                &mut selection,
                 notification_count,
             );
-            if let Some(group) = targets.get(&ch) {
-                for index in group {
-                    if !indices.contains(index) {
-                        indices.push(*index);
-                    }
-                }
+            if ch == NEGATE {
+                selection.negate = !selection.negate;
+                continue;
             }
+            let atom = if ch == ALL_TARGET {
+                targets.all.clone()
+            } else if NAMED_TARGET_SIGILS.contains(&ch) {
+                let name = take_word(&mut chars);
+                if name.is_empty() {
+                    return Err(anyhow!("'{}' needs a name after it", ch));
+                }
+                targets.named(ch, &name)?
+            } else {
+                targets.groups.get(&ch).cloned().unwrap_or_default()
+            };
+            selection.push_atom(atom);
             continue;
         }
 
-        if let Some(action) = Action::from_char(ch) {
-            finalize_pending(
+        if let Some(action) = keymap.action(ch) {
+            flush_digits(
                 &mut current_digits,
                 &mut range_start,
-                &mut indices,
+                &mut selection,
                 notification_count,
             );
 
-            if !indices.is_empty() {
-                for index in &indices {
-                    result.entry(*index).or_default().push(action);
-                }
-            }
+            on_action(action, selection.resolve()?)?;
 
             // Keep the index list for subsequent actions until new digits appear.
             after_action = true;
             continue;
         }
 
-        current_digits.clear();
-        range_start = None;
-        indices.clear();
-        after_action = false;
+        return Err(anyhow!("unknown key '{}'", ch));
     }
 
-    result
+    if after_action {
+        selection.check_operand()?;
+        return Ok(Vec::new());
+    }
+    flush_digits(
+        &mut current_digits,
+        &mut range_start,
+        &mut selection,
+        notification_count,
+    );
+    Ok(selection.resolve()?.to_vec())
 }
 
 #[cfg(test)]
 mod tests {
-    use super::parse_commands;
+    use super::{expand_macros, parse_commands, parse_targets, Targets, TARGET_SYNTAX};
+    use crate::keymap::Keymap;
     use crate::types::Action;
-    use std::collections::HashMap;
+    use std::collections::{BTreeMap, HashMap};
 
     #[test]
     fn parses_single_actions() {
-        let targets = HashMap::new();
-        let result = parse_commands("1o", 10, &targets);
+        let targets = Targets::default();
+        let result = parse_commands("1o", 10, &targets, &Keymap::default()).unwrap();
         assert_eq!(result.get(&1), Some(&vec![Action::Open]));
 
-        let result = parse_commands("3y", 10, &targets);
+        let result = parse_commands("3y", 10, &targets, &Keymap::default()).unwrap();
         assert_eq!(result.get(&3), Some(&vec![Action::PrettyYank]));
 
-        let result = parse_commands("8Y", 10, &targets);
+        let result = parse_commands("8Y", 10, &targets, &Keymap::default()).unwrap();
         assert_eq!(result.get(&8), Some(&vec![Action::Yank]));
 
-        let result = parse_commands("5r", 10, &targets);
+        let result = parse_commands("5r", 10, &targets, &Keymap::default()).unwrap();
         assert_eq!(result.get(&5), Some(&vec![Action::Read]));
 
-        let result = parse_commands("7d", 10, &targets);
+        let result = parse_commands("7d", 10, &targets, &Keymap::default()).unwrap();
         assert_eq!(result.get(&7), Some(&vec![Action::Done]));
 
-        let result = parse_commands("2q", 10, &targets);
+        let result = parse_commands("2q", 10, &targets, &Keymap::default()).unwrap();
         assert_eq!(result.get(&2), Some(&vec![Action::Unsubscribe]));
 
-        let result = parse_commands("4p", 10, &targets);
+        let result = parse_commands("4p", 10, &targets, &Keymap::default()).unwrap();
         assert_eq!(result.get(&4), Some(&vec![Action::Review]));
 
-        let result = parse_commands("4P", 10, &targets);
+        let result = parse_commands("4P", 10, &targets, &Keymap::default()).unwrap();
         assert_eq!(result.get(&4), Some(&vec![Action::ReviewNoAnalyze]));
 
-        let result = parse_commands("6b", 10, &targets);
+        let result = parse_commands("6b", 10, &targets, &Keymap::default()).unwrap();
         assert_eq!(result.get(&6), Some(&vec![Action::Branch]));
     }
 
     #[test]
     fn parses_multi_digit_indices() {
-        let targets = HashMap::new();
-        let result = parse_commands("11o", 20, &targets);
+        let targets = Targets::default();
+        let result = parse_commands("11o", 20, &targets, &Keymap::default()).unwrap();
         assert_eq!(result.get(&11), Some(&vec![Action::Open]));
 
-        let result = parse_commands("123d", 200, &targets);
+        let result = parse_commands("123d", 200, &targets, &Keymap::default()).unwrap();
         assert_eq!(result.get(&123), Some(&vec![Action::Done]));
     }
 
     #[test]
     fn splits_concatenated_indices_when_out_of_range() {
-        let targets = HashMap::new();
-        let result = parse_commands("23r", 10, &targets);
+        let targets = Targets::default();
+        let result = parse_commands("23r", 10, &targets, &Keymap::default()).unwrap();
         assert_eq!(result.get(&2), Some(&vec![Action::Read]));
         assert_eq!(result.get(&3), Some(&vec![Action::Read]));
         assert!(!result.contains_key(&23));
@@ -280,8 +686,8 @@ mod tests {
 
     #[test]
     fn keeps_multi_digit_index_when_in_range() {
-        let targets = HashMap::new();
-        let result = parse_commands("23r", 30, &targets);
+        let targets = Targets::default();
+        let result = parse_commands("23r", 30, &targets, &Keymap::default()).unwrap();
         assert_eq!(result.get(&23), Some(&vec![Action::Read]));
         assert!(!result.contains_key(&2));
         assert!(!result.contains_key(&3));
@@ -289,8 +695,8 @@ mod tests {
 
     #[test]
     fn splits_long_runs_greedily() {
-        let targets = HashMap::new();
-        let result = parse_commands("123456r", 50, &targets);
+        let targets = Targets::default();
+        let result = parse_commands("123456r", 50, &targets, &Keymap::default()).unwrap();
         assert_eq!(result.get(&12), Some(&vec![Action::Read]));
         assert_eq!(result.get(&34), Some(&vec![Action::Read]));
         assert_eq!(result.get(&5), Some(&vec![Action::Read]));
@@ -299,16 +705,16 @@ mod tests {
 
     #[test]
     fn splits_trailing_zero_when_out_of_range() {
-        let targets = HashMap::new();
-        let result = parse_commands("10r", 9, &targets);
+        let targets = Targets::default();
+        let result = parse_commands("10r", 9, &targets, &Keymap::default()).unwrap();
         assert_eq!(result.get(&1), Some(&vec![Action::Read]));
         assert!(!result.contains_key(&10));
     }
 
     #[test]
     fn splits_ranges_with_greedy_endpoints() {
-        let targets = HashMap::new();
-        let result = parse_commands("1-23r", 10, &targets);
+        let targets = Targets::default();
+        let result = parse_commands("1-23r", 10, &targets, &Keymap::default()).unwrap();
         assert_eq!(result.get(&1), Some(&vec![Action::Read]));
         assert_eq!(result.get(&2), Some(&vec![Action::Read]));
         assert_eq!(result.get(&3), Some(&vec![Action::Read]));
@@ -316,8 +722,8 @@ mod tests {
 
     #[test]
     fn parses_ranges() {
-        let targets = HashMap::new();
-        let result = parse_commands("1-3q", 10, &targets);
+        let targets = Targets::default();
+        let result = parse_commands("1-3q", 10, &targets, &Keymap::default()).unwrap();
         assert_eq!(result.get(&1), Some(&vec![Action::Unsubscribe]));
         assert_eq!(result.get(&2), Some(&vec![Action::Unsubscribe]));
         assert_eq!(result.get(&3), Some(&vec![Action::Unsubscribe]));
@@ -325,8 +731,8 @@ mod tests {
 
     #[test]
     fn parses_reverse_ranges() {
-        let targets = HashMap::new();
-        let result = parse_commands("3-1q", 10, &targets);
+        let targets = Targets::default();
+        let result = parse_commands("3-1q", 10, &targets, &Keymap::default()).unwrap();
         assert_eq!(result.get(&1), Some(&vec![Action::Unsubscribe]));
         assert_eq!(result.get(&2), Some(&vec![Action::Unsubscribe]));
         assert_eq!(result.get(&3), Some(&vec![Action::Unsubscribe]));
@@ -334,8 +740,8 @@ mod tests {
 
     #[test]
     fn parses_lists_with_separators_and_multiple_actions() {
-        let targets = HashMap::new();
-        let result = parse_commands("1, 2 3 q y", 10, &targets);
+        let targets = Targets::default();
+        let result = parse_commands("1, 2 3 q y", 10, &targets, &Keymap::default()).unwrap();
         let expected = vec![Action::Unsubscribe, Action::PrettyYank];
         assert_eq!(result.get(&1), Some(&expected));
         assert_eq!(result.get(&2), Some(&expected));
@@ -344,8 +750,8 @@ mod tests {
 
     #[test]
     fn parses_multiple_actions_for_same_index() {
-        let targets = HashMap::new();
-        let result = parse_commands("1o1r1y", 10, &targets);
+        let targets = Targets::default();
+        let result = parse_commands("1o1r1y", 10, &targets, &Keymap::default()).unwrap();
         assert_eq!(
             result.get(&1),
             Some(&vec![Action::Open, Action::Read, Action::PrettyYank])
@@ -354,24 +760,24 @@ mod tests {
 
     #[test]
     fn ignores_out_of_range_indices() {
-        let targets = HashMap::new();
-        let result = parse_commands("99o1r", 5, &targets);
+        let targets = Targets::default();
+        let result = parse_commands("99o1r", 5, &targets, &Keymap::default()).unwrap();
         assert_eq!(result.get(&1), Some(&vec![Action::Read]));
         assert!(!result.contains_key(&99));
     }
 
     #[test]
     fn resets_on_invalid_chars() {
-        let targets = HashMap::new();
-        let result = parse_commands("1o x 2r", 10, &targets);
+        let targets = Targets::default();
+        let result = parse_commands("1o x 2r", 10, &targets, &Keymap::default()).unwrap();
         assert_eq!(result.get(&1), Some(&vec![Action::Open]));
         assert_eq!(result.get(&2), Some(&vec![Action::Read]));
     }
 
     #[test]
     fn supports_repeated_actions_after_single_index() {
-        let targets = HashMap::new();
-        let result = parse_commands("11oooyd", 20, &targets);
+        let targets = Targets::default();
+        let result = parse_commands("11oooyd", 20, &targets, &Keymap::default()).unwrap();
         assert_eq!(
             result.get(&11),
             Some(&vec![
@@ -386,28 +792,277 @@ mod tests {
 
     #[test]
     fn parses_status_targets() {
-        let mut targets = HashMap::new();
-        targets.insert('m', vec![2, 4]);
-        let result = parse_commands("md", 10, &targets);
+        let mut targets = Targets::default();
+        targets.groups.insert('m', vec![2, 4]);
+        let result = parse_commands("md", 10, &targets, &Keymap::default()).unwrap();
         assert_eq!(result.get(&2), Some(&vec![Action::Done]));
         assert_eq!(result.get(&4), Some(&vec![Action::Done]));
     }
 
     #[test]
     fn parses_review_targets() {
-        let mut targets = HashMap::new();
-        targets.insert('?', vec![1, 3]);
-        let result = parse_commands("?o", 10, &targets);
+        let mut targets = Targets::default();
+        targets.groups.insert('?', vec![1, 3]);
+        let result = parse_commands("?o", 10, &targets, &Keymap::default()).unwrap();
         assert_eq!(result.get(&1), Some(&vec![Action::Open]));
         assert_eq!(result.get(&3), Some(&vec![Action::Open]));
     }
 
     #[test]
     fn parses_conflict_targets() {
-        let mut targets = HashMap::new();
-        targets.insert('!', vec![2, 4]);
-        let result = parse_commands("!o", 10, &targets);
+        let mut targets = Targets::default();
+        targets.groups.insert('!', vec![2, 4]);
+        let result = parse_commands("!o", 10, &targets, &Keymap::default()).unwrap();
         assert_eq!(result.get(&2), Some(&vec![Action::Open]));
         assert_eq!(result.get(&4), Some(&vec![Action::Open]));
     }
+
+    #[test]
+    fn parses_reason_targets() {
+        let mut targets = Targets::default();
+        targets.push_reason("ci_activity", 2);
+        targets.push_reason("mention", 3);
+        targets.push_reason("mention", 5);
+        let result = parse_commands(
+            "#ci_activity d #Mention o",
+            10,
+            &targets,
+            &Keymap::default(),
+        )
+        .unwrap();
+        assert_eq!(result.get(&2), Some(&vec![Action::Done]));
+        assert_eq!(result.get(&3), Some(&vec![Action::Open]));
+        assert_eq!(result.get(&5), Some(&vec![Action::Open]));
+
+        let result = parse_commands("1 #mention r", 10, &targets, &Keymap::default()).unwrap();
+        assert_eq!(result.len(), 3);
+        assert!(
+            parse_commands("#author d", 10, &targets, &Keymap::default())
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn parses_repo_and_author_targets() {
+        let mut targets = Targets::default();
+        targets.push_repo("acme/widgets", 1);
+        targets.push_repo("other/widgets", 2);
+        targets.push_repo("acme/infra", 3);
+        targets.push_author("renovate[bot]", 3);
+        targets.push_author("octocat", 4);
+
+        let result = parse_commands("@acme/widgets d", 10, &targets, &Keymap::default()).unwrap();
+        assert_eq!(result.keys().collect::<Vec<_>>(), vec![&1]);
+
+        let result = parse_commands("@Widgets r", 10, &targets, &Keymap::default()).unwrap();
+        assert_eq!(result.len(), 2);
+        assert_eq!(result.get(&2), Some(&vec![Action::Read]));
+
+        let result = parse_commands(
+            "~renovate[bot] d ~octocat o",
+            10,
+            &targets,
+            &Keymap::default(),
+        )
+        .unwrap();
+        assert_eq!(result.get(&3), Some(&vec![Action::Done]));
+        assert_eq!(result.get(&4), Some(&vec![Action::Open]));
+
+        let result =
+            parse_commands("@acme/infra&~octocat d", 10, &targets, &Keymap::default()).unwrap();
+        assert!(result.is_empty());
+        let result = parse_commands(
+            "@acme/infra&~renovate[bot] d",
+            10,
+            &targets,
+            &Keymap::default(),
+        )
+        .unwrap();
+        assert_eq!(result.get(&3), Some(&vec![Action::Done]));
+
+        assert!(parse_commands("@acme d", 10, &targets, &Keymap::default())
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn composes_targets_with_intersection_and_negation() {
+        let mut targets = Targets {
+            all: (1..=6).collect(),
+            ..Targets::default()
+        };
+        targets.groups.insert('m', vec![1, 2, 3]);
+        targets.groups.insert('u', vec![2, 3, 4]);
+        targets.groups.insert('a', vec![3, 5]);
+        let keymap = Keymap::default();
+
+        let sorted = |input: &str| {
+            let mut keys: Vec<usize> = parse_commands(input, 6, &targets, &keymap)
+                .unwrap()
+                .into_keys()
+                .collect();
+            keys.sort_unstable();
+            keys
+        };
+
+        assert_eq!(sorted("m&ud"), vec![2, 3]);
+        assert_eq!(sorted("^ad"), vec![1, 2, 4, 6]);
+        assert_eq!(sorted("m&^ad"), vec![1, 2]);
+        assert_eq!(sorted("*r"), vec![1, 2, 3, 4, 5, 6]);
+        // `&` binds tighter than the implicit union.
+        assert_eq!(sorted("a m&u d"), vec![2, 3, 5]);
+        assert_eq!(sorted("1-4&ao"), vec![3]);
+        assert_eq!(sorted("^^ad"), vec![3, 5]);
+    }
+
+    #[test]
+    fn reports_malformed_commands() {
+        let targets = Targets::default();
+        let keymap = Keymap::default();
+        for (input, message) in [
+            ("1z", "unknown key 'z'"),
+            ("m&", "'&' needs a target after it"),
+            ("&u d", "'&' needs a target before it"),
+            ("md&u", "'&' needs a target before it"),
+            ("^", "'^' needs a target after it"),
+            ("# d", "'#' needs a name after it"),
+        ] {
+            let err = parse_commands(input, 10, &targets, &keymap).unwrap_err();
+            assert_eq!(err.to_string(), message, "{input}");
+        }
+    }
+
+    #[test]
+    fn parses_section_targets() {
+        let targets = Targets {
+            sections: vec![
+                ("ready_to_merge", vec![1, 2, 3]),
+                ("needs_action", vec![4, 5]),
+                ("needs_review", vec![6]),
+                ("pinned", Vec::new()),
+            ],
+            ..Targets::default()
+        };
+        let keymap = Keymap::default();
+        let sorted = |input: &str| {
+            let mut keys: Vec<usize> = parse_commands(input, 6, &targets, &keymap)
+                .unwrap()
+                .into_keys()
+                .collect();
+            keys.sort_unstable();
+            keys
+        };
+
+        assert_eq!(sorted("%ready d"), vec![1, 2, 3]);
+        assert_eq!(sorted("%ready2 d"), vec![2]);
+        assert_eq!(sorted("%m2-3 o"), vec![2, 3]);
+        assert_eq!(sorted("%action o %review1 r"), vec![4, 5, 6]);
+        assert_eq!(sorted("%needs_action9 o"), Vec::<usize>::new());
+        assert_eq!(sorted("%pin d"), Vec::<usize>::new());
+
+        for (input, message) in [
+            (
+                "%needs d",
+                "section 'needs' is ambiguous: needs_action, needs_review",
+            ),
+            ("%bogus d", "unknown section 'bogus'"),
+            ("%2 d", "'%' needs a section name"),
+            ("%ready2x d", "invalid section position '2x'"),
+        ] {
+            let err = parse_commands(input, 6, &targets, &keymap).unwrap_err();
+            assert_eq!(err.to_string(), message, "{input}");
+        }
+    }
+
+    #[test]
+    fn parses_age_targets() {
+        let hour = 60 * 60;
+        let targets = Targets {
+            ages: vec![
+                (1, 30),
+                (2, 2 * hour),
+                (3, 8 * 24 * hour),
+                (4, 40 * 24 * hour),
+            ],
+            groups: HashMap::from([('u', vec![1, 4])]),
+            ..Targets::default()
+        };
+        let keymap = Keymap::default();
+        let sorted = |input: &str| {
+            let mut keys: Vec<usize> = parse_commands(input, 4, &targets, &keymap)
+                .unwrap()
+                .into_keys()
+                .collect();
+            keys.sort_unstable();
+            keys
+        };
+
+        assert_eq!(sorted(">7d d"), vec![3, 4]);
+        assert_eq!(sorted(">30d d"), vec![4]);
+        assert_eq!(sorted("<1h r"), vec![1]);
+        assert_eq!(sorted(">1w&u d"), vec![4]);
+        assert_eq!(sorted("<90m >4w o"), vec![1, 4]);
+
+        for input in [">7 d", ">d d", ">7y d", "<"] {
+            assert!(
+                parse_commands(input, 4, &targets, &keymap).is_err(),
+                "{input}"
+            );
+        }
+    }
+
+    #[test]
+    fn target_syntax_examples_parse() {
+        let targets = Targets {
+            all: vec![1, 2],
+            sections: vec![("ready_to_merge", vec![1, 2])],
+            ..Targets::default()
+        };
+        let keymap = Keymap::default();
+        for (examples, _) in TARGET_SYNTAX {
+            for example in examples.split(", ") {
+                let input = format!("{} o", example);
+                assert!(
+                    parse_commands(&input, 4, &targets, &keymap).is_ok(),
+                    "{input}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn parses_targets_without_actions() {
+        let targets = Targets {
+            all: vec![1, 2, 3, 4],
+            groups: HashMap::from([('u', vec![1, 4])]),
+            ..Targets::default()
+        };
+        let keymap = Keymap::default();
+        let parse = |input: &str| parse_targets(input, 4, &targets, &keymap);
+
+        assert_eq!(parse("3").unwrap(), vec![3]);
+        assert_eq!(parse("1-2 u").unwrap(), vec![1, 2, 4]);
+        assert_eq!(parse("^u").unwrap(), vec![2, 3]);
+        assert_eq!(
+            parse("3o").unwrap_err().to_string(),
+            "expected targets, found the open action"
+        );
+        assert!(parse("u&").is_err());
+    }
+
+    #[test]
+    fn expands_macros_as_whole_words() {
+        let macros =
+            BTreeMap::from([("triage".to_string(), "md cd fd #ci_activity r".to_string())]);
+        assert_eq!(
+            expand_macros("1o :triage", &macros).unwrap(),
+            "1o md cd fd #ci_activity r"
+        );
+        assert_eq!(expand_macros("1o,  2r", &macros).unwrap(), "1o,  2r");
+        assert_eq!(
+            expand_macros(":nope", &macros).unwrap_err().to_string(),
+            "unknown macro ':nope'"
+        );
+    }
 }
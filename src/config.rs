@@ -0,0 +1,834 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    fs,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+use anyhow::{anyhow, Context, Result};
+use ratatui::style::Color;
+use serde::Deserialize;
+
+use crate::{
+    commands::MACRO_SIGIL,
+    custom_actions::{is_destructive, validate_placeholders, validate_template, CustomAction},
+    desktop::{AlertRule, DesktopSettings, QuietHours, DESKTOP_PLACEHOLDERS},
+    hooks::{HookEventKind, HookSettings},
+    keymap::Keymap,
+    store::config_home,
+    types::Action,
+    ui::Theme,
+};
+
+const CONFIG_RELATIVE_PATH: &str = "ghn/config.toml";
+const DEFAULT_INTERVAL_SECS: u64 = 60;
+const DEFAULT_REPO_ROOT: &str = "~/Developer";
+const DEFAULT_EDITOR: &str = "nvim";
+const DEFAULT_REVIEW_COMMAND: &str = "ReviewPR";
+const DEFAULT_ANALYZE_FLAG: &str = "--analyze";
+const DEFAULT_WORKTREE_DIR: &str = ".worktrees";
+const DEFAULT_CONFIRM_ABOVE: usize = 10;
+
+/// Placeholders accepted by `review.launch`.
+pub const REVIEW_PLACEHOLDERS: [&str; 7] =
+    ["url", "repo", "owner", "name", "number", "dir", "analyze"];
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    pub interval: Option<u64>,
+    pub unread_only: Option<bool>,
+    pub review: ReviewConfig,
+    pub worktree: WorktreeConfig,
+    pub daemon: DaemonConfig,
+    pub desktop: DesktopConfig,
+    pub hooks: HooksConfig,
+    pub confirm: ConfirmConfig,
+    pub colors: ColorsConfig,
+    pub keys: KeysConfig,
+    pub macros: BTreeMap<String, String>,
+    pub custom_actions: Vec<CustomActionConfig>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ReviewConfig {
+    pub repo_root: Option<String>,
+    pub editor: Option<String>,
+    pub command: Option<String>,
+    pub launch: Option<String>,
+    pub analyze_flag: Option<String>,
+    pub workdir: Option<ReviewWorkdir>,
+    pub search_roots: Vec<String>,
+    pub clone: Option<CloneMode>,
+    pub clone_root: Option<String>,
+}
+
+/// Where the review command runs.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReviewWorkdir {
+    /// The local clone, found under `repo_root` or `search_roots`.
+    #[default]
+    Repo,
+    /// The directory `ghn` was started from.
+    Current,
+}
+
+/// What to do when no local clone is found.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CloneMode {
+    #[default]
+    Ask,
+    Always,
+    Never,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct WorktreeConfig {
+    pub dir: Option<String>,
+    pub open: Option<WorktreeOpen>,
+}
+
+/// What to launch inside a freshly prepared worktree.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WorktreeOpen {
+    #[default]
+    None,
+    /// `review.editor`, started in the worktree.
+    Editor,
+    /// `$SHELL`, falling back to `sh`.
+    Shell,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct DaemonConfig {
+    pub socket: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct DesktopConfig {
+    pub enabled: Option<bool>,
+    pub command: Option<String>,
+    pub rules: Option<Vec<AlertRule>>,
+    pub quiet_hours: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct HooksConfig {
+    pub command: Option<String>,
+    pub url: Option<String>,
+    pub events: Option<Vec<HookEventKind>>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ConfirmConfig {
+    pub above: Option<usize>,
+    pub destructive: Option<bool>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ColorsConfig {
+    pub actions: BTreeMap<String, String>,
+    pub buckets: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct KeysConfig {
+    pub actions: BTreeMap<String, String>,
+    pub targets: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CustomActionConfig {
+    pub name: String,
+    pub key: String,
+    pub command: String,
+    #[serde(default)]
+    pub foreground: bool,
+    #[serde(default)]
+    pub destructive: bool,
+}
+
+/// Fully resolved settings after merging the config file with CLI flags.
+#[derive(Debug, Clone)]
+pub struct Settings {
+    pub interval: u64,
+    pub unread_only: bool,
+    pub review: ReviewSettings,
+    pub worktree: WorktreeSettings,
+    /// Control socket of `ghn daemon`; `None` uses the default location.
+    pub daemon_socket: Option<PathBuf>,
+    pub desktop: DesktopSettings,
+    pub hooks: HookSettings,
+    pub confirm: ConfirmSettings,
+    pub theme: Theme,
+    pub keymap: Keymap,
+    /// Command lines run by `:name`, keyed by name.
+    pub macros: BTreeMap<String, String>,
+    pub custom_actions: Vec<CustomAction>,
+}
+
+#[derive(Debug, Clone)]
+pub struct ReviewSettings {
+    /// `repo_root` followed by any extra `search_roots`.
+    pub search_roots: Vec<PathBuf>,
+    pub clone: CloneMode,
+    pub clone_root: PathBuf,
+    pub editor: String,
+    pub command: String,
+    /// Shell template replacing the `<editor> -c "<command> ..."` preset.
+    pub launch: Option<String>,
+    pub analyze_flag: String,
+    pub workdir: ReviewWorkdir,
+}
+
+impl ReviewSettings {
+    /// Short name used in status messages, e.g. "ReviewPR".
+    pub fn name(&self) -> &str {
+        if self.launch.is_some() {
+            "Review"
+        } else {
+            &self.command
+        }
+    }
+
+    pub fn description(&self) -> String {
+        match &self.launch {
+            Some(_) => "review command".to_string(),
+            None => format!("{} in {}", self.command, self.editor),
+        }
+    }
+}
+
+/// When a batch is previewed before it runs.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfirmSettings {
+    /// Preview batches that touch more than this many items.
+    pub above: usize,
+    /// Preview any batch containing a destructive action.
+    pub destructive: bool,
+}
+
+impl Default for ConfirmSettings {
+    fn default() -> Self {
+        Self {
+            above: DEFAULT_CONFIRM_ABOVE,
+            destructive: true,
+        }
+    }
+}
+
+impl ConfirmSettings {
+    pub fn requires_confirmation(
+        &self,
+        pending: &HashMap<usize, Vec<Action>>,
+        custom_actions: &[CustomAction],
+    ) -> bool {
+        pending.len() > self.above
+            || (self.destructive
+                && pending
+                    .values()
+                    .flatten()
+                    .any(|action| is_destructive(*action, custom_actions)))
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct WorktreeSettings {
+    /// Directory under the local clone that holds one worktree per PR.
+    pub dir: String,
+    pub open: WorktreeOpen,
+}
+
+/// Flags given on the command line; `None` means "not passed".
+#[derive(Debug, Default)]
+pub struct CliOverrides {
+    pub interval: Option<u64>,
+    pub unread_only: Option<bool>,
+}
+
+pub fn config_path() -> Result<PathBuf> {
+    Ok(config_home()?.join(CONFIG_RELATIVE_PATH))
+}
+
+pub fn load_settings(cli: &CliOverrides) -> Result<Settings> {
+    let path = config_path()?;
+    let config = read_config(&path)?;
+    let home = home_dir()?;
+    resolve_settings(config, cli, &home)
+        .with_context(|| format!("invalid config: {}", path.display()))
+}
+
+fn read_config(path: &Path) -> Result<Config> {
+    let contents = match fs::read_to_string(path) {
+        Ok(contents) => contents,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => {
+            return Ok(Config::default());
+        }
+        Err(err) => {
+            return Err(err).with_context(|| format!("failed to read config: {}", path.display()));
+        }
+    };
+
+    toml::from_str(&contents).with_context(|| format!("failed to parse config: {}", path.display()))
+}
+
+fn resolve_settings(config: Config, cli: &CliOverrides, home: &Path) -> Result<Settings> {
+    let interval = cli
+        .interval
+        .or(config.interval)
+        .unwrap_or(DEFAULT_INTERVAL_SECS);
+    if interval == 0 {
+        return Err(anyhow!("interval must be at least 1 second"));
+    }
+
+    let repo_root = config
+        .review
+        .repo_root
+        .as_deref()
+        .unwrap_or(DEFAULT_REPO_ROOT);
+    let repo_root = expand_home(non_empty(repo_root, "review.repo_root")?, home);
+    let mut search_roots = vec![repo_root.clone()];
+    for root in &config.review.search_roots {
+        let root = expand_home(non_empty(root, "review.search_roots")?, home);
+        if !search_roots.contains(&root) {
+            search_roots.push(root);
+        }
+    }
+    let clone_root = match config.review.clone_root.as_deref() {
+        Some(root) => expand_home(non_empty(root, "review.clone_root")?, home),
+        None => repo_root.clone(),
+    };
+    let review = ReviewSettings {
+        search_roots,
+        clone: config.review.clone.unwrap_or_default(),
+        clone_root,
+        editor: non_empty(
+            config.review.editor.as_deref().unwrap_or(DEFAULT_EDITOR),
+            "review.editor",
+        )?
+        .to_string(),
+        command: non_empty(
+            config
+                .review
+                .command
+                .as_deref()
+                .unwrap_or(DEFAULT_REVIEW_COMMAND),
+            "review.command",
+        )?
+        .to_string(),
+        launch: config
+            .review
+            .launch
+            .as_deref()
+            .map(|launch| {
+                let launch = non_empty(launch, "review.launch")?;
+                validate_placeholders(launch, &REVIEW_PLACEHOLDERS).context("review.launch")?;
+                Ok::<_, anyhow::Error>(launch.to_string())
+            })
+            .transpose()?,
+        analyze_flag: config
+            .review
+            .analyze_flag
+            .as_deref()
+            .unwrap_or(DEFAULT_ANALYZE_FLAG)
+            .trim()
+            .to_string(),
+        workdir: config.review.workdir.unwrap_or_default(),
+    };
+
+    let worktree = WorktreeSettings {
+        dir: non_empty(
+            config
+                .worktree
+                .dir
+                .as_deref()
+                .unwrap_or(DEFAULT_WORKTREE_DIR),
+            "worktree.dir",
+        )?
+        .to_string(),
+        open: config.worktree.open.unwrap_or_default(),
+    };
+
+    let daemon_socket = config
+        .daemon
+        .socket
+        .as_deref()
+        .map(|socket| {
+            Ok::<_, anyhow::Error>(expand_home(non_empty(socket, "daemon.socket")?, home))
+        })
+        .transpose()?;
+
+    let desktop = DesktopSettings {
+        enabled: config.desktop.enabled.unwrap_or(false),
+        command: config
+            .desktop
+            .command
+            .as_deref()
+            .map(|command| {
+                let command = non_empty(command, "desktop.command")?;
+                validate_placeholders(command, &DESKTOP_PLACEHOLDERS).context("desktop.command")?;
+                Ok::<_, anyhow::Error>(command.to_string())
+            })
+            .transpose()?,
+        rules: config
+            .desktop
+            .rules
+            .unwrap_or_else(|| AlertRule::DEFAULT.to_vec()),
+        quiet_hours: config
+            .desktop
+            .quiet_hours
+            .as_deref()
+            .map(|value| QuietHours::parse(value).context("desktop.quiet_hours"))
+            .transpose()?,
+    };
+
+    let hooks = HookSettings {
+        command: config
+            .hooks
+            .command
+            .as_deref()
+            .map(|command| non_empty(command, "hooks.command").map(str::to_string))
+            .transpose()?,
+        url: config
+            .hooks
+            .url
+            .as_deref()
+            .map(|url| {
+                let url = non_empty(url, "hooks.url")?;
+                if !url.starts_with("http://") && !url.starts_with("https://") {
+                    return Err(anyhow!("hooks.url must be an http(s) URL"));
+                }
+                Ok(url.to_string())
+            })
+            .transpose()?,
+        events: config
+            .hooks
+            .events
+            .unwrap_or_else(|| HookEventKind::ALL.to_vec()),
+    };
+
+    let confirm = ConfirmSettings {
+        above: config.confirm.above.unwrap_or(DEFAULT_CONFIRM_ABOVE),
+        destructive: config.confirm.destructive.unwrap_or(true),
+    };
+
+    let mut theme = Theme::default();
+    for (name, value) in &config.colors.actions {
+        parse_color(value)
+            .and_then(|color| theme.set_action_color(name, color))
+            .with_context(|| format!("colors.actions.{}", name))?;
+    }
+    for (name, value) in &config.colors.buckets {
+        parse_color(value)
+            .and_then(|color| theme.set_bucket_color(name, color))
+            .with_context(|| format!("colors.buckets.{}", name))?;
+    }
+
+    let mut custom_actions = Vec::with_capacity(config.custom_actions.len());
+    let mut custom_keys = Vec::with_capacity(config.custom_actions.len());
+    for (idx, action) in config.custom_actions.into_iter().enumerate() {
+        let context = || format!("custom_actions[{}]", idx);
+        let name = non_empty(&action.name, "name")
+            .with_context(context)?
+            .to_string();
+        let command = non_empty(&action.command, "command").with_context(context)?;
+        validate_template(command).with_context(|| format!("custom action '{}'", name))?;
+        custom_keys.push((name.clone(), action.key));
+        custom_actions.push(CustomAction {
+            name,
+            command: command.to_string(),
+            foreground: action.foreground,
+            destructive: action.destructive,
+        });
+    }
+
+    let keymap = Keymap::from_overrides(&config.keys.actions, &config.keys.targets, &custom_keys)?;
+
+    let mut macros = BTreeMap::new();
+    for (name, command) in config.macros {
+        if name.is_empty()
+            || !name
+                .chars()
+                .all(|ch| ch.is_ascii_alphanumeric() || ch == '-' || ch == '_')
+        {
+            return Err(anyhow!(
+                "macros.{} must use only letters, digits, '-' and '_'",
+                name
+            ));
+        }
+        let command = non_empty(&command, &format!("macros.{}", name))?;
+        if command
+            .split_whitespace()
+            .any(|word| word.starts_with(MACRO_SIGIL))
+        {
+            return Err(anyhow!("macros.{} cannot call another macro", name));
+        }
+        macros.insert(name, command.to_string());
+    }
+
+    Ok(Settings {
+        interval,
+        unread_only: cli.unread_only.or(config.unread_only).unwrap_or(false),
+        review,
+        worktree,
+        daemon_socket,
+        desktop,
+        hooks,
+        confirm,
+        theme,
+        keymap,
+        macros,
+        custom_actions,
+    })
+}
+
+fn non_empty<'a>(value: &'a str, key: &str) -> Result<&'a str> {
+    let trimmed = value.trim();
+    if trimmed.is_empty() {
+        return Err(anyhow!("{} must not be empty", key));
+    }
+    Ok(trimmed)
+}
+
+fn parse_color(value: &str) -> Result<Color> {
+    Color::from_str(value.trim()).map_err(|_| anyhow!("unknown color '{}'", value))
+}
+
+pub fn expand_home(value: &str, home: &Path) -> PathBuf {
+    if value == "~" {
+        return home.to_path_buf();
+    }
+    match value.strip_prefix("~/") {
+        Some(rest) => home.join(rest),
+        None => PathBuf::from(value),
+    }
+}
+
+pub fn home_dir() -> Result<PathBuf> {
+    if let Ok(value) = std::env::var("HOME") {
+        let trimmed = value.trim();
+        if !trimmed.is_empty() {
+            return Ok(PathBuf::from(trimmed));
+        }
+    }
+
+    if let Ok(value) = std::env::var("USERPROFILE") {
+        let trimmed = value.trim();
+        if !trimmed.is_empty() {
+            return Ok(PathBuf::from(trimmed));
+        }
+    }
+
+    Err(anyhow!("HOME/USERPROFILE is not set"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        expand_home, resolve_settings, CliOverrides, CloneMode, Config, ConfirmSettings,
+        ReviewWorkdir, WorktreeOpen,
+    };
+    use crate::custom_actions::CustomAction;
+    use crate::desktop::AlertRule;
+    use crate::hooks::HookEventKind;
+    use crate::types::Action;
+    use std::collections::HashMap;
+    use std::path::{Path, PathBuf};
+
+    fn parse(contents: &str) -> Config {
+        toml::from_str(contents).unwrap()
+    }
+
+    #[test]
+    fn defaults_match_previous_behavior() {
+        let settings = resolve_settings(
+            Config::default(),
+            &CliOverrides::default(),
+            Path::new("/home/me"),
+        )
+        .unwrap();
+        assert_eq!(settings.interval, 60);
+        assert!(!settings.unread_only);
+        assert_eq!(settings.review.editor, "nvim");
+        assert_eq!(settings.review.command, "ReviewPR");
+        assert_eq!(settings.review.launch, None);
+        assert_eq!(settings.review.analyze_flag, "--analyze");
+        assert_eq!(settings.review.workdir, ReviewWorkdir::Repo);
+        assert_eq!(
+            settings.review.search_roots,
+            vec![PathBuf::from("/home/me/Developer")]
+        );
+        assert_eq!(settings.review.clone, CloneMode::Ask);
+        assert_eq!(
+            settings.review.clone_root,
+            PathBuf::from("/home/me/Developer")
+        );
+        assert_eq!(settings.review.description(), "ReviewPR in nvim");
+        assert_eq!(settings.worktree.dir, ".worktrees");
+        assert_eq!(settings.worktree.open, WorktreeOpen::None);
+    }
+
+    #[test]
+    fn cli_flags_override_config() {
+        let config = parse(
+            "interval = 120\nunread_only = true\n[review]\nrepo_root = \"~/src\"\neditor = \"vim\"\n",
+        );
+        let cli = CliOverrides {
+            interval: Some(15),
+            unread_only: None,
+        };
+        let settings = resolve_settings(config, &cli, Path::new("/home/me")).unwrap();
+        assert_eq!(settings.interval, 15);
+        assert!(settings.unread_only);
+        assert_eq!(
+            settings.review.search_roots,
+            vec![PathBuf::from("/home/me/src")]
+        );
+        assert_eq!(settings.review.editor, "vim");
+    }
+
+    #[test]
+    fn rejects_invalid_values() {
+        assert!(toml::from_str::<Config>("poll = 5\n").is_err());
+
+        let home = Path::new("/home/me");
+        let cli = CliOverrides::default();
+        assert!(resolve_settings(parse("interval = 0\n"), &cli, home).is_err());
+        assert!(resolve_settings(parse("[review]\neditor = \" \"\n"), &cli, home).is_err());
+
+        let err = resolve_settings(parse("[colors.actions]\nopen = \"plaid\"\n"), &cli, home)
+            .unwrap_err();
+        assert!(format!("{:#}", err).contains("plaid"));
+        let err =
+            resolve_settings(parse("[colors.buckets]\nsoon = \"red\"\n"), &cli, home).unwrap_err();
+        assert!(format!("{:#}", err).contains("colors.buckets.soon"));
+    }
+
+    #[test]
+    fn keys_section_builds_keymap() {
+        let config = parse("[keys.actions]\nunsubscribe = \"Q\"\n");
+        let settings =
+            resolve_settings(config, &CliOverrides::default(), Path::new("/home/me")).unwrap();
+        assert_eq!(settings.keymap.action('Q'), Some(Action::Unsubscribe));
+
+        let config = parse("[keys.targets]\nmerged = \"o\"\n");
+        let err =
+            resolve_settings(config, &CliOverrides::default(), Path::new("/home/me")).unwrap_err();
+        assert!(format!("{:#}", err).contains("key 'o'"));
+    }
+
+    #[test]
+    fn custom_actions_are_validated() {
+        let home = Path::new("/home/me");
+        let cli = CliOverrides::default();
+        let config = parse(
+            "[[custom_actions]]\nname = \"checkout\"\nkey = \"g\"\ncommand = \"gh pr checkout {number}\"\nforeground = true\n",
+        );
+        let settings = resolve_settings(config, &cli, home).unwrap();
+        assert_eq!(settings.custom_actions.len(), 1);
+        assert!(settings.custom_actions[0].foreground);
+        assert_eq!(settings.keymap.action('g'), Some(Action::Custom(0)));
+
+        let config =
+            parse("[[custom_actions]]\nname = \"x\"\nkey = \"g\"\ncommand = \"echo {path}\"\n");
+        let err = resolve_settings(config, &cli, home).unwrap_err();
+        assert!(format!("{:#}", err).contains("{path}"));
+    }
+
+    #[test]
+    fn review_launch_is_validated() {
+        let home = Path::new("/home/me");
+        let cli = CliOverrides::default();
+        let config =
+            parse("[review]\nlaunch = \"gh pr diff {url} | delta\"\nworkdir = \"current\"\n");
+        let settings = resolve_settings(config, &cli, home).unwrap();
+        assert_eq!(
+            settings.review.launch.as_deref(),
+            Some("gh pr diff {url} | delta")
+        );
+        assert_eq!(settings.review.workdir, ReviewWorkdir::Current);
+        assert_eq!(settings.review.name(), "Review");
+
+        let err = resolve_settings(parse("[review]\nlaunch = \"hx {branch}\"\n"), &cli, home)
+            .unwrap_err();
+        assert!(format!("{:#}", err).contains("review.launch"));
+        assert!(toml::from_str::<Config>("[review]\nworkdir = \"home\"\n").is_err());
+    }
+
+    #[test]
+    fn review_search_roots_start_with_repo_root() {
+        let config = parse(
+            "[review]\nrepo_root = \"~/src\"\nsearch_roots = [\"~/ghq\", \"~/src\"]\nclone = \"never\"\nclone_root = \"~/ghq/github.com\"\n",
+        );
+        let settings =
+            resolve_settings(config, &CliOverrides::default(), Path::new("/home/me")).unwrap();
+        assert_eq!(
+            settings.review.search_roots,
+            vec![PathBuf::from("/home/me/src"), PathBuf::from("/home/me/ghq")]
+        );
+        assert_eq!(settings.review.clone, CloneMode::Never);
+        assert_eq!(
+            settings.review.clone_root,
+            PathBuf::from("/home/me/ghq/github.com")
+        );
+    }
+
+    #[test]
+    fn worktree_section_is_parsed() {
+        let config = parse("[worktree]\ndir = \"../wt\"\nopen = \"shell\"\n");
+        let settings =
+            resolve_settings(config, &CliOverrides::default(), Path::new("/home/me")).unwrap();
+        assert_eq!(settings.worktree.dir, "../wt");
+        assert_eq!(settings.worktree.open, WorktreeOpen::Shell);
+        assert!(toml::from_str::<Config>("[worktree]\nopen = \"ide\"\n").is_err());
+    }
+
+    #[test]
+    fn desktop_section_is_validated() {
+        let home = Path::new("/home/me");
+        let cli = CliOverrides::default();
+        let settings = resolve_settings(Config::default(), &cli, home).unwrap();
+        assert!(!settings.desktop.enabled);
+        assert_eq!(settings.desktop.rules, AlertRule::DEFAULT.to_vec());
+
+        let config = parse(
+            "[desktop]\nenabled = true\nrules = [\"assign\"]\nquiet_hours = \"22:00-07:30\"\ncommand = \"terminal-notifier -title {title} -open {url}\"\n",
+        );
+        let settings = resolve_settings(config, &cli, home).unwrap();
+        assert!(settings.desktop.enabled);
+        assert_eq!(settings.desktop.rules, vec![AlertRule::Assign]);
+        assert!(settings.desktop.quiet_hours.is_some());
+
+        let err =
+            resolve_settings(parse("[desktop]\nquiet_hours = \"late\"\n"), &cli, home).unwrap_err();
+        assert!(format!("{:#}", err).contains("desktop.quiet_hours"));
+        let err = resolve_settings(parse("[desktop]\ncommand = \"say {branch}\"\n"), &cli, home)
+            .unwrap_err();
+        assert!(format!("{:#}", err).contains("desktop.command"));
+        assert!(toml::from_str::<Config>("[desktop]\nrules = [\"push\"]\n").is_err());
+    }
+
+    #[test]
+    fn hooks_section_is_validated() {
+        let home = Path::new("/home/me");
+        let cli = CliOverrides::default();
+        let settings = resolve_settings(Config::default(), &cli, home).unwrap();
+        assert!(!settings.hooks.is_enabled());
+
+        let config = parse(
+            "[hooks]\ncommand = \"cat >> ~/events.jsonl\"\nevents = [\"merged\", \"ci_changed\"]\n",
+        );
+        let settings = resolve_settings(config, &cli, home).unwrap();
+        assert!(settings.hooks.is_enabled());
+        assert_eq!(
+            settings.hooks.events,
+            vec![HookEventKind::Merged, HookEventKind::CiChanged]
+        );
+
+        let err =
+            resolve_settings(parse("[hooks]\nurl = \"example.com\"\n"), &cli, home).unwrap_err();
+        assert!(format!("{:#}", err).contains("hooks.url"));
+        assert!(toml::from_str::<Config>("[hooks]\nevents = [\"opened\"]\n").is_err());
+    }
+
+    #[test]
+    fn confirm_section_sets_preview_rules() {
+        let home = Path::new("/home/me");
+        let cli = CliOverrides::default();
+        let settings = resolve_settings(Config::default(), &cli, home).unwrap();
+        assert_eq!(settings.confirm, ConfirmSettings::default());
+
+        let done = HashMap::from([(1, vec![Action::Done])]);
+        let unsubscribe = HashMap::from([(1, vec![Action::Unsubscribe])]);
+        let opens: HashMap<usize, Vec<Action>> =
+            (1..=11).map(|index| (index, vec![Action::Open])).collect();
+        // Done is undoable, so a single `3d` runs straight away.
+        assert!(!settings.confirm.requires_confirmation(&done, &[]));
+        assert!(settings.confirm.requires_confirmation(&unsubscribe, &[]));
+        assert!(settings.confirm.requires_confirmation(&opens, &[]));
+        assert!(!settings
+            .confirm
+            .requires_confirmation(&HashMap::from([(1, vec![Action::Read])]), &[]));
+
+        let custom = |destructive| CustomAction {
+            name: "close".to_string(),
+            command: "gh pr close {url}".to_string(),
+            foreground: false,
+            destructive,
+        };
+        let close = HashMap::from([(1, vec![Action::Custom(0)])]);
+        assert!(settings
+            .confirm
+            .requires_confirmation(&close, &[custom(true)]));
+        assert!(!settings
+            .confirm
+            .requires_confirmation(&close, &[custom(false)]));
+
+        let config = parse(
+            "[confirm]
+above = 20
+destructive = false
+",
+        );
+        let settings = resolve_settings(config, &cli, home).unwrap();
+        assert!(!settings.confirm.requires_confirmation(&unsubscribe, &[]));
+        assert!(!settings.confirm.requires_confirmation(&opens, &[]));
+    }
+
+    #[test]
+    fn macros_are_validated() {
+        let home = Path::new("/home/me");
+        let cli = CliOverrides::default();
+        let settings = resolve_settings(
+            parse("[macros]\ntriage = \" md cd fd #ci_activity r \"\n"),
+            &cli,
+            home,
+        )
+        .unwrap();
+        assert_eq!(settings.macros["triage"], "md cd fd #ci_activity r");
+
+        for contents in [
+            "[macros]\n\"tri age\" = \"md\"\n",
+            "[macros]\ntriage = \" \"\n",
+            "[macros]\ntriage = \"md :other\"\n",
+        ] {
+            let err = resolve_settings(parse(contents), &cli, home).unwrap_err();
+            assert!(err.to_string().contains("macros."), "{contents}");
+        }
+    }
+
+    #[test]
+    fn daemon_socket_expands_home() {
+        let home = Path::new("/home/me");
+        let cli = CliOverrides::default();
+        let settings = resolve_settings(Config::default(), &cli, home).unwrap();
+        assert_eq!(settings.daemon_socket, None);
+
+        let config = parse("[daemon]\nsocket = \"~/.ghn.sock\"\n");
+        let settings = resolve_settings(config, &cli, home).unwrap();
+        assert_eq!(
+            settings.daemon_socket,
+            Some(PathBuf::from("/home/me/.ghn.sock"))
+        );
+    }
+
+    #[test]
+    fn expand_home_handles_tilde() {
+        let home = Path::new("/home/me");
+        assert_eq!(expand_home("~", home), PathBuf::from("/home/me"));
+        assert_eq!(expand_home("~/code", home), PathBuf::from("/home/me/code"));
+        assert_eq!(expand_home("/srv/code", home), PathBuf::from("/srv/code"));
+    }
+}
@@ -0,0 +1,201 @@
+use std::process::Command;
+
+use anyhow::{anyhow, Context, Result};
+
+use crate::types::Action;
+
+const PLACEHOLDERS: [&str; 7] = [
+    "url",
+    "repo",
+    "owner",
+    "number",
+    "branch",
+    "title",
+    "thread_id",
+];
+
+/// A user-defined action from the `[[custom_actions]]` config section.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CustomAction {
+    pub name: String,
+    pub command: String,
+    pub foreground: bool,
+    /// Previewed before running, like unsubscribe.
+    pub destructive: bool,
+}
+
+/// Whether `[confirm] destructive` previews batches containing `action`.
+pub fn is_destructive(action: Action, custom_actions: &[CustomAction]) -> bool {
+    match action {
+        Action::Custom(idx) => custom_actions
+            .get(idx)
+            .is_some_and(|custom| custom.destructive),
+        _ => action.is_destructive(),
+    }
+}
+
+/// Values substituted into a command template for one target.
+#[derive(Debug, Clone, Default)]
+pub struct TemplateValues {
+    pub url: String,
+    pub repo: String,
+    pub number: Option<String>,
+    pub branch: Option<String>,
+    pub title: String,
+    pub thread_id: Option<String>,
+}
+
+impl TemplateValues {
+    fn get(&self, name: &str) -> Option<&str> {
+        match name {
+            "url" => Some(&self.url),
+            "repo" => Some(&self.repo),
+            "owner" => self.repo.split('/').next(),
+            "number" => self.number.as_deref(),
+            "branch" => self.branch.as_deref(),
+            "title" => Some(&self.title),
+            "thread_id" => self.thread_id.as_deref(),
+            _ => None,
+        }
+    }
+}
+
+/// Checks that every `{placeholder}` in a template is known and every brace is closed.
+pub fn validate_template(template: &str) -> Result<()> {
+    validate_placeholders(template, &PLACEHOLDERS)
+}
+
+pub fn validate_placeholders(template: &str, placeholders: &[&str]) -> Result<()> {
+    for segment in split_template(template)? {
+        if let Segment::Placeholder(name) = segment {
+            if !placeholders.contains(&name) {
+                return Err(anyhow!(
+                    "unknown placeholder {{{}}} (expected one of: {})",
+                    name,
+                    placeholders.join(", ")
+                ));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Expands a template, shell-quoting each substituted value.
+pub fn render_command(template: &str, values: &TemplateValues) -> Result<String> {
+    render_with(template, |name| {
+        values
+            .get(name)
+            .map(shell_quote)
+            .ok_or_else(|| anyhow!("{{{}}} is not available for this item", name))
+    })
+}
+
+/// Expands a template with `lookup`, which is responsible for any quoting.
+pub fn render_with(
+    template: &str,
+    mut lookup: impl FnMut(&str) -> Result<String>,
+) -> Result<String> {
+    let mut command = String::new();
+    for segment in split_template(template)? {
+        match segment {
+            Segment::Literal(text) => command.push_str(text),
+            Segment::Placeholder(name) => command.push_str(&lookup(name)?),
+        }
+    }
+    Ok(command)
+}
+
+pub fn run_in_background(action: &CustomAction, command: &str) -> Result<()> {
+    let output = Command::new("sh")
+        .arg("-c")
+        .arg(command)
+        .output()
+        .with_context(|| format!("failed to run {}", action.name))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let message = stderr.lines().last().unwrap_or("").trim();
+        return Err(anyhow!(
+            "{} exited with status {}{}",
+            action.name,
+            output.status,
+            if message.is_empty() {
+                String::new()
+            } else {
+                format!(": {}", message)
+            }
+        ));
+    }
+    Ok(())
+}
+
+enum Segment<'a> {
+    Literal(&'a str),
+    Placeholder(&'a str),
+}
+
+fn split_template(template: &str) -> Result<Vec<Segment<'_>>> {
+    let mut segments = Vec::new();
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        if start > 0 {
+            segments.push(Segment::Literal(&rest[..start]));
+        }
+        let after = &rest[start + 1..];
+        let end = after
+            .find('}')
+            .ok_or_else(|| anyhow!("unclosed '{{' in command template"))?;
+        segments.push(Segment::Placeholder(after[..end].trim()));
+        rest = &after[end + 1..];
+    }
+    if !rest.is_empty() {
+        segments.push(Segment::Literal(rest));
+    }
+    Ok(segments)
+}
+
+pub fn shell_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', r"'\''"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{render_command, validate_template, TemplateValues};
+
+    fn sample_values() -> TemplateValues {
+        TemplateValues {
+            url: "https://github.com/acme/widgets/pull/42".to_string(),
+            repo: "acme/widgets".to_string(),
+            number: Some("42".to_string()),
+            branch: Some("feature/branch".to_string()),
+            title: "Don't panic".to_string(),
+            thread_id: None,
+        }
+    }
+
+    #[test]
+    fn render_command_quotes_values() {
+        let command = render_command(
+            "gh pr checkout {number} --repo {repo} && echo {title} {owner}",
+            &sample_values(),
+        )
+        .unwrap();
+        assert_eq!(
+            command,
+            r"gh pr checkout '42' --repo 'acme/widgets' && echo 'Don'\''t panic' 'acme'"
+        );
+    }
+
+    #[test]
+    fn render_command_rejects_missing_values() {
+        let err = render_command("notify {thread_id}", &sample_values()).unwrap_err();
+        assert!(err.to_string().contains("{thread_id}"));
+    }
+
+    #[test]
+    fn validate_template_checks_placeholders() {
+        assert!(validate_template("code {url} {branch}").is_ok());
+        assert!(validate_template("code {path}").is_err());
+        assert!(validate_template("code {url").is_err());
+    }
+}
@@ -0,0 +1,653 @@
+use std::{collections::HashSet, path::PathBuf, time::Duration};
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    config::Settings,
+    pins::retain_unread_or_pinned,
+    status::{cache_home, StatusSummary},
+    types::{MyPullRequest, Notification},
+};
+
+const RUNTIME_SOCKET_NAME: &str = "ghn.sock";
+const CACHE_SOCKET_RELATIVE_PATH: &str = "ghn/daemon.sock";
+/// Client refreshes are coalesced so a busy script can't spend the whole rate-limit budget.
+const MIN_REFRESH_GAP: Duration = Duration::from_secs(10);
+const MAX_BACKOFF: Duration = Duration::from_secs(15 * 60);
+
+/// One line of JSON sent by a client.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "cmd", rename_all = "snake_case")]
+pub enum Request {
+    /// The latest inbox snapshot.
+    State,
+    /// The latest snapshot, then another after every poll until the client disconnects.
+    Subscribe,
+    Status,
+    /// Poll now, subject to the refresh gap and any rate-limit backoff.
+    Refresh,
+    Read {
+        targets: Vec<String>,
+    },
+    Done {
+        targets: Vec<String>,
+    },
+}
+
+/// One line of JSON sent back for each request.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Response {
+    pub ok: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub snapshot: Option<Snapshot>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status: Option<StatusSummary>,
+    /// Subject URLs handled by `read` or `done`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub marked: Vec<String>,
+}
+
+impl Response {
+    fn error(err: impl std::fmt::Display) -> Self {
+        Self {
+            error: Some(err.to_string()),
+            ..Self::default()
+        }
+    }
+}
+
+/// Everything the daemon fetched, before ignore rules and pins are applied.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Snapshot {
+    pub notifications: Vec<Notification>,
+    pub my_prs: Vec<MyPullRequest>,
+    /// Unix timestamp of the last successful poll, or 0 before the first one.
+    pub fetched_at: i64,
+    /// Error from the most recent poll; the data above is then from the last good one.
+    pub error: Option<String>,
+}
+
+impl Snapshot {
+    /// The daemon always fetches read notifications so it can serve `unread_only` clients too.
+    pub fn into_inbox(
+        self,
+        include_read: bool,
+        pinned: &HashSet<String>,
+    ) -> (Vec<Notification>, Vec<MyPullRequest>) {
+        let mut notifications = self.notifications;
+        if !include_read {
+            retain_unread_or_pinned(&mut notifications, pinned);
+        }
+        (notifications, self.my_prs)
+    }
+}
+
+pub fn socket_path(settings: &Settings) -> Result<PathBuf> {
+    if let Some(path) = &settings.daemon_socket {
+        return Ok(path.clone());
+    }
+    if let Ok(value) = std::env::var("XDG_RUNTIME_DIR") {
+        let trimmed = value.trim();
+        if !trimmed.is_empty() {
+            return Ok(PathBuf::from(trimmed).join(RUNTIME_SOCKET_NAME));
+        }
+    }
+    Ok(cache_home()?.join(CACHE_SOCKET_RELATIVE_PATH))
+}
+
+/// How long to wait before the next poll; rate-limit errors double the interval each time.
+fn next_poll_delay(interval: Duration, rate_limited: u32) -> Duration {
+    if rate_limited == 0 {
+        return interval;
+    }
+    let factor = 2u32.saturating_pow(rate_limited.min(16));
+    interval
+        .saturating_mul(factor)
+        .min(MAX_BACKOFF)
+        .max(interval)
+}
+
+fn is_rate_limited(err: &anyhow::Error) -> bool {
+    format!("{:#}", err).contains("rate limited")
+}
+
+#[cfg(unix)]
+pub use unix::{request, run, subscribe, Subscription};
+
+#[cfg(not(unix))]
+pub async fn run(_settings: &Settings) -> Result<()> {
+    Err(anyhow::anyhow!("ghn daemon needs Unix domain sockets"))
+}
+
+#[cfg(not(unix))]
+pub async fn request(_path: &std::path::Path, _request: &Request) -> Result<Option<Response>> {
+    Ok(None)
+}
+
+#[cfg(not(unix))]
+pub struct Subscription;
+
+#[cfg(not(unix))]
+impl Subscription {
+    pub async fn next(&mut self) -> Result<Option<Snapshot>> {
+        Ok(None)
+    }
+}
+
+#[cfg(not(unix))]
+pub async fn subscribe(_path: &std::path::Path) -> Result<Option<Subscription>> {
+    Ok(None)
+}
+
+#[cfg(unix)]
+mod unix {
+    use std::{
+        collections::HashSet, fs, io::ErrorKind, os::unix::fs::PermissionsExt, path::Path,
+        sync::Arc, time::Duration,
+    };
+
+    use anyhow::{anyhow, Context, Result};
+    use tokio::{
+        io::{AsyncBufReadExt, AsyncWriteExt, BufReader, Lines},
+        net::{unix::OwnedReadHalf, UnixListener, UnixStream},
+        sync::{watch, Notify},
+        time::Instant,
+    };
+
+    use super::{
+        is_rate_limited, next_poll_delay, socket_path, Request, Response, Snapshot, MIN_REFRESH_GAP,
+    };
+    use crate::{
+        cli::{build_inbox, find_notification},
+        config::Settings,
+        desktop::{deliver_alerts, AlertTracker, DesktopSettings},
+        execute_action,
+        github::fetch_notifications_and_my_prs_cached,
+        hooks::{deliver, HookSettings, HookTracker},
+        ignore::load_ignored_prs,
+        pins::load_pinned_urls,
+        status::{write_status_cache, StatusSummary},
+        types::Action,
+        util::gh_auth_token,
+        watched_inbox, EntrySnapshot,
+    };
+
+    struct Daemon {
+        client: reqwest::Client,
+        token: String,
+        include_read: bool,
+        hooks: HookSettings,
+        desktop: DesktopSettings,
+        state: watch::Sender<Snapshot>,
+        wake: Notify,
+    }
+
+    /// Runs the shared poller and serves it on the control socket until killed.
+    pub async fn run(settings: &Settings) -> Result<()> {
+        let path = socket_path(settings)?;
+        let listener = bind(&path).await?;
+        let daemon = Arc::new(Daemon {
+            client: reqwest::Client::new(),
+            token: gh_auth_token()?,
+            include_read: !settings.unread_only,
+            hooks: settings.hooks.clone(),
+            desktop: settings.desktop.clone(),
+            state: watch::Sender::new(Snapshot::default()),
+            wake: Notify::new(),
+        });
+
+        tokio::spawn(poll(daemon.clone(), Duration::from_secs(settings.interval)));
+        eprintln!("ghn daemon listening on {}", path.display());
+
+        loop {
+            let (stream, _) = listener
+                .accept()
+                .await
+                .context("failed to accept daemon connection")?;
+            let daemon = daemon.clone();
+            tokio::spawn(async move {
+                // A client hanging up mid-response is not the daemon's problem.
+                let _ = serve(&daemon, stream).await;
+            });
+        }
+    }
+
+    async fn bind(path: &Path) -> Result<UnixListener> {
+        if UnixStream::connect(path).await.is_ok() {
+            return Err(anyhow!(
+                "a ghn daemon is already listening on {}",
+                path.display()
+            ));
+        }
+        // Nobody answered, so any existing file is left over from a daemon that was killed.
+        match fs::remove_file(path) {
+            Ok(()) => {}
+            Err(err) if err.kind() == ErrorKind::NotFound => {}
+            Err(err) => {
+                return Err(err)
+                    .with_context(|| format!("failed to remove stale socket: {}", path.display()));
+            }
+        }
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .with_context(|| format!("failed to create {}", parent.display()))?;
+        }
+
+        let listener = UnixListener::bind(path)
+            .with_context(|| format!("failed to bind {}", path.display()))?;
+        // The socket can act with the user's GitHub token, so keep other users out.
+        fs::set_permissions(path, fs::Permissions::from_mode(0o600))
+            .with_context(|| format!("failed to restrict {}", path.display()))?;
+        Ok(listener)
+    }
+
+    async fn poll(daemon: Arc<Daemon>, interval: Duration) {
+        let mut viewer_login: Option<String> = None;
+        let mut rate_limited = 0u32;
+        let mut hooks = HookTracker::new(daemon.hooks.clone());
+        let mut alerts = AlertTracker::new(daemon.desktop.clone());
+
+        loop {
+            let started = Instant::now();
+            let result = fetch_notifications_and_my_prs_cached(
+                &daemon.client,
+                &daemon.token,
+                true,
+                viewer_login.as_deref(),
+            )
+            .await;
+            match result {
+                Ok(payload) => {
+                    rate_limited = 0;
+                    let next_login = payload.viewer_login.trim();
+                    if !next_login.is_empty() && next_login != "unknown" {
+                        viewer_login = Some(next_login.to_string());
+                    }
+                    // The snapshot keeps everything for other clients, but hooks and alerts
+                    // follow this daemon's own `unread_only` and ignore list like the TUI's.
+                    let pinned = if daemon.include_read {
+                        HashSet::new()
+                    } else {
+                        load_pinned_urls().unwrap_or_default()
+                    };
+                    let ignore_list = load_ignored_prs().unwrap_or_default();
+                    let (watched, watched_prs) = watched_inbox(
+                        &payload.notifications,
+                        &payload.my_prs,
+                        daemon.include_read,
+                        &pinned,
+                        &ignore_list,
+                        chrono::Utc::now().naive_utc(),
+                    );
+                    let events = hooks.update(&watched, &watched_prs);
+                    hooks.retain_fetched(&payload.notifications, &payload.my_prs);
+                    if !events.is_empty() {
+                        let daemon = daemon.clone();
+                        tokio::spawn(async move {
+                            if let Err(err) = deliver(&daemon.client, &daemon.hooks, events).await {
+                                eprintln!("event hook failed: {:#}", err);
+                            }
+                        });
+                    }
+                    let new_alerts =
+                        alerts.update(&watched, &watched_prs, chrono::Local::now().time());
+                    if !new_alerts.is_empty() {
+                        let command = alerts.command().map(str::to_string);
+                        tokio::spawn(async move {
+                            if let Err(err) = deliver_alerts(command, new_alerts).await {
+                                eprintln!("desktop notification failed: {:#}", err);
+                            }
+                        });
+                    }
+                    let fetched_at = chrono::Utc::now().timestamp();
+                    daemon.state.send_modify(|snapshot| {
+                        snapshot.notifications = payload.notifications;
+                        snapshot.my_prs = payload.my_prs;
+                        snapshot.fetched_at = fetched_at;
+                        snapshot.error = None;
+                    });
+                    let snapshot = daemon.state.borrow().clone();
+                    let include_read = daemon.include_read;
+                    // Keeps `ghn status --cached` working for clients that never connect.
+                    tokio::task::spawn_blocking(move || {
+                        summarize(snapshot, include_read).and_then(|summary| {
+                            write_status_cache(&summary)?;
+                            Ok(())
+                        })
+                    });
+                }
+                Err(err) => {
+                    if is_rate_limited(&err) {
+                        rate_limited += 1;
+                    }
+                    daemon
+                        .state
+                        .send_modify(|snapshot| snapshot.error = Some(format!("{:#}", err)));
+                }
+            }
+
+            let deadline = started + next_poll_delay(interval, rate_limited);
+            loop {
+                tokio::select! {
+                    _ = tokio::time::sleep_until(deadline) => break,
+                    _ = daemon.wake.notified() => {
+                        // While backing off, client refreshes wait for the deadline.
+                        if rate_limited == 0 {
+                            tokio::time::sleep_until(started + MIN_REFRESH_GAP).await;
+                            break;
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    async fn serve(daemon: &Daemon, stream: UnixStream) -> Result<()> {
+        let (read, mut write) = stream.into_split();
+        let mut lines = BufReader::new(read).lines();
+
+        while let Some(line) = lines.next_line().await? {
+            if line.trim().is_empty() {
+                continue;
+            }
+            let request = match serde_json::from_str::<Request>(&line) {
+                Ok(request) => request,
+                Err(err) => {
+                    send(
+                        &mut write,
+                        &Response::error(format!("invalid request: {}", err)),
+                    )
+                    .await?;
+                    continue;
+                }
+            };
+
+            if request == Request::Subscribe {
+                let mut state = daemon.state.subscribe();
+                loop {
+                    let snapshot = state.borrow_and_update().clone();
+                    if snapshot.fetched_at > 0 || snapshot.error.is_some() {
+                        let response = Response {
+                            ok: true,
+                            snapshot: Some(snapshot),
+                            ..Response::default()
+                        };
+                        send(&mut write, &response).await?;
+                    }
+                    if state.changed().await.is_err() {
+                        return Ok(());
+                    }
+                }
+            }
+
+            let response = handle(daemon, request)
+                .await
+                .unwrap_or_else(|err| Response::error(format!("{:#}", err)));
+            send(&mut write, &response).await?;
+        }
+        Ok(())
+    }
+
+    async fn handle(daemon: &Daemon, request: Request) -> Result<Response> {
+        let response = match request {
+            Request::State => Response {
+                ok: true,
+                snapshot: Some(first_snapshot(daemon).await?),
+                ..Response::default()
+            },
+            Request::Status => {
+                let snapshot = first_snapshot(daemon).await?;
+                let include_read = daemon.include_read;
+                let summary =
+                    tokio::task::spawn_blocking(move || summarize(snapshot, include_read))
+                        .await??;
+                Response {
+                    ok: true,
+                    status: Some(summary),
+                    ..Response::default()
+                }
+            }
+            Request::Refresh => {
+                daemon.wake.notify_one();
+                Response {
+                    ok: true,
+                    ..Response::default()
+                }
+            }
+            Request::Read { targets } => mark(daemon, &targets, Action::Read).await?,
+            Request::Done { targets } => mark(daemon, &targets, Action::Done).await?,
+            // `serve` streams subscriptions itself; answer rather than panic if one gets here.
+            Request::Subscribe => Response::error("subscribe is only served as a stream"),
+        };
+        Ok(response)
+    }
+
+    /// Waits for the first poll so early clients don't see an empty inbox.
+    async fn first_snapshot(daemon: &Daemon) -> Result<Snapshot> {
+        let mut state = daemon.state.subscribe();
+        let snapshot = state
+            .wait_for(|snapshot| snapshot.fetched_at > 0 || snapshot.error.is_some())
+            .await
+            .context("daemon poller stopped")?
+            .clone();
+        if snapshot.fetched_at == 0 {
+            return Err(anyhow!(
+                "{}",
+                snapshot.error.unwrap_or_else(|| "no data yet".to_string())
+            ));
+        }
+        Ok(snapshot)
+    }
+
+    async fn mark(daemon: &Daemon, targets: &[String], action: Action) -> Result<Response> {
+        let snapshot = first_snapshot(daemon).await?;
+        let notifications = targets
+            .iter()
+            .map(|target| {
+                find_notification(&snapshot.notifications, target)
+                    .cloned()
+                    .ok_or_else(|| anyhow!("no notification matches '{}'", target))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let mut marked = Vec::with_capacity(notifications.len());
+        for notification in notifications {
+            let entry = EntrySnapshot::Notification(notification.clone());
+            execute_action(
+                &daemon.client,
+                &daemon.token,
+                action,
+                &entry,
+                entry.url(),
+                None,
+            )
+            .await
+            .with_context(|| format!("failed to mark {}", notification.subject.url))?;
+            // Update the shared snapshot right away; the next poll confirms it.
+            daemon.state.send_modify(|snapshot| {
+                if action == Action::Done {
+                    snapshot.notifications.retain(|n| n.id != notification.id);
+                } else if let Some(n) = snapshot
+                    .notifications
+                    .iter_mut()
+                    .find(|n| n.id == notification.id)
+                {
+                    n.unread = false;
+                }
+            });
+            marked.push(notification.subject.url);
+        }
+        daemon.wake.notify_one();
+
+        Ok(Response {
+            ok: true,
+            marked,
+            ..Response::default()
+        })
+    }
+
+    fn summarize(snapshot: Snapshot, include_read: bool) -> Result<StatusSummary> {
+        let generated_at = snapshot.fetched_at;
+        let pinned = load_pinned_urls()?;
+        let (notifications, my_prs) = snapshot.into_inbox(include_read, &pinned);
+        let app = build_inbox(notifications, my_prs, include_read)?;
+        Ok(StatusSummary::new(
+            &app.notifications,
+            &app.my_prs,
+            generated_at,
+        ))
+    }
+
+    async fn send(write: &mut (impl AsyncWriteExt + Unpin), response: &Response) -> Result<()> {
+        let mut line = serde_json::to_vec(response)?;
+        line.push(b'\n');
+        write.write_all(&line).await?;
+        Ok(())
+    }
+
+    async fn connect(path: &Path) -> Result<Option<UnixStream>> {
+        match UnixStream::connect(path).await {
+            Ok(stream) => Ok(Some(stream)),
+            // No daemon running; callers fall back to talking to GitHub themselves.
+            Err(err)
+                if matches!(
+                    err.kind(),
+                    ErrorKind::NotFound | ErrorKind::ConnectionRefused
+                ) =>
+            {
+                Ok(None)
+            }
+            Err(err) => {
+                Err(err).with_context(|| format!("failed to connect to daemon: {}", path.display()))
+            }
+        }
+    }
+
+    fn parse_response(line: Option<String>) -> Result<Response> {
+        let line = line.ok_or_else(|| anyhow!("daemon closed the connection"))?;
+        let response: Response =
+            serde_json::from_str(&line).context("invalid response from daemon")?;
+        if !response.ok {
+            return Err(anyhow!(
+                "daemon: {}",
+                response.error.as_deref().unwrap_or("request failed")
+            ));
+        }
+        Ok(response)
+    }
+
+    /// Sends one request; `None` means no daemon is running at `path`.
+    pub async fn request(path: &Path, request: &Request) -> Result<Option<Response>> {
+        let Some(stream) = connect(path).await? else {
+            return Ok(None);
+        };
+        let (read, mut write) = stream.into_split();
+        let mut line = serde_json::to_vec(request)?;
+        line.push(b'\n');
+        write.write_all(&line).await?;
+        let mut lines = BufReader::new(read).lines();
+        parse_response(lines.next_line().await?).map(Some)
+    }
+
+    pub struct Subscription {
+        lines: Lines<BufReader<OwnedReadHalf>>,
+        // Dropping the write half would tell the daemon we hung up.
+        _write: tokio::net::unix::OwnedWriteHalf,
+    }
+
+    impl Subscription {
+        /// Waits for the next snapshot; `None` once the daemon goes away.
+        pub async fn next(&mut self) -> Result<Option<Snapshot>> {
+            let Some(line) = self.lines.next_line().await? else {
+                return Ok(None);
+            };
+            let response = parse_response(Some(line))?;
+            Ok(response.snapshot)
+        }
+    }
+
+    pub async fn subscribe(path: &Path) -> Result<Option<Subscription>> {
+        let Some(stream) = connect(path).await? else {
+            return Ok(None);
+        };
+        let (read, mut write) = stream.into_split();
+        let mut line = serde_json::to_vec(&Request::Subscribe)?;
+        line.push(b'\n');
+        write.write_all(&line).await?;
+        Ok(Some(Subscription {
+            lines: BufReader::new(read).lines(),
+            _write: write,
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{next_poll_delay, Request, Response, Snapshot, MAX_BACKOFF};
+    use std::collections::HashSet;
+    use std::time::Duration;
+
+    #[test]
+    fn requests_use_tagged_json() {
+        assert_eq!(
+            serde_json::from_str::<Request>(r#"{"cmd":"done","targets":["42"]}"#).unwrap(),
+            Request::Done {
+                targets: vec!["42".to_string()]
+            }
+        );
+        assert_eq!(
+            serde_json::to_string(&Request::Subscribe).unwrap(),
+            r#"{"cmd":"subscribe"}"#
+        );
+        assert!(serde_json::from_str::<Request>(r#"{"cmd":"merge"}"#).is_err());
+
+        let response = Response::error("boom");
+        assert_eq!(
+            serde_json::to_string(&response).unwrap(),
+            r#"{"ok":false,"error":"boom"}"#
+        );
+    }
+
+    #[test]
+    fn poll_delay_backs_off_when_rate_limited() {
+        let interval = Duration::from_secs(60);
+        assert_eq!(next_poll_delay(interval, 0), interval);
+        assert_eq!(next_poll_delay(interval, 1), Duration::from_secs(120));
+        assert_eq!(next_poll_delay(interval, 3), Duration::from_secs(480));
+        assert_eq!(next_poll_delay(interval, 40), MAX_BACKOFF);
+        // An interval longer than the cap is never shortened.
+        let slow = Duration::from_secs(3600);
+        assert_eq!(next_poll_delay(slow, 2), slow);
+    }
+
+    #[test]
+    fn snapshot_filters_read_for_unread_only_clients() {
+        let notification = |id: &str, unread: bool| {
+            format!(
+                r#"{{"id":"{id}","node_id":"n{id}","subject_id":null,"unread":{unread},"pinned":false,"reason":"mention","updated_at":"2024-01-01T00:00:00Z","subject":{{"title":"t","url":"https://github.com/acme/widgets/issues/{id}","kind":"Issue","author":null,"status":[],"ci_status":"Failure","review_status":null,"merge_state_status":null,"head_ref":null}},"repository":{{"name":"widgets","full_name":"acme/widgets","merge_settings":null}},"url":"u{id}"}}"#
+            )
+        };
+        let snapshot: Snapshot = serde_json::from_str(&format!(
+            r#"{{"notifications":[{},{}],"my_prs":[],"fetched_at":1,"error":null}}"#,
+            notification("1", true),
+            notification("2", false)
+        ))
+        .unwrap();
+
+        let no_pins = HashSet::new();
+        let (all, _) = snapshot.clone().into_inbox(true, &no_pins);
+        assert_eq!(all.len(), 2);
+        let (unread, my_prs) = snapshot.clone().into_inbox(false, &no_pins);
+        assert_eq!(unread.len(), 1);
+        assert_eq!(unread[0].id, "1");
+        assert!(my_prs.is_empty());
+
+        // A pinned thread stays listed after it's read.
+        let pinned = HashSet::from(["https://github.com/acme/widgets/issues/2".to_string()]);
+        let (unread, _) = snapshot.into_inbox(false, &pinned);
+        assert_eq!(unread.len(), 2);
+    }
+}
@@ -0,0 +1,403 @@
+use std::{
+    collections::HashSet,
+    process::{Command, Stdio},
+};
+
+use anyhow::{anyhow, Context, Result};
+use chrono::NaiveTime;
+use serde::Deserialize;
+
+use crate::{
+    custom_actions::{render_with, shell_quote},
+    types::{CiStatus, MyPullRequest, Notification, ReviewStatus},
+};
+
+/// Placeholders accepted by `desktop.command`.
+pub const DESKTOP_PLACEHOLDERS: [&str; 5] = ["title", "body", "url", "repo", "rule"];
+
+/// What counts as worth a desktop notification.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AlertRule {
+    ReviewRequested,
+    /// Direct or team mentions.
+    Mention,
+    Assign,
+    /// CI started failing on one of my pull requests.
+    CiFailure,
+    /// One of my pull requests was approved.
+    Approved,
+    ChangesRequested,
+}
+
+impl AlertRule {
+    pub const DEFAULT: [AlertRule; 4] = [
+        AlertRule::ReviewRequested,
+        AlertRule::Mention,
+        AlertRule::CiFailure,
+        AlertRule::Approved,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::ReviewRequested => "review_requested",
+            Self::Mention => "mention",
+            Self::Assign => "assign",
+            Self::CiFailure => "ci_failure",
+            Self::Approved => "approved",
+            Self::ChangesRequested => "changes_requested",
+        }
+    }
+
+    fn title(self) -> &'static str {
+        match self {
+            Self::ReviewRequested => "Review requested",
+            Self::Mention => "Mentioned",
+            Self::Assign => "Assigned",
+            Self::CiFailure => "CI failed",
+            Self::Approved => "Approved",
+            Self::ChangesRequested => "Changes requested",
+        }
+    }
+
+    fn matches_notification(self, notification: &Notification) -> bool {
+        match self {
+            Self::ReviewRequested => notification.reason == "review_requested",
+            Self::Mention => matches!(notification.reason.as_str(), "mention" | "team_mention"),
+            Self::Assign => notification.reason == "assign",
+            _ => false,
+        }
+    }
+
+    fn matches_my_pr(self, pr: &MyPullRequest) -> bool {
+        match self {
+            Self::CiFailure => pr.subject.ci_status == Some(CiStatus::Failure),
+            Self::Approved => pr.subject.review_status == Some(ReviewStatus::Approved),
+            Self::ChangesRequested => {
+                pr.subject.review_status == Some(ReviewStatus::ChangesRequested)
+            }
+            _ => false,
+        }
+    }
+}
+
+/// A local time window, possibly spanning midnight, in which alerts are dropped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct QuietHours {
+    start: NaiveTime,
+    end: NaiveTime,
+}
+
+impl QuietHours {
+    /// Parses `HH:MM-HH:MM`.
+    pub fn parse(value: &str) -> Result<Self> {
+        let (start, end) = value
+            .split_once('-')
+            .ok_or_else(|| anyhow!("expected HH:MM-HH:MM, got '{}'", value))?;
+        let parse = |time: &str| {
+            NaiveTime::parse_from_str(time.trim(), "%H:%M")
+                .with_context(|| format!("invalid time '{}' (expected HH:MM)", time.trim()))
+        };
+        Ok(Self {
+            start: parse(start)?,
+            end: parse(end)?,
+        })
+    }
+
+    pub fn contains(&self, time: NaiveTime) -> bool {
+        if self.start <= self.end {
+            self.start <= time && time < self.end
+        } else {
+            time >= self.start || time < self.end
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DesktopSettings {
+    pub enabled: bool,
+    /// Shell template; `None` runs `notify-send` directly.
+    pub command: Option<String>,
+    pub rules: Vec<AlertRule>,
+    pub quiet_hours: Option<QuietHours>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Alert {
+    pub rule: AlertRule,
+    pub repo: String,
+    pub title: String,
+    pub url: String,
+}
+
+/// Remembers what has already been announced between fetches.
+#[derive(Debug, Default)]
+pub struct AlertTracker {
+    settings: DesktopSettings,
+    /// `thread id@updated_at` of notifications seen so far.
+    seen_threads: HashSet<String>,
+    /// `rule:pr id` for my pull requests matching a rule on the last fetch.
+    active_prs: HashSet<String>,
+    primed: bool,
+}
+
+impl AlertTracker {
+    pub fn new(settings: DesktopSettings) -> Self {
+        Self {
+            settings,
+            ..Self::default()
+        }
+    }
+
+    /// Returns alerts for items that are new since the last call.
+    ///
+    /// The first call only records the current inbox so starting `ghn` doesn't replay it.
+    pub fn update(
+        &mut self,
+        notifications: &[Notification],
+        my_prs: &[MyPullRequest],
+        local_time: NaiveTime,
+    ) -> Vec<Alert> {
+        if !self.settings.enabled {
+            return Vec::new();
+        }
+
+        let mut alerts = Vec::new();
+        for notification in notifications.iter().filter(|n| n.unread) {
+            let key = format!("{}@{}", notification.id, notification.updated_at);
+            if !self.seen_threads.insert(key) {
+                continue;
+            }
+            if let Some(rule) = self
+                .settings
+                .rules
+                .iter()
+                .copied()
+                .find(|rule| rule.matches_notification(notification))
+            {
+                alerts.push(Alert {
+                    rule,
+                    repo: notification.repository.full_name.clone(),
+                    title: notification.subject.title.clone(),
+                    url: notification.subject.url.clone(),
+                });
+            }
+        }
+
+        let mut active_prs = HashSet::new();
+        for pr in my_prs {
+            for rule in self.settings.rules.iter().copied() {
+                if !rule.matches_my_pr(pr) {
+                    continue;
+                }
+                let key = format!("{}:{}", rule.name(), pr.id);
+                if !self.active_prs.contains(&key) {
+                    alerts.push(Alert {
+                        rule,
+                        repo: pr.repository.full_name.clone(),
+                        title: pr.subject.title.clone(),
+                        url: pr.subject.url.clone(),
+                    });
+                }
+                active_prs.insert(key);
+            }
+        }
+        self.active_prs = active_prs;
+
+        let quiet = self
+            .settings
+            .quiet_hours
+            .is_some_and(|quiet| quiet.contains(local_time));
+        if !self.primed || quiet {
+            self.primed = true;
+            return Vec::new();
+        }
+        alerts
+    }
+
+    pub fn command(&self) -> Option<&str> {
+        self.settings.command.as_deref()
+    }
+}
+
+/// Shows one poll's alerts without blocking the poller.
+pub async fn deliver_alerts(command: Option<String>, alerts: Vec<Alert>) -> Result<()> {
+    tokio::task::spawn_blocking(move || send_alerts(command.as_deref(), &alerts))
+        .await
+        .context("desktop notification task failed")?
+}
+
+/// Shows each alert, stopping at the first command that fails.
+pub fn send_alerts(command: Option<&str>, alerts: &[Alert]) -> Result<()> {
+    for alert in alerts {
+        let title = format!("{} · {}", alert.rule.title(), alert.repo);
+        let status = match command {
+            Some(template) => {
+                let command = render_with(template, |name| {
+                    let value = match name {
+                        "title" => title.as_str(),
+                        "body" => alert.title.as_str(),
+                        "url" => alert.url.as_str(),
+                        "repo" => alert.repo.as_str(),
+                        "rule" => alert.rule.name(),
+                        _ => return Err(anyhow!("unknown placeholder {{{}}}", name)),
+                    };
+                    Ok(shell_quote(value))
+                })?;
+                quiet(Command::new("sh").arg("-c").arg(&command))
+                    .status()
+                    .context("failed to run desktop.command")?
+            }
+            None => quiet(
+                Command::new("notify-send")
+                    .args(["--app-name", "ghn"])
+                    .arg(&title)
+                    .arg(&alert.title),
+            )
+            .status()
+            .context("failed to run notify-send (set desktop.command to use something else)")?,
+        };
+        if !status.success() {
+            return Err(anyhow!(
+                "desktop notification command exited with {}",
+                status
+            ));
+        }
+    }
+    Ok(())
+}
+
+fn quiet(command: &mut Command) -> &mut Command {
+    // Output would draw over the TUI.
+    command
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{AlertRule, AlertTracker, DesktopSettings, QuietHours};
+    use crate::types::{CiStatus, MyPullRequest, Notification, Repository, Subject};
+    use chrono::NaiveTime;
+
+    fn subject(title: &str) -> Subject {
+        Subject {
+            title: title.to_string(),
+            url: format!("https://github.com/acme/widgets/pull/{title}"),
+            kind: "PullRequest".to_string(),
+            author: None,
+            status: Vec::new(),
+            ci_status: None,
+            review_status: None,
+            merge_state_status: None,
+            head_ref: None,
+        }
+    }
+
+    fn repository() -> Repository {
+        Repository {
+            name: "widgets".to_string(),
+            full_name: "acme/widgets".to_string(),
+            merge_settings: None,
+        }
+    }
+
+    fn notification(id: &str, reason: &str, updated_at: &str) -> Notification {
+        Notification {
+            id: id.to_string(),
+            node_id: format!("node-{id}"),
+            subject_id: None,
+            unread: true,
+            pinned: false,
+            reason: reason.to_string(),
+            updated_at: updated_at.to_string(),
+            subject: subject(id),
+            repository: repository(),
+            url: String::new(),
+        }
+    }
+
+    fn my_pr(id: &str, ci_status: Option<CiStatus>) -> MyPullRequest {
+        let mut subject = subject(id);
+        subject.ci_status = ci_status;
+        MyPullRequest {
+            id: id.to_string(),
+            updated_at: "2024-01-01T00:00:00Z".to_string(),
+            pinned: false,
+            subject,
+            repository: repository(),
+            url: String::new(),
+        }
+    }
+
+    fn tracker(quiet_hours: Option<QuietHours>) -> AlertTracker {
+        AlertTracker::new(DesktopSettings {
+            enabled: true,
+            command: None,
+            rules: AlertRule::DEFAULT.to_vec(),
+            quiet_hours,
+        })
+    }
+
+    fn noon() -> NaiveTime {
+        NaiveTime::from_hms_opt(12, 0, 0).unwrap()
+    }
+
+    #[test]
+    fn alerts_only_for_new_matching_items() {
+        let mut tracker = tracker(None);
+        let existing = notification("1", "review_requested", "2024-01-01T00:00:00Z");
+        assert!(tracker
+            .update(std::slice::from_ref(&existing), &[], noon())
+            .is_empty());
+
+        let mentioned = notification("2", "mention", "2024-01-01T00:00:00Z");
+        let subscribed = notification("3", "subscribed", "2024-01-01T00:00:00Z");
+        let alerts = tracker.update(&[existing.clone(), mentioned, subscribed], &[], noon());
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0].rule, AlertRule::Mention);
+
+        // New activity on a known thread is announced again.
+        let bumped = notification("1", "review_requested", "2024-01-02T00:00:00Z");
+        let alerts = tracker.update(std::slice::from_ref(&bumped), &[], noon());
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0].rule, AlertRule::ReviewRequested);
+        assert!(tracker.update(&[bumped], &[], noon()).is_empty());
+    }
+
+    #[test]
+    fn my_pr_alerts_fire_on_transitions() {
+        let mut tracker = tracker(None);
+        assert!(tracker
+            .update(&[], &[my_pr("10", Some(CiStatus::Pending))], noon())
+            .is_empty());
+
+        let alerts = tracker.update(&[], &[my_pr("10", Some(CiStatus::Failure))], noon());
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0].rule, AlertRule::CiFailure);
+        assert!(tracker
+            .update(&[], &[my_pr("10", Some(CiStatus::Failure))], noon())
+            .is_empty());
+    }
+
+    #[test]
+    fn quiet_hours_drop_alerts_and_span_midnight() {
+        let quiet = QuietHours::parse("22:00-08:00").unwrap();
+        assert!(quiet.contains(NaiveTime::from_hms_opt(23, 30, 0).unwrap()));
+        assert!(quiet.contains(NaiveTime::from_hms_opt(7, 59, 0).unwrap()));
+        assert!(!quiet.contains(noon()));
+        assert!(QuietHours::parse("22:00").is_err());
+        assert!(QuietHours::parse("25:00-08:00").is_err());
+
+        let mut tracker = tracker(Some(quiet));
+        let night = NaiveTime::from_hms_opt(23, 0, 0).unwrap();
+        tracker.update(&[], &[], noon());
+        let mention = notification("1", "mention", "2024-01-01T00:00:00Z");
+        assert!(tracker
+            .update(std::slice::from_ref(&mention), &[], night)
+            .is_empty());
+        // Items that arrived during quiet hours stay silent afterwards.
+        assert!(tracker.update(&[mention], &[], noon()).is_empty());
+    }
+}
@@ -0,0 +1,343 @@
+use std::collections::BTreeMap;
+
+use anyhow::{anyhow, Result};
+
+use crate::commands::parse_age;
+use crate::filter::Filter;
+use crate::ignore::IgnoreRule;
+
+/// `:` commands with their arguments and help text, in completion order.
+pub const EX_COMMANDS: [(&str, &str, &str); 8] = [
+    ("filter", "<query>", "Set the / filter; no query clears it"),
+    (
+        "sort",
+        "updated|age|repo|title",
+        "Order items within each section",
+    ),
+    (
+        "snooze",
+        "<targets> <duration>",
+        "Hide items for a while, e.g. :snooze 3 2h",
+    ),
+    (
+        "ignore-repo",
+        "<owner/repo>",
+        "Add a repo: rule to the ignore list",
+    ),
+    ("reload-config", "", "Re-read config.toml"),
+    (
+        "export",
+        "md|json",
+        "Copy the visible items to the clipboard",
+    ),
+    ("help", "", "Show this help"),
+    ("quit", "", "Quit"),
+];
+
+/// Order of items within each section.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SortOrder {
+    /// Most recently updated first.
+    #[default]
+    Updated,
+    /// Least recently updated first.
+    Age,
+    Repo,
+    Title,
+}
+
+impl SortOrder {
+    pub const ALL: [SortOrder; 4] = [Self::Updated, Self::Age, Self::Repo, Self::Title];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Updated => "updated",
+            Self::Age => "age",
+            Self::Repo => "repo",
+            Self::Title => "title",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    Markdown,
+    Json,
+}
+
+impl ExportFormat {
+    pub const ALL: [ExportFormat; 2] = [Self::Markdown, Self::Json];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Markdown => "md",
+            Self::Json => "json",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ExCommand {
+    /// An empty query clears the filter.
+    Filter(Filter),
+    Sort(SortOrder),
+    /// Targets are resolved against the list when the command runs.
+    Snooze {
+        targets: String,
+        seconds: i64,
+    },
+    IgnoreRepo(String),
+    ReloadConfig,
+    Export(ExportFormat),
+    Help,
+    Quit,
+    /// Name of a saved command line from `[macros]`.
+    Macro(String),
+}
+
+/// Parses the text after `:`. Commands may be shortened to any unique prefix, e.g. `:q`.
+pub fn parse_ex(input: &str, macros: &BTreeMap<String, String>) -> Result<ExCommand> {
+    let input = input.trim();
+    let (name, args) = input
+        .split_once(char::is_whitespace)
+        .map_or((input, ""), |(name, args)| (name, args.trim()));
+    if name.is_empty() {
+        return Err(anyhow!("expected a command"));
+    }
+
+    let command = match resolve_name(name, macros)? {
+        Resolved::Macro => return no_args(name, args, ExCommand::Macro(name.to_string())),
+        Resolved::Command(command) => command,
+    };
+    match command {
+        "filter" => Ok(ExCommand::Filter(Filter::parse(args)?)),
+        "sort" => {
+            let order = SortOrder::ALL
+                .into_iter()
+                .find(|order| order.name() == args)
+                .ok_or_else(|| anyhow!("unknown sort order '{}' ({})", args, sort_names()))?;
+            Ok(ExCommand::Sort(order))
+        }
+        "snooze" => {
+            let (targets, duration) = args
+                .rsplit_once(char::is_whitespace)
+                .ok_or_else(|| anyhow!("usage: :snooze <targets> <duration>, e.g. :snooze 3 2h"))?;
+            let seconds = parse_age(duration)?;
+            if seconds == 0 {
+                return Err(anyhow!("snooze duration must be more than zero"));
+            }
+            Ok(ExCommand::Snooze {
+                targets: targets.trim().to_string(),
+                seconds,
+            })
+        }
+        "ignore-repo" => {
+            if args.is_empty() || args.contains(char::is_whitespace) {
+                return Err(anyhow!("usage: :ignore-repo <owner/repo>, globs allowed"));
+            }
+            let rule = format!("repo:{}", args);
+            IgnoreRule::parse(&rule)?;
+            Ok(ExCommand::IgnoreRepo(rule))
+        }
+        "reload-config" => no_args(command, args, ExCommand::ReloadConfig),
+        "export" => {
+            let format = ExportFormat::ALL
+                .into_iter()
+                .find(|format| format.name() == args)
+                .ok_or_else(|| anyhow!("unknown export format '{}' (md or json)", args))?;
+            Ok(ExCommand::Export(format))
+        }
+        "help" => no_args(command, args, ExCommand::Help),
+        "quit" => no_args(command, args, ExCommand::Quit),
+        _ => unreachable!("EX_COMMANDS and parse_ex disagree"),
+    }
+}
+
+enum Resolved {
+    Command(&'static str),
+    Macro,
+}
+
+fn resolve_name(name: &str, macros: &BTreeMap<String, String>) -> Result<Resolved> {
+    if let Some((command, _, _)) = EX_COMMANDS.iter().find(|(command, _, _)| *command == name) {
+        return Ok(Resolved::Command(command));
+    }
+    if macros.contains_key(name) {
+        return Ok(Resolved::Macro);
+    }
+    let matches: Vec<&'static str> = EX_COMMANDS
+        .iter()
+        .map(|(command, _, _)| *command)
+        .filter(|command| command.starts_with(name))
+        .collect();
+    match matches.as_slice() {
+        [command] => Ok(Resolved::Command(command)),
+        [] => Err(anyhow!("unknown command ':{}'", name)),
+        _ => Err(anyhow!("':{}' is ambiguous: {}", name, matches.join(", "))),
+    }
+}
+
+fn no_args(name: &str, args: &str, command: ExCommand) -> Result<ExCommand> {
+    if !args.is_empty() {
+        return Err(anyhow!("':{}' takes no arguments", name));
+    }
+    Ok(command)
+}
+
+fn sort_names() -> String {
+    SortOrder::ALL.map(SortOrder::name).join(", ")
+}
+
+/// Tab completion: the completed line, plus the candidates when more than one still fits.
+pub fn complete_ex(input: &str, macros: &BTreeMap<String, String>) -> (String, Vec<String>) {
+    let (prefix, word, options): (&str, &str, Vec<String>) = match input.split_once(' ') {
+        None => {
+            let names = EX_COMMANDS
+                .iter()
+                .map(|(command, _, _)| command.to_string())
+                .chain(macros.keys().cloned())
+                .collect();
+            ("", input, names)
+        }
+        Some((name, arg)) if !arg.contains(' ') => {
+            let options = match name {
+                "sort" => SortOrder::ALL
+                    .map(|order| order.name().to_string())
+                    .to_vec(),
+                "export" => ExportFormat::ALL
+                    .map(|format| format.name().to_string())
+                    .to_vec(),
+                _ => return (input.to_string(), Vec::new()),
+            };
+            (&input[..name.len() + 1], arg, options)
+        }
+        Some(_) => return (input.to_string(), Vec::new()),
+    };
+
+    let matches: Vec<String> = options
+        .into_iter()
+        .filter(|option| option.starts_with(word))
+        .collect();
+    match matches.as_slice() {
+        [] => (input.to_string(), Vec::new()),
+        [only] => {
+            let takes_args = prefix.is_empty()
+                && EX_COMMANDS
+                    .iter()
+                    .any(|(command, args, _)| command == only && !args.is_empty());
+            let space = if takes_args { " " } else { "" };
+            (format!("{}{}{}", prefix, only, space), Vec::new())
+        }
+        _ => {
+            let common = common_prefix(&matches);
+            (format!("{}{}", prefix, common), matches)
+        }
+    }
+}
+
+fn common_prefix(words: &[String]) -> &str {
+    let first = &words[0];
+    let len = words[1..].iter().fold(first.len(), |len, word| {
+        first[..len]
+            .char_indices()
+            .zip(word.chars())
+            .find(|((_, a), b)| a != b)
+            .map_or(len.min(word.len()), |((idx, _), _)| idx)
+    });
+    &first[..len]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{complete_ex, parse_ex, ExCommand, ExportFormat, SortOrder};
+    use std::collections::BTreeMap;
+
+    fn macros() -> BTreeMap<String, String> {
+        BTreeMap::from([("triage".to_string(), "md cd".to_string())])
+    }
+
+    #[test]
+    fn parses_commands_and_prefixes() {
+        let macros = macros();
+        assert_eq!(parse_ex("q", &macros).unwrap(), ExCommand::Quit);
+        assert_eq!(
+            parse_ex("sort age", &macros).unwrap(),
+            ExCommand::Sort(SortOrder::Age)
+        );
+        assert_eq!(
+            parse_ex("export md", &macros).unwrap(),
+            ExCommand::Export(ExportFormat::Markdown)
+        );
+        assert_eq!(
+            parse_ex("snooze 1-3 %ready 2h", &macros).unwrap(),
+            ExCommand::Snooze {
+                targets: "1-3 %ready".to_string(),
+                seconds: 2 * 60 * 60,
+            }
+        );
+        assert_eq!(
+            parse_ex("ignore-repo acme/*", &macros).unwrap(),
+            ExCommand::IgnoreRepo("repo:acme/*".to_string())
+        );
+        assert_eq!(
+            parse_ex("triage", &macros).unwrap(),
+            ExCommand::Macro("triage".to_string())
+        );
+        assert!(matches!(
+            parse_ex("filter repo:foo", &macros).unwrap(),
+            ExCommand::Filter(filter) if filter.query() == "repo:foo"
+        ));
+    }
+
+    #[test]
+    fn rejects_bad_commands() {
+        let macros = macros();
+        for (input, message) in [
+            ("s", "':s' is ambiguous: sort, snooze"),
+            ("nope", "unknown command ':nope'"),
+            ("quit now", "':quit' takes no arguments"),
+            (
+                "sort size",
+                "unknown sort order 'size' (updated, age, repo, title)",
+            ),
+            ("export pdf", "unknown export format 'pdf' (md or json)"),
+        ] {
+            assert_eq!(parse_ex(input, &macros).unwrap_err().to_string(), message);
+        }
+        assert!(parse_ex("snooze 3", &macros).is_err());
+        assert!(parse_ex("snooze 3 0h", &macros).is_err());
+        assert!(parse_ex("ignore-repo", &macros).is_err());
+        assert!(parse_ex("filter ci:red", &macros).is_err());
+    }
+
+    #[test]
+    fn completes_names_and_arguments() {
+        let macros = macros();
+        assert_eq!(complete_ex("so", &macros), ("sort ".to_string(), vec![]));
+        assert_eq!(complete_ex("qu", &macros), ("quit".to_string(), vec![]));
+        assert_eq!(complete_ex("tr", &macros), ("triage".to_string(), vec![]));
+        assert_eq!(
+            complete_ex("s", &macros),
+            (
+                "s".to_string(),
+                vec!["sort".to_string(), "snooze".to_string()]
+            )
+        );
+        assert_eq!(
+            complete_ex("re", &macros),
+            ("reload-config".to_string(), vec![])
+        );
+        assert_eq!(
+            complete_ex("export j", &macros),
+            ("export json".to_string(), vec![])
+        );
+        assert_eq!(
+            complete_ex("sort ", &macros).1,
+            ["updated", "age", "repo", "title"]
+        );
+        assert_eq!(
+            complete_ex("filter rep", &macros),
+            ("filter rep".to_string(), vec![])
+        );
+    }
+}
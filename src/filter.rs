@@ -0,0 +1,281 @@
+use anyhow::{anyhow, Result};
+
+use crate::commands::parse_age;
+use crate::types::{CiStatus, ReviewStatus, Subject};
+
+/// The fields a `/` search looks at for one entry.
+pub struct FilterItem<'a> {
+    pub subject: &'a Subject,
+    pub repo: &'a str,
+    /// Notification reason; my PRs have none.
+    pub reason: Option<&'a str>,
+    pub unread: bool,
+    pub pinned: bool,
+    /// Seconds since the last update, if the timestamp parsed.
+    pub age: Option<i64>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Is {
+    Unread,
+    Read,
+    Pinned,
+    PullRequest,
+    Issue,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Term {
+    /// Lowercased text matched against title, repo, author, reason and branch.
+    Text(String),
+    Repo(String),
+    Author(String),
+    Reason(String),
+    Is(Is),
+    Ci(CiStatus),
+    Review(ReviewStatus),
+    OlderThan(i64),
+    NewerThan(i64),
+}
+
+/// A `/` search: free text and `key:value` qualifiers, all of which must match.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Filter {
+    query: String,
+    terms: Vec<Term>,
+}
+
+impl Filter {
+    pub fn parse(query: &str) -> Result<Self> {
+        let terms = tokenize(query)?
+            .into_iter()
+            .map(|token| parse_term(&token))
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Self {
+            query: query.trim().to_string(),
+            terms,
+        })
+    }
+
+    pub fn query(&self) -> &str {
+        &self.query
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.terms.is_empty()
+    }
+
+    pub fn matches(&self, item: &FilterItem<'_>) -> bool {
+        self.terms.iter().all(|term| term_matches(term, item))
+    }
+}
+
+fn tokenize(query: &str) -> Result<Vec<String>> {
+    let mut tokens = Vec::new();
+    let mut current = String::new();
+    let mut quoted = false;
+    for ch in query.chars() {
+        match ch {
+            '"' => quoted = !quoted,
+            ch if ch.is_whitespace() && !quoted => {
+                if !current.is_empty() {
+                    tokens.push(std::mem::take(&mut current));
+                }
+            }
+            ch => current.push(ch),
+        }
+    }
+    if quoted {
+        return Err(anyhow!("unclosed quote"));
+    }
+    if !current.is_empty() {
+        tokens.push(current);
+    }
+    Ok(tokens)
+}
+
+fn parse_term(token: &str) -> Result<Term> {
+    let Some((key, value)) = token.split_once(':') else {
+        return Ok(Term::Text(token.to_lowercase()));
+    };
+    let value = value.to_lowercase();
+    if value.is_empty() {
+        return Err(anyhow!("{}: needs a value", key));
+    }
+
+    let term = match key.to_lowercase().as_str() {
+        "repo" => Term::Repo(value),
+        "author" => Term::Author(value),
+        "reason" => Term::Reason(value),
+        "is" => Term::Is(match value.as_str() {
+            "unread" => Is::Unread,
+            "read" => Is::Read,
+            "pinned" => Is::Pinned,
+            "pr" => Is::PullRequest,
+            "issue" => Is::Issue,
+            _ => {
+                return Err(anyhow!(
+                    "unknown is:{} (expected unread, read, pinned, pr or issue)",
+                    value
+                ))
+            }
+        }),
+        "ci" => Term::Ci(match value.as_str() {
+            "success" => CiStatus::Success,
+            "pending" => CiStatus::Pending,
+            "failure" => CiStatus::Failure,
+            _ => {
+                return Err(anyhow!(
+                    "unknown ci:{} (expected success, pending or failure)",
+                    value
+                ))
+            }
+        }),
+        "review" => Term::Review(match value.as_str() {
+            "approved" => ReviewStatus::Approved,
+            "changes_requested" => ReviewStatus::ChangesRequested,
+            "review_required" => ReviewStatus::ReviewRequired,
+            _ => {
+                return Err(anyhow!(
+                    "unknown review:{} (expected approved, changes_requested or review_required)",
+                    value
+                ))
+            }
+        }),
+        "age" => {
+            if let Some(age) = value.strip_prefix('>') {
+                Term::OlderThan(parse_age(age)?)
+            } else if let Some(age) = value.strip_prefix('<') {
+                Term::NewerThan(parse_age(age)?)
+            } else {
+                return Err(anyhow!("age: needs > or <, e.g. age:>7d"));
+            }
+        }
+        // URLs and other text with colons are searched as-is.
+        _ => Term::Text(token.to_lowercase()),
+    };
+    Ok(term)
+}
+
+fn contains(haystack: &str, needle: &str) -> bool {
+    haystack.to_lowercase().contains(needle)
+}
+
+fn term_matches(term: &Term, item: &FilterItem<'_>) -> bool {
+    let subject = item.subject;
+    match term {
+        Term::Text(text) => {
+            contains(&subject.title, text)
+                || contains(item.repo, text)
+                || subject
+                    .author
+                    .as_deref()
+                    .is_some_and(|author| contains(author, text))
+                || item.reason.is_some_and(|reason| contains(reason, text))
+                || subject
+                    .head_ref
+                    .as_deref()
+                    .is_some_and(|branch| contains(branch, text))
+        }
+        Term::Repo(repo) => contains(item.repo, repo),
+        Term::Author(author) => subject
+            .author
+            .as_deref()
+            .is_some_and(|value| value.eq_ignore_ascii_case(author)),
+        Term::Reason(reason) => item
+            .reason
+            .is_some_and(|value| value.eq_ignore_ascii_case(reason)),
+        Term::Is(Is::Unread) => item.unread,
+        Term::Is(Is::Read) => !item.unread,
+        Term::Is(Is::Pinned) => item.pinned,
+        Term::Is(Is::PullRequest) => subject.kind.eq_ignore_ascii_case("pullrequest"),
+        Term::Is(Is::Issue) => subject.kind.eq_ignore_ascii_case("issue"),
+        Term::Ci(ci) => subject.ci_status == Some(*ci),
+        Term::Review(review) => subject.review_status == Some(*review),
+        Term::OlderThan(limit) => item.age.is_some_and(|age| age > *limit),
+        Term::NewerThan(limit) => item.age.is_some_and(|age| age < *limit),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Filter, FilterItem};
+    use crate::types::{CiStatus, ReviewStatus, Subject};
+
+    fn subject() -> Subject {
+        Subject {
+            title: "Fix flaky login test".to_string(),
+            url: "https://github.com/acme/widgets/pull/7".to_string(),
+            kind: "PullRequest".to_string(),
+            author: Some("octocat".to_string()),
+            status: Vec::new(),
+            ci_status: Some(CiStatus::Failure),
+            review_status: Some(ReviewStatus::Approved),
+            merge_state_status: None,
+            head_ref: Some("fix/login-retry".to_string()),
+        }
+    }
+
+    fn matches(query: &str, subject: &Subject, reason: Option<&str>, unread: bool) -> bool {
+        Filter::parse(query).unwrap().matches(&FilterItem {
+            subject,
+            repo: "acme/widgets",
+            reason,
+            unread,
+            pinned: false,
+            age: Some(3 * 24 * 60 * 60),
+        })
+    }
+
+    #[test]
+    fn free_text_searches_every_field() {
+        let subject = subject();
+        for query in ["FLAKY", "acme/wid", "octo", "mention", "login-retry", ""] {
+            assert!(matches(query, &subject, Some("mention"), true), "{query}");
+        }
+        assert!(!matches("gadgets", &subject, Some("mention"), true));
+        assert!(matches("\"login test\"", &subject, None, true));
+        assert!(!matches("\"test login\"", &subject, None, true));
+    }
+
+    #[test]
+    fn qualifiers_must_all_match() {
+        let subject = subject();
+        assert!(matches(
+            "repo:widgets author:OctoCat ci:failure review:approved is:unread is:pr",
+            &subject,
+            None,
+            true
+        ));
+        assert!(!matches("is:unread", &subject, None, false));
+        assert!(matches("is:read", &subject, None, false));
+        assert!(!matches("reason:mention", &subject, None, true));
+        assert!(matches(
+            "reason:mention flaky",
+            &subject,
+            Some("mention"),
+            true
+        ));
+        assert!(!matches("ci:pending", &subject, None, true));
+        assert!(!matches("author:octo", &subject, None, true));
+        assert!(matches("age:>2d age:<1w", &subject, None, true));
+        assert!(!matches("age:>7d", &subject, None, true));
+    }
+
+    #[test]
+    fn invalid_qualifiers_are_errors() {
+        assert!(Filter::parse("ci:red").is_err());
+        assert!(Filter::parse("is:").is_err());
+        assert!(Filter::parse("age:7d").is_err());
+        assert!(Filter::parse("age:>7y").is_err());
+        // Parsed on every keystroke, so partial or non-ASCII input must not panic.
+        assert!(Filter::parse("age:").is_err());
+        assert!(Filter::parse("age:é").is_err());
+        assert!(Filter::parse("age:>é").is_err());
+        assert!(Filter::parse("age:>").is_err());
+        assert!(Filter::parse("\"open").is_err());
+        assert!(Filter::parse("  ").unwrap().is_empty());
+        // Unknown keys are plain text so URLs still work.
+        assert!(!Filter::parse("https://github.com").unwrap().is_empty());
+    }
+}
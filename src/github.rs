@@ -125,8 +125,11 @@ struct GraphQlPullRequest {
 struct GraphQlPrettyPullRequest {
     title: String,
     url: String,
+    state: String,
+    is_draft: bool,
     additions: i64,
     deletions: i64,
+    head_ref_name: String,
     head_repository: Option<GraphQlPrettyRepository>,
     head_repository_owner: Option<GraphQlPrettyRepositoryOwner>,
 }
@@ -181,8 +184,11 @@ pub struct PullRequestKey {
 pub struct PrettyPullRequest {
     pub url: String,
     pub title: String,
+    pub state: String,
+    pub is_draft: bool,
     pub additions: i64,
     pub deletions: i64,
+    pub head_ref: String,
     pub head_repo_owner: String,
     pub head_repo_name: String,
 }
@@ -242,8 +248,11 @@ query PrettyPullRequest($owner: String!, $name: String!, $number: Int!) {
     pullRequest(number: $number) {
       title
       url
+      state
+      isDraft
       additions
       deletions
+      headRefName
       headRepository { name }
       headRepositoryOwner { login }
     }
@@ -570,6 +579,7 @@ fn transform_notification_thread(
             .as_ref()
             .and_then(|subject| subject.id.clone()),
         unread,
+        pinned: false,
         reason,
         updated_at,
         subject,
@@ -627,6 +637,7 @@ fn transform_pull_request(pr: GraphQlPullRequest) -> MyPullRequest {
     MyPullRequest {
         id: pr.id,
         updated_at: pr.updated_at,
+        pinned: false,
         subject,
         repository: Repository {
             name: pr.repository.name,
@@ -1025,8 +1036,11 @@ pub async fn fetch_pretty_pull_request(
     Ok(PrettyPullRequest {
         url: pr.url,
         title: pr.title,
+        state: pr.state,
+        is_draft: pr.is_draft,
         additions: pr.additions,
         deletions: pr.deletions,
+        head_ref: pr.head_ref_name,
         head_repo_owner,
         head_repo_name,
     })
@@ -1552,6 +1566,7 @@ mod tests {
             node_id: "node-1".to_string(),
             subject_id: Some("pr-1".to_string()),
             unread: false,
+            pinned: false,
             reason: "mention".to_string(),
             updated_at: "2024-01-01T00:00:00Z".to_string(),
             subject: Subject {
@@ -1577,6 +1592,7 @@ mod tests {
             MyPullRequest {
                 id: "pr-1".to_string(),
                 updated_at: "2024-01-02T00:00:00Z".to_string(),
+                pinned: false,
                 subject: Subject {
                     title: "My PR".to_string(),
                     url: "https://github.com/acme/widgets/pull/1".to_string(),
@@ -1598,6 +1614,7 @@ mod tests {
             MyPullRequest {
                 id: "pr-2".to_string(),
                 updated_at: "2024-01-03T00:00:00Z".to_string(),
+                pinned: false,
                 subject: Subject {
                     title: "Another PR".to_string(),
                     url: "https://github.com/acme/widgets/pull/2".to_string(),
@@ -0,0 +1,169 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::Result;
+
+use crate::store::{config_home, read_lines, write_lines};
+
+const HISTORY_RELATIVE_PATH: &str = "ghn/history.txt";
+const MAX_ENTRIES: usize = 500;
+const COMMAND_HISTORY: &str = "command history";
+
+pub fn history_path() -> Result<PathBuf> {
+    Ok(config_home()?.join(HISTORY_RELATIVE_PATH))
+}
+
+/// Submitted command lines, oldest first, saved across sessions.
+#[derive(Debug, Default)]
+pub struct History {
+    entries: Vec<String>,
+    // `None` keeps the history in memory only.
+    path: Option<PathBuf>,
+    // Entry shown while browsing with Up/Down, and the line that was being typed before.
+    browse: Option<(usize, String)>,
+}
+
+/// State of a Ctrl+R search; the command line shows the match while it's open.
+#[derive(Debug, Default)]
+pub struct ReverseSearch {
+    pub query: String,
+    pub found: Option<usize>,
+    /// Line to restore if the search is cancelled.
+    pub draft: String,
+}
+
+impl History {
+    pub fn load() -> Result<Self> {
+        let path = history_path()?;
+        Ok(Self {
+            entries: read_entries(&path)?,
+            path: Some(path),
+            browse: None,
+        })
+    }
+
+    pub fn last(&self) -> Option<&str> {
+        self.entries.last().map(String::as_str)
+    }
+
+    pub fn get(&self, index: usize) -> Option<&str> {
+        self.entries.get(index).map(String::as_str)
+    }
+
+    /// Records a submitted line, skipping blanks and immediate repeats.
+    pub fn push(&mut self, command: &str) -> Result<()> {
+        self.browse = None;
+        let command = command.trim();
+        if command.is_empty() || self.last() == Some(command) {
+            return Ok(());
+        }
+        self.entries.push(command.to_string());
+        if self.entries.len() > MAX_ENTRIES {
+            self.entries.drain(..self.entries.len() - MAX_ENTRIES);
+        }
+        match &self.path {
+            Some(path) => write_entries(path, &self.entries),
+            None => Ok(()),
+        }
+    }
+
+    /// Steps back from the line being typed, returning the entry to show.
+    pub fn older(&mut self, current: &str) -> Option<&str> {
+        let index = match &self.browse {
+            Some((0, _)) => return None,
+            Some((index, _)) => index - 1,
+            None => self.entries.len().checked_sub(1)?,
+        };
+        let draft = match self.browse.take() {
+            Some((_, draft)) => draft,
+            None => current.to_string(),
+        };
+        self.browse = Some((index, draft));
+        self.get(index)
+    }
+
+    /// Steps forward, ending on the line that was being typed before browsing.
+    pub fn newer(&mut self) -> Option<String> {
+        let (index, draft) = self.browse.take()?;
+        if index + 1 < self.entries.len() {
+            self.browse = Some((index + 1, draft));
+            return self.get(index + 1).map(str::to_string);
+        }
+        Some(draft)
+    }
+
+    pub fn stop_browsing(&mut self) {
+        self.browse = None;
+    }
+
+    /// Most recent entry before `before` containing `query`.
+    pub fn search(&self, query: &str, before: usize) -> Option<usize> {
+        let before = before.min(self.entries.len());
+        self.entries[..before]
+            .iter()
+            .rposition(|entry| entry.contains(query))
+    }
+}
+
+fn read_entries(path: &Path) -> Result<Vec<String>> {
+    Ok(read_lines(path, COMMAND_HISTORY)?
+        .into_iter()
+        .filter(|line| !line.trim().is_empty())
+        .collect())
+}
+
+fn write_entries(path: &Path, entries: &[String]) -> Result<()> {
+    write_lines(path, entries, COMMAND_HISTORY)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::History;
+
+    fn history(entries: &[&str]) -> History {
+        let mut history = History::default();
+        for entry in entries {
+            history.push(entry).unwrap();
+        }
+        history
+    }
+
+    #[test]
+    fn push_skips_blanks_and_repeats() {
+        let history = history(&["1o", "  ", "1o", "2d", "1o"]);
+        assert_eq!(history.entries, ["1o", "2d", "1o"]);
+    }
+
+    #[test]
+    fn browsing_returns_to_the_draft() {
+        let mut history = history(&["1o", "2d"]);
+        assert_eq!(history.older("3r"), Some("2d"));
+        assert_eq!(history.older("ignored"), Some("1o"));
+        assert_eq!(history.older("ignored"), None);
+        assert_eq!(history.newer().as_deref(), Some("2d"));
+        assert_eq!(history.newer().as_deref(), Some("3r"));
+        assert_eq!(history.newer(), None);
+    }
+
+    #[test]
+    fn search_walks_back_through_matches() {
+        let history = history(&["md cd", "1o", "fd #ci_activity"]);
+        assert_eq!(history.search("d", 3), Some(2));
+        assert_eq!(history.search("d", 2), Some(0));
+        assert_eq!(history.search("d", 0), None);
+        assert_eq!(history.search("zz", 3), None);
+    }
+
+    #[test]
+    fn writes_and_reloads_entries() {
+        let dir = std::env::temp_dir().join(format!("ghn-history-{}", std::process::id()));
+        let path = dir.join("history.txt");
+        let mut history = History {
+            path: Some(path.clone()),
+            ..History::default()
+        };
+        history.push("1o").unwrap();
+        history.push("2d").unwrap();
+        assert_eq!(super::read_entries(&path).unwrap(), ["1o", "2d"]);
+        std::fs::remove_dir_all(dir).ok();
+    }
+}
@@ -0,0 +1,464 @@
+use std::{
+    collections::{HashMap, HashSet},
+    io::Write,
+    process::{Command, Stdio},
+};
+
+use anyhow::{anyhow, Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::types::{CiStatus, MyPullRequest, Notification, ReviewStatus, SubjectStatus};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HookEventKind {
+    /// A thread appeared or has new activity.
+    NewNotification,
+    CiChanged,
+    ReviewChanged,
+    Merged,
+    Closed,
+}
+
+impl HookEventKind {
+    pub const ALL: [HookEventKind; 5] = [
+        HookEventKind::NewNotification,
+        HookEventKind::CiChanged,
+        HookEventKind::ReviewChanged,
+        HookEventKind::Merged,
+        HookEventKind::Closed,
+    ];
+}
+
+/// One change between two polls, delivered as JSON.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct HookEvent {
+    pub event: HookEventKind,
+    pub url: String,
+    pub repo: String,
+    pub title: String,
+    pub kind: String,
+    /// Notification thread id; absent for my PRs without a notification.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub thread_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+    /// Previous and new value for `ci_changed` and `review_changed`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub from: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub to: Option<String>,
+    pub updated_at: String,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct HookSettings {
+    /// Shell command that receives one JSON event per line on stdin.
+    pub command: Option<String>,
+    /// Endpoint that receives `{"events": [...]}` as a POST.
+    pub url: Option<String>,
+    pub events: Vec<HookEventKind>,
+}
+
+impl HookSettings {
+    pub fn is_enabled(&self) -> bool {
+        self.command.is_some() || self.url.is_some()
+    }
+}
+
+/// What the diff looks at for one subject URL.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct ItemState {
+    ci: Option<CiStatus>,
+    review: Option<ReviewStatus>,
+    merged: bool,
+    closed: bool,
+}
+
+/// Diffs each poll against the previous one.
+#[derive(Debug, Default)]
+pub struct HookTracker {
+    settings: HookSettings,
+    // Last `updated_at` per thread id, kept while GitHub still returns the thread.
+    threads: HashMap<String, String>,
+    items: HashMap<String, ItemState>,
+    // False until the first poll, which only records the inbox.
+    primed: bool,
+}
+
+impl HookTracker {
+    pub fn new(settings: HookSettings) -> Self {
+        Self {
+            settings,
+            ..Self::default()
+        }
+    }
+
+    pub fn settings(&self) -> &HookSettings {
+        &self.settings
+    }
+
+    pub fn update(
+        &mut self,
+        notifications: &[Notification],
+        my_prs: &[MyPullRequest],
+    ) -> Vec<HookEvent> {
+        if !self.settings.is_enabled() {
+            return Vec::new();
+        }
+
+        let mut items = HashMap::new();
+        let mut events = Vec::new();
+        let first_poll = !std::mem::replace(&mut self.primed, true);
+
+        for notification in notifications {
+            let event = |kind| HookEvent {
+                event: kind,
+                url: notification.subject.url.clone(),
+                repo: notification.repository.full_name.clone(),
+                title: notification.subject.title.clone(),
+                kind: notification.subject.kind.clone(),
+                thread_id: Some(notification.id.clone()),
+                reason: Some(notification.reason.clone()),
+                from: None,
+                to: None,
+                updated_at: notification.updated_at.clone(),
+            };
+            let previous = self
+                .threads
+                .insert(notification.id.clone(), notification.updated_at.clone());
+            if !first_poll && previous.as_ref() != Some(&notification.updated_at) {
+                events.push(event(HookEventKind::NewNotification));
+            }
+            let state = item_state(
+                &notification.subject.status,
+                notification.subject.ci_status,
+                notification.subject.review_status,
+            );
+            self.diff_item(&notification.subject.url, &state, &event, &mut events);
+            items.insert(notification.subject.url.clone(), state);
+        }
+
+        for pr in my_prs {
+            // Already diffed through its notification.
+            if items.contains_key(&pr.subject.url) {
+                continue;
+            }
+            let event = |kind| HookEvent {
+                event: kind,
+                url: pr.subject.url.clone(),
+                repo: pr.repository.full_name.clone(),
+                title: pr.subject.title.clone(),
+                kind: pr.subject.kind.clone(),
+                thread_id: None,
+                reason: None,
+                from: None,
+                to: None,
+                updated_at: pr.updated_at.clone(),
+            };
+            let state = item_state(
+                &pr.subject.status,
+                pr.subject.ci_status,
+                pr.subject.review_status,
+            );
+            self.diff_item(&pr.subject.url, &state, &event, &mut events);
+            items.insert(pr.subject.url.clone(), state);
+        }
+
+        // Keep state for items that dropped out of this poll, e.g. read threads in unread-only mode.
+        self.items.extend(items);
+        events.retain(|event| self.settings.events.contains(&event.event));
+        events
+    }
+
+    /// Forgets threads and items the latest fetch no longer returned, so a long-running daemon
+    /// doesn't keep one entry per thread ever seen. Pass the whole fetch, not just what
+    /// `update` was given: items only filtered out (ignored, or read under `unread_only`) keep
+    /// their state. A forgotten thread that GitHub returns again is reported as new.
+    pub fn retain_fetched(&mut self, notifications: &[Notification], my_prs: &[MyPullRequest]) {
+        let ids: HashSet<&str> = notifications.iter().map(|n| n.id.as_str()).collect();
+        let urls: HashSet<&str> = notifications
+            .iter()
+            .map(|n| n.subject.url.as_str())
+            .chain(my_prs.iter().map(|pr| pr.subject.url.as_str()))
+            .collect();
+        self.threads.retain(|id, _| ids.contains(id.as_str()));
+        self.items.retain(|url, _| urls.contains(url.as_str()));
+    }
+
+    fn diff_item(
+        &self,
+        url: &str,
+        state: &ItemState,
+        event: &dyn Fn(HookEventKind) -> HookEvent,
+        events: &mut Vec<HookEvent>,
+    ) {
+        let Some(previous) = self.items.get(url) else {
+            return;
+        };
+        if previous.ci != state.ci {
+            events.push(HookEvent {
+                from: previous.ci.map(|ci| ci_name(ci).to_string()),
+                to: state.ci.map(|ci| ci_name(ci).to_string()),
+                ..event(HookEventKind::CiChanged)
+            });
+        }
+        if previous.review != state.review {
+            events.push(HookEvent {
+                from: previous.review.map(|r| review_name(r).to_string()),
+                to: state.review.map(|r| review_name(r).to_string()),
+                ..event(HookEventKind::ReviewChanged)
+            });
+        }
+        if state.merged && !previous.merged {
+            events.push(event(HookEventKind::Merged));
+        } else if state.closed && !previous.closed {
+            events.push(event(HookEventKind::Closed));
+        }
+    }
+}
+
+fn item_state(
+    status: &[SubjectStatus],
+    ci: Option<CiStatus>,
+    review: Option<ReviewStatus>,
+) -> ItemState {
+    ItemState {
+        ci,
+        review,
+        merged: status.contains(&SubjectStatus::Merged),
+        closed: status.contains(&SubjectStatus::Closed),
+    }
+}
+
+fn ci_name(ci: CiStatus) -> &'static str {
+    match ci {
+        CiStatus::Success => "success",
+        CiStatus::Pending => "pending",
+        CiStatus::Failure => "failure",
+    }
+}
+
+fn review_name(review: ReviewStatus) -> &'static str {
+    match review {
+        ReviewStatus::Approved => "approved",
+        ReviewStatus::ChangesRequested => "changes_requested",
+        ReviewStatus::ReviewRequired => "review_required",
+    }
+}
+
+#[derive(Serialize)]
+struct HookPayload<'a> {
+    events: &'a [HookEvent],
+}
+
+/// Sends one poll's events to the configured command and endpoint.
+pub async fn deliver(
+    client: &reqwest::Client,
+    settings: &HookSettings,
+    events: Vec<HookEvent>,
+) -> Result<()> {
+    if let Some(url) = &settings.url {
+        let response = client
+            .post(url)
+            .json(&HookPayload { events: &events })
+            .send()
+            .await
+            .with_context(|| format!("failed to post events to {}", url))?;
+        if !response.status().is_success() {
+            return Err(anyhow!(
+                "hook endpoint {} returned {}",
+                url,
+                response.status()
+            ));
+        }
+    }
+    if let Some(command) = settings.command.clone() {
+        tokio::task::spawn_blocking(move || run_command(&command, &events)).await??;
+    }
+    Ok(())
+}
+
+fn run_command(command: &str, events: &[HookEvent]) -> Result<()> {
+    let mut input = Vec::new();
+    for event in events {
+        serde_json::to_writer(&mut input, event)?;
+        input.push(b'\n');
+    }
+
+    let mut child = Command::new("sh")
+        .arg("-c")
+        .arg(command)
+        .stdin(Stdio::piped())
+        // Output would draw over the TUI.
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .context("failed to run hooks.command")?;
+    if let Some(mut stdin) = child.stdin.take() {
+        // A hook that ignores its input may exit before reading it; that's fine.
+        let _ = stdin.write_all(&input);
+    }
+    let status = child.wait().context("failed to wait for hooks.command")?;
+    if !status.success() {
+        return Err(anyhow!("hooks.command exited with {}", status));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{HookEventKind, HookSettings, HookTracker};
+    use crate::types::{
+        CiStatus, MyPullRequest, Notification, Repository, ReviewStatus, Subject, SubjectStatus,
+    };
+
+    fn subject(number: u32) -> Subject {
+        Subject {
+            title: format!("PR {number}"),
+            url: format!("https://github.com/acme/widgets/pull/{number}"),
+            kind: "PullRequest".to_string(),
+            author: None,
+            status: Vec::new(),
+            ci_status: None,
+            review_status: None,
+            merge_state_status: None,
+            head_ref: None,
+        }
+    }
+
+    fn repository() -> Repository {
+        Repository {
+            name: "widgets".to_string(),
+            full_name: "acme/widgets".to_string(),
+            merge_settings: None,
+        }
+    }
+
+    fn notification(number: u32, updated_at: &str) -> Notification {
+        Notification {
+            id: number.to_string(),
+            node_id: format!("node-{number}"),
+            subject_id: None,
+            unread: true,
+            pinned: false,
+            reason: "review_requested".to_string(),
+            updated_at: updated_at.to_string(),
+            subject: subject(number),
+            repository: repository(),
+            url: String::new(),
+        }
+    }
+
+    fn my_pr(number: u32) -> MyPullRequest {
+        MyPullRequest {
+            id: format!("pr-{number}"),
+            updated_at: "2024-01-01T00:00:00Z".to_string(),
+            pinned: false,
+            subject: subject(number),
+            repository: repository(),
+            url: String::new(),
+        }
+    }
+
+    fn tracker(events: &[HookEventKind]) -> HookTracker {
+        HookTracker::new(HookSettings {
+            command: Some("cat".to_string()),
+            url: None,
+            events: events.to_vec(),
+        })
+    }
+
+    #[test]
+    fn first_poll_is_silent_then_new_threads_emit() {
+        let mut tracker = tracker(&HookEventKind::ALL);
+        let first = notification(1, "2024-01-01T00:00:00Z");
+        assert!(tracker.update(std::slice::from_ref(&first), &[]).is_empty());
+
+        let second = notification(2, "2024-01-01T00:00:00Z");
+        let events = tracker.update(&[first.clone(), second], &[]);
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].event, HookEventKind::NewNotification);
+        assert_eq!(events[0].thread_id.as_deref(), Some("2"));
+
+        let bumped = notification(1, "2024-01-02T00:00:00Z");
+        let events = tracker.update(&[bumped], &[]);
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].thread_id.as_deref(), Some("1"));
+    }
+
+    #[test]
+    fn threads_returning_unchanged_stay_silent() {
+        let mut tracker = tracker(&HookEventKind::ALL);
+        let thread = notification(1, "2024-01-01T00:00:00Z");
+        tracker.update(std::slice::from_ref(&thread), &[]);
+        // Read in unread-only mode, or hidden by a snooze, then back with no new activity.
+        assert!(tracker.update(&[], &[]).is_empty());
+        assert!(tracker.update(&[thread], &[]).is_empty());
+    }
+
+    #[test]
+    fn threads_gone_from_the_fetch_are_forgotten_and_new_when_back() {
+        let mut tracker = tracker(&HookEventKind::ALL);
+        let thread = notification(1, "2024-01-01T00:00:00Z");
+        tracker.update(std::slice::from_ref(&thread), &[]);
+
+        // Still fetched but filtered out of the inbox: state is kept, so no repeat.
+        assert!(tracker.update(&[], &[]).is_empty());
+        tracker.retain_fetched(std::slice::from_ref(&thread), &[]);
+        assert_eq!(tracker.threads.len(), 1);
+        assert!(tracker
+            .update(std::slice::from_ref(&thread), &[])
+            .is_empty());
+
+        // Gone from GitHub's response entirely (e.g. marked done elsewhere): forgotten, so it
+        // fires `new_notification` again if GitHub ever returns it.
+        tracker.update(&[], &[]);
+        tracker.retain_fetched(&[], &[]);
+        assert!(tracker.threads.is_empty());
+        assert!(tracker.items.is_empty());
+        let events = tracker.update(&[thread], &[]);
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].event, HookEventKind::NewNotification);
+    }
+
+    #[test]
+    fn pr_state_changes_emit_transitions() {
+        let mut tracker = tracker(&HookEventKind::ALL);
+        let mut pr = my_pr(7);
+        pr.subject.ci_status = Some(CiStatus::Pending);
+        tracker.update(&[], std::slice::from_ref(&pr));
+
+        pr.subject.ci_status = Some(CiStatus::Failure);
+        pr.subject.review_status = Some(ReviewStatus::Approved);
+        let events = tracker.update(&[], std::slice::from_ref(&pr));
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0].event, HookEventKind::CiChanged);
+        assert_eq!(events[0].from.as_deref(), Some("pending"));
+        assert_eq!(events[0].to.as_deref(), Some("failure"));
+        assert_eq!(events[1].event, HookEventKind::ReviewChanged);
+        assert_eq!(events[1].to.as_deref(), Some("approved"));
+
+        pr.subject.status = vec![SubjectStatus::Merged];
+        let events = tracker.update(&[], std::slice::from_ref(&pr));
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].event, HookEventKind::Merged);
+        assert!(tracker.update(&[], &[pr]).is_empty());
+    }
+
+    #[test]
+    fn events_are_filtered_and_serialized() {
+        let mut tracker = tracker(&[HookEventKind::Closed]);
+        let first = notification(1, "2024-01-01T00:00:00Z");
+        tracker.update(std::slice::from_ref(&first), &[]);
+
+        let mut closed = notification(1, "2024-01-02T00:00:00Z");
+        closed.subject.status = vec![SubjectStatus::Closed];
+        let events = tracker.update(&[closed], &[]);
+        assert_eq!(events.len(), 1);
+        let json = serde_json::to_value(&events[0]).unwrap();
+        assert_eq!(json["event"], "closed");
+        assert_eq!(json["thread_id"], "1");
+        assert!(json.get("from").is_none());
+    }
+}
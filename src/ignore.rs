@@ -1,14 +1,19 @@
 use std::{
-    collections::HashSet,
-    fs::{self, File, OpenOptions},
-    io::{BufRead, BufReader, Write},
     path::{Path, PathBuf},
     sync::{Mutex, OnceLock},
 };
 
-use anyhow::{anyhow, Context, Result};
+use anyhow::{anyhow, Result};
+use chrono::{NaiveDate, NaiveDateTime};
+use regex::Regex;
+
+use crate::{
+    store::{append_line, config_home, read_lines, write_lines},
+    types::{MyPullRequest, Notification},
+};
 
 const IGNORE_RELATIVE_PATH: &str = "ghn/ignores.txt";
+const IGNORE_LIST: &str = "ignore list";
 
 static IGNORE_LOCK: OnceLock<Mutex<()>> = OnceLock::new();
 
@@ -16,163 +21,401 @@ fn ignore_lock() -> &'static Mutex<()> {
     IGNORE_LOCK.get_or_init(|| Mutex::new(()))
 }
 
-fn config_home() -> Result<PathBuf> {
-    if let Ok(value) = std::env::var("XDG_CONFIG_HOME") {
-        let trimmed = value.trim();
-        if !trimmed.is_empty() {
-            return Ok(PathBuf::from(trimmed));
+#[derive(Debug, Clone)]
+pub enum IgnoreMatcher {
+    Url(String),
+    Repo(String),
+    Author(String),
+    Title(Regex),
+}
+
+impl IgnoreMatcher {
+    fn parse(value: &str) -> Result<Self> {
+        if let Some(pattern) = value.strip_prefix("repo:") {
+            return Ok(Self::Repo(non_empty(pattern, "repo")?.to_string()));
+        }
+        if let Some(login) = value.strip_prefix("author:") {
+            return Ok(Self::Author(non_empty(login, "author")?.to_string()));
         }
+        if let Some(pattern) = value.strip_prefix("title:") {
+            let pattern = non_empty(pattern, "title")?;
+            let regex = Regex::new(pattern)
+                .map_err(|err| anyhow!("invalid title pattern '{}': {}", pattern, err))?;
+            return Ok(Self::Title(regex));
+        }
+        let url = value.strip_prefix("url:").unwrap_or(value);
+        Ok(Self::Url(non_empty(url, "url")?.to_string()))
     }
 
-    if let Ok(value) = std::env::var("APPDATA") {
-        let trimmed = value.trim();
-        if !trimmed.is_empty() {
-            return Ok(PathBuf::from(trimmed));
+    pub fn label(&self) -> String {
+        match self {
+            Self::Url(url) => url.clone(),
+            Self::Repo(pattern) => format!("repo:{}", pattern),
+            Self::Author(login) => format!("author:{}", login),
+            Self::Title(regex) => format!("title:{}", regex.as_str()),
         }
     }
+}
+
+fn non_empty<'a>(value: &'a str, kind: &str) -> Result<&'a str> {
+    let trimmed = value.trim();
+    if trimmed.is_empty() {
+        return Err(anyhow!("empty {} rule", kind));
+    }
+    Ok(trimmed)
+}
 
-    if let Ok(value) = std::env::var("HOME") {
-        let trimmed = value.trim();
-        if !trimmed.is_empty() {
-            return Ok(PathBuf::from(trimmed).join(".config"));
+const EXPIRY_TIME_FORMAT: &str = "%Y-%m-%dT%H:%MZ";
+
+/// When a rule stops applying: after a whole day, or at a UTC time for snoozes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Expiry {
+    Date(NaiveDate),
+    Time(NaiveDateTime),
+}
+
+impl Expiry {
+    fn parse(value: &str) -> Result<Self> {
+        if let Ok(time) = NaiveDateTime::parse_from_str(value, EXPIRY_TIME_FORMAT) {
+            return Ok(Self::Time(time));
         }
+        NaiveDate::parse_from_str(value, "%Y-%m-%d")
+            .map(Self::Date)
+            .map_err(|_| {
+                anyhow!(
+                    "invalid expiry '{}' (use YYYY-MM-DD or YYYY-MM-DDTHH:MMZ)",
+                    value
+                )
+            })
     }
 
-    if let Ok(value) = std::env::var("USERPROFILE") {
-        let trimmed = value.trim();
-        if !trimmed.is_empty() {
-            return Ok(PathBuf::from(trimmed).join(".config"));
+    /// Whether the rule still applies at `now` (UTC).
+    pub fn is_after(self, now: NaiveDateTime) -> bool {
+        match self {
+            Self::Date(date) => now.date() <= date,
+            Self::Time(time) => now < time,
         }
     }
+}
 
-    Err(anyhow!(
-        "unable to resolve config directory (set XDG_CONFIG_HOME or HOME)"
-    ))
+impl std::fmt::Display for Expiry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Date(date) => write!(f, "{}", date),
+            Self::Time(time) => write!(f, "{} UTC", time.format("%Y-%m-%d %H:%M")),
+        }
+    }
 }
 
-pub fn ignores_path() -> Result<PathBuf> {
-    Ok(config_home()?.join(IGNORE_RELATIVE_PATH))
+/// One line of the ignore list: `<matcher> [until:YYYY-MM-DD[THH:MMZ]] [# reason]`.
+#[derive(Debug, Clone)]
+pub struct IgnoreRule {
+    /// The rule as written in the file, used to find it again when editing.
+    pub line: String,
+    pub matcher: IgnoreMatcher,
+    pub reason: Option<String>,
+    pub expires: Option<Expiry>,
 }
 
-pub fn load_ignored_prs() -> Result<HashSet<String>> {
-    let path = ignores_path()?;
-    read_ignored_prs(&path)
+impl IgnoreRule {
+    pub fn parse(line: &str) -> Result<Self> {
+        let (rule, reason) = match line.split_once(" # ") {
+            Some((rule, reason)) => (rule.trim(), Some(reason.trim().to_string())),
+            None => (line.trim(), None),
+        };
+        let reason = reason.filter(|value| !value.is_empty());
+
+        let (matcher, expires) = match rule.rsplit_once(char::is_whitespace) {
+            Some((matcher, last)) if last.starts_with("until:") => {
+                let expiry = Expiry::parse(&last["until:".len()..])?;
+                (matcher.trim(), Some(expiry))
+            }
+            _ => (rule, None),
+        };
+
+        Ok(Self {
+            line: line.trim().to_string(),
+            matcher: IgnoreMatcher::parse(matcher)?,
+            reason,
+            expires,
+        })
+    }
+
+    pub fn is_active(&self, now: NaiveDateTime) -> bool {
+        match self.expires {
+            Some(expires) => expires.is_after(now),
+            None => true,
+        }
+    }
+
+    pub fn matches(&self, item: &IgnoreCandidate<'_>) -> bool {
+        match &self.matcher {
+            IgnoreMatcher::Url(url) => item.url == url,
+            IgnoreMatcher::Repo(pattern) => glob_match(pattern, item.repo_full_name),
+            IgnoreMatcher::Author(login) => {
+                item.author.is_some_and(|author| glob_match(login, author))
+            }
+            IgnoreMatcher::Title(regex) => regex.is_match(item.title),
+        }
+    }
 }
 
-fn read_ignored_prs(path: &Path) -> Result<HashSet<String>> {
-    let file = match File::open(path) {
-        Ok(file) => file,
-        Err(err) if err.kind() == std::io::ErrorKind::NotFound => {
-            return Ok(HashSet::new());
+/// The fields of a notification or PR that ignore rules can match on.
+pub struct IgnoreCandidate<'a> {
+    pub url: &'a str,
+    pub repo_full_name: &'a str,
+    pub author: Option<&'a str>,
+    pub title: &'a str,
+}
+
+impl<'a> IgnoreCandidate<'a> {
+    pub fn from_notification(notification: &'a Notification) -> Self {
+        Self {
+            url: &notification.subject.url,
+            repo_full_name: &notification.repository.full_name,
+            author: notification.subject.author.as_deref(),
+            title: &notification.subject.title,
         }
-        Err(err) => {
-            return Err(err)
-                .with_context(|| format!("failed to open ignore list: {}", path.display()));
+    }
+
+    pub fn from_pull_request(pr: &'a MyPullRequest) -> Self {
+        Self {
+            url: &pr.url,
+            repo_full_name: &pr.repository.full_name,
+            author: pr.subject.author.as_deref(),
+            title: &pr.subject.title,
         }
-    };
+    }
+}
 
-    let reader = BufReader::new(file);
-    let mut ignores = HashSet::new();
+#[derive(Debug, Clone, Default)]
+pub struct IgnoreList {
+    rules: Vec<IgnoreRule>,
+    // Lines that failed to parse; they're skipped so one typo doesn't disable every rule.
+    warnings: Vec<String>,
+}
 
-    for line in reader.lines() {
-        let line = line.context("failed to read ignore list")?;
-        let trimmed = line.trim();
-        if trimmed.is_empty() || trimmed.starts_with('#') {
-            continue;
+impl IgnoreList {
+    pub fn parse(contents: &str) -> Self {
+        let mut list = Self::default();
+        for (idx, line) in contents.lines().enumerate() {
+            let trimmed = line.trim();
+            if trimmed.is_empty() || trimmed.starts_with('#') {
+                continue;
+            }
+            match IgnoreRule::parse(trimmed) {
+                Ok(rule) => list.rules.push(rule),
+                Err(err) => list
+                    .warnings
+                    .push(format!("ignore list line {}: {:#}", idx + 1, err)),
+            }
+        }
+        list
+    }
+
+    pub fn rules(&self) -> &[IgnoreRule] {
+        &self.rules
+    }
+
+    /// Skipped lines with their line numbers, for the status bar.
+    pub fn warnings(&self) -> &[String] {
+        &self.warnings
+    }
+
+    pub fn len(&self) -> usize {
+        self.rules.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// Whether an exact URL rule exists, regardless of expiry.
+    pub fn contains(&self, url: &str) -> bool {
+        self.rules
+            .iter()
+            .any(|rule| matches!(&rule.matcher, IgnoreMatcher::Url(value) if value == url))
+    }
+
+    pub fn insert(&mut self, url: String) {
+        if !self.contains(&url) {
+            self.rules.push(IgnoreRule {
+                line: url.clone(),
+                matcher: IgnoreMatcher::Url(url),
+                reason: None,
+                expires: None,
+            });
         }
-        ignores.insert(trimmed.to_string());
     }
 
-    Ok(ignores)
+    pub fn ignores(&self, item: &IgnoreCandidate<'_>, now: NaiveDateTime) -> bool {
+        self.rules
+            .iter()
+            .any(|rule| rule.is_active(now) && rule.matches(item))
+    }
+
+    /// Counts, per rule, how many of `items` it currently hides.
+    pub fn hidden_counts(&self, items: &[IgnoreCandidate<'_>], now: NaiveDateTime) -> Vec<usize> {
+        self.rules
+            .iter()
+            .map(|rule| {
+                if !rule.is_active(now) {
+                    return 0;
+                }
+                items.iter().filter(|item| rule.matches(item)).count()
+            })
+            .collect()
+    }
+}
+
+/// Case-insensitive glob match supporting `*` (any run) and `?` (one char).
+fn glob_match(pattern: &str, value: &str) -> bool {
+    let pattern: Vec<char> = pattern.to_lowercase().chars().collect();
+    let value: Vec<char> = value.to_lowercase().chars().collect();
+    let (mut p, mut v) = (0usize, 0usize);
+    let mut backtrack: Option<(usize, usize)> = None;
+
+    while v < value.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == value[v]) {
+            p += 1;
+            v += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            backtrack = Some((p, v));
+            p += 1;
+        } else if let Some((star, matched)) = backtrack {
+            p = star + 1;
+            v = matched + 1;
+            backtrack = Some((star, matched + 1));
+        } else {
+            return false;
+        }
+    }
+
+    pattern[p..].iter().all(|ch| *ch == '*')
+}
+
+pub fn ignores_path() -> Result<PathBuf> {
+    Ok(config_home()?.join(IGNORE_RELATIVE_PATH))
+}
+
+pub fn load_ignored_prs() -> Result<IgnoreList> {
+    let path = ignores_path()?;
+    read_ignored_prs(&path)
+}
+
+fn read_ignored_prs(path: &Path) -> Result<IgnoreList> {
+    let lines = read_lines(path, IGNORE_LIST)?;
+    Ok(IgnoreList::parse(&lines.join("\n")))
 }
 
 pub fn append_ignored_pr(url: &str) -> Result<bool> {
+    append_ignore_rule(url)
+}
+
+/// Appends `line` unless the same rule is already present.
+pub fn append_ignore_rule(line: &str) -> Result<bool> {
+    let line = line.trim();
+    let rule = IgnoreRule::parse(line)?;
+
     // Serialize read/append to keep the file consistent when multiple actions run concurrently.
     let _guard = ignore_lock().lock().expect("ignore list lock poisoned");
     let path = ignores_path()?;
     let existing = read_ignored_prs(&path)?;
 
-    if existing.contains(url) {
+    let duplicate = match &rule.matcher {
+        IgnoreMatcher::Url(url) => existing.contains(url),
+        _ => existing.rules().iter().any(|rule| rule.line == line),
+    };
+    if duplicate {
         return Ok(false);
     }
 
-    if let Some(parent) = path.parent() {
-        fs::create_dir_all(parent).with_context(|| {
-            format!(
-                "failed to create ignore list directory: {}",
-                parent.display()
-            )
-        })?;
-    }
-
-    let mut file = OpenOptions::new()
-        .create(true)
-        .append(true)
-        .open(&path)
-        .with_context(|| format!("failed to open ignore list: {}", path.display()))?;
-
-    writeln!(file, "{}", url)
-        .with_context(|| format!("failed to write ignore list: {}", path.display()))?;
-
+    append_line(&path, line, IGNORE_LIST)?;
     Ok(true)
 }
 
+/// Hides `url` until `until` (UTC), replacing any earlier snooze of it.
+pub fn snooze_url(url: &str, until: NaiveDateTime) -> Result<()> {
+    let _guard = ignore_lock().lock().expect("ignore list lock poisoned");
+    let path = ignores_path()?;
+    let is_snooze = |line: &str| {
+        IgnoreRule::parse(line).is_ok_and(|rule| {
+            matches!(rule.matcher, IgnoreMatcher::Url(ref value) if value == url)
+                && matches!(rule.expires, Some(Expiry::Time(_)))
+        })
+    };
+    rewrite_matching_lines(&path, is_snooze, None)?;
+
+    let line = format!(
+        "{} until:{} # snoozed",
+        url,
+        until.format(EXPIRY_TIME_FORMAT)
+    );
+    append_line(&path, &line, IGNORE_LIST)
+}
+
 pub fn remove_ignored_pr(url: &str) -> Result<bool> {
     // Serialize read/write to keep the file consistent when multiple actions run concurrently.
     let _guard = ignore_lock().lock().expect("ignore list lock poisoned");
     let path = ignores_path()?;
+    rewrite_matching_lines(
+        &path,
+        |line| {
+            IgnoreRule::parse(line)
+                .is_ok_and(|rule| matches!(rule.matcher, IgnoreMatcher::Url(value) if value == url))
+        },
+        None,
+    )
+}
 
-    let file = match File::open(&path) {
-        Ok(file) => file,
-        Err(err) if err.kind() == std::io::ErrorKind::NotFound => {
-            return Ok(false);
-        }
-        Err(err) => {
-            return Err(err)
-                .with_context(|| format!("failed to open ignore list: {}", path.display()));
-        }
-    };
+/// Replaces the rule written as `line` with `replacement`, or deletes it when `None`.
+pub fn replace_ignore_rule(line: &str, replacement: Option<&str>) -> Result<bool> {
+    let replacement = replacement.map(str::trim);
+    if let Some(replacement) = replacement {
+        IgnoreRule::parse(replacement)?;
+    }
+
+    let _guard = ignore_lock().lock().expect("ignore list lock poisoned");
+    let path = ignores_path()?;
+    let target = line.trim();
+    rewrite_matching_lines(&path, |line| line.trim() == target, replacement)
+}
 
-    let reader = BufReader::new(file);
+fn rewrite_matching_lines(
+    path: &Path,
+    is_target: impl Fn(&str) -> bool,
+    replacement: Option<&str>,
+) -> Result<bool> {
     let mut lines = Vec::new();
-    let mut removed = false;
+    let mut changed = false;
 
-    for line in reader.lines() {
-        let line = line.context("failed to read ignore list")?;
-        if line.trim() == url {
-            removed = true;
+    for line in read_lines(path, IGNORE_LIST)? {
+        if is_target(&line) {
+            changed = true;
+            if let Some(replacement) = replacement {
+                lines.push(replacement.to_string());
+            }
             continue;
         }
         lines.push(line);
     }
 
-    if !removed {
+    if !changed {
         return Ok(false);
     }
 
-    if let Some(parent) = path.parent() {
-        fs::create_dir_all(parent).with_context(|| {
-            format!(
-                "failed to create ignore list directory: {}",
-                parent.display()
-            )
-        })?;
-    }
-
-    let mut file = File::create(&path)
-        .with_context(|| format!("failed to open ignore list: {}", path.display()))?;
-
-    for line in lines {
-        writeln!(file, "{}", line)
-            .with_context(|| format!("failed to write ignore list: {}", path.display()))?;
-    }
-
+    write_lines(path, &lines, IGNORE_LIST)?;
     Ok(true)
 }
 
 #[cfg(test)]
 mod tests {
-    use super::{append_ignored_pr, ignores_path, load_ignored_prs, remove_ignored_pr};
+    use super::{
+        append_ignore_rule, append_ignored_pr, glob_match, ignores_path, load_ignored_prs,
+        remove_ignored_pr, replace_ignore_rule, snooze_url, Expiry, IgnoreCandidate, IgnoreList,
+        IgnoreMatcher, IgnoreRule,
+    };
+    use chrono::{NaiveDate, NaiveDateTime};
     use std::ffi::OsString;
     use std::fs;
     use std::path::PathBuf;
@@ -238,6 +481,20 @@ mod tests {
         assert_eq!(count, 1);
     }
 
+    #[test]
+    fn append_ignore_rule_validates_and_dedupes() {
+        let _guard = ENV_LOCK.lock().unwrap();
+        let _env = TempConfigEnv::new();
+
+        assert!(append_ignore_rule("repo:acme/docs-* # noisy").unwrap());
+        assert!(!append_ignore_rule("  repo:acme/docs-* # noisy ").unwrap());
+        assert!(append_ignore_rule("title:(").is_err());
+
+        let ignores = load_ignored_prs().unwrap();
+        assert_eq!(ignores.len(), 1);
+        assert_eq!(ignores.rules()[0].reason.as_deref(), Some("noisy"));
+    }
+
     #[test]
     fn load_ignored_prs_returns_empty_when_missing() {
         let _guard = ENV_LOCK.lock().unwrap();
@@ -314,4 +571,140 @@ mod tests {
 
         assert!(!remove_ignored_pr("https://github.com/acme/widgets/pull/1").unwrap());
     }
+
+    fn at(year: i32, month: u32, day: u32, hour: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(year, month, day)
+            .and_then(|date| date.and_hms_opt(hour, 0, 0))
+            .unwrap()
+    }
+
+    fn candidate<'a>(
+        repo: &'a str,
+        author: Option<&'a str>,
+        title: &'a str,
+    ) -> IgnoreCandidate<'a> {
+        IgnoreCandidate {
+            url: "https://github.com/acme/widgets/pull/7",
+            repo_full_name: repo,
+            author,
+            title,
+        }
+    }
+
+    #[test]
+    fn parse_rule_reads_reason_and_expiry() {
+        let rule = IgnoreRule::parse("repo:acme/* until:2024-02-01 # noisy org").unwrap();
+        assert!(matches!(rule.matcher, IgnoreMatcher::Repo(ref value) if value == "acme/*"));
+        assert_eq!(rule.reason.as_deref(), Some("noisy org"));
+        assert_eq!(
+            rule.expires,
+            NaiveDate::from_ymd_opt(2024, 2, 1).map(Expiry::Date)
+        );
+        let rule = IgnoreRule::parse("repo:acme/* until:2024-02-01T14:30Z").unwrap();
+        assert_eq!(rule.expires.unwrap().to_string(), "2024-02-01 14:30 UTC");
+
+        let rule = IgnoreRule::parse("https://github.com/acme/widgets/pull/1").unwrap();
+        assert!(matches!(rule.matcher, IgnoreMatcher::Url(_)));
+        assert!(rule.reason.is_none());
+        assert!(rule.expires.is_none());
+    }
+
+    #[test]
+    fn parse_skips_invalid_rules_with_line_numbers() {
+        let list = IgnoreList::parse(
+            "# header\ntitle:(unclosed\nhttps://github.com/acme/widgets/pull/1\nrepo:\n",
+        );
+        assert_eq!(list.len(), 1);
+        assert!(list.contains("https://github.com/acme/widgets/pull/1"));
+        assert_eq!(list.warnings().len(), 2);
+        assert!(list.warnings()[0].starts_with("ignore list line 2:"));
+        assert!(list.warnings()[1].starts_with("ignore list line 4:"));
+        assert!(IgnoreRule::parse("repo:acme/* until:tomorrow").is_err());
+        assert!(IgnoreRule::parse("author:").is_err());
+    }
+
+    #[test]
+    fn rules_match_repo_author_and_title() {
+        let list =
+            IgnoreList::parse("repo:acme/docs-*\nauthor:dependabot[bot]\ntitle:^chore\\(deps\\)\n");
+        let today = at(2024, 1, 1, 12);
+
+        assert!(list.ignores(&candidate("Acme/docs-site", None, "Fix"), today));
+        assert!(list.ignores(
+            &candidate("acme/widgets", Some("dependabot[bot]"), "Bump"),
+            today
+        ));
+        assert!(list.ignores(&candidate("acme/widgets", None, "chore(deps): bump"), today));
+        assert!(!list.ignores(&candidate("acme/widgets", Some("octocat"), "Fix"), today));
+    }
+
+    #[test]
+    fn expired_rules_are_inactive() {
+        let list = IgnoreList::parse("repo:acme/widgets until:2024-01-31\n");
+        let item = candidate("acme/widgets", None, "Fix");
+        assert!(list.ignores(&item, at(2024, 1, 31, 23)));
+        assert!(!list.ignores(&item, at(2024, 2, 1, 0)));
+
+        let list = IgnoreList::parse("repo:acme/widgets until:2024-01-31T10:00Z\n");
+        assert!(list.ignores(&item, at(2024, 1, 31, 9)));
+        assert!(!list.ignores(&item, at(2024, 1, 31, 10)));
+    }
+
+    #[test]
+    fn glob_match_handles_wildcards() {
+        assert!(glob_match("acme/*", "acme/widgets"));
+        assert!(glob_match("*/widgets", "octo/widgets"));
+        assert!(glob_match("acme/w?dgets", "acme/widgets"));
+        assert!(!glob_match("acme/*", "other/widgets"));
+        assert!(!glob_match("acme", "acme/widgets"));
+    }
+
+    #[test]
+    fn replace_ignore_rule_edits_and_removes_lines() {
+        let _guard = ENV_LOCK.lock().unwrap();
+        let _env = TempConfigEnv::new();
+
+        let path = ignores_path().unwrap();
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(&path, "# keep\nrepo:acme/* # noisy\nauthor:bot\n").unwrap();
+
+        assert!(replace_ignore_rule("repo:acme/* # noisy", Some("repo:acme/docs")).unwrap());
+        assert!(replace_ignore_rule("author:bot", None).unwrap());
+        assert!(!replace_ignore_rule("author:bot", None).unwrap());
+        assert!(replace_ignore_rule("repo:acme/docs", Some("title:(")).is_err());
+
+        let contents = fs::read_to_string(&path).unwrap();
+        assert_eq!(contents, "# keep\nrepo:acme/docs\n");
+    }
+
+    #[test]
+    fn snooze_url_replaces_earlier_snoozes() {
+        let _guard = ENV_LOCK.lock().unwrap();
+        let _env = TempConfigEnv::new();
+
+        let url = "https://github.com/acme/widgets/pull/1";
+        assert!(append_ignore_rule("repo:acme/docs").unwrap());
+        snooze_url(url, at(2024, 1, 1, 10)).unwrap();
+        snooze_url(url, at(2024, 1, 1, 12)).unwrap();
+
+        let contents = fs::read_to_string(ignores_path().unwrap()).unwrap();
+        assert_eq!(
+            contents,
+            format!(
+                "repo:acme/docs\n{} until:2024-01-01T12:00Z # snoozed\n",
+                url
+            )
+        );
+    }
+
+    #[test]
+    fn hidden_counts_skip_expired_rules() {
+        let list =
+            IgnoreList::parse("repo:acme/*\nrepo:acme/widgets until:2023-12-31\nauthor:nobody\n");
+        let items = [
+            candidate("acme/widgets", None, "Fix"),
+            candidate("acme/docs", None, "Docs"),
+        ];
+        assert_eq!(list.hidden_counts(&items, at(2024, 1, 1, 0)), vec![2, 0, 0]);
+    }
 }
@@ -0,0 +1,125 @@
+use std::collections::HashMap;
+
+use ratatui::style::{Color, Style};
+use tui_textarea::{CursorMove, TextArea};
+
+use crate::github::PrettyPullRequest;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RuleDetail {
+    Loading,
+    Loaded { title: String, state: String },
+    Failed(String),
+}
+
+impl RuleDetail {
+    pub fn from_pull_request(pr: &PrettyPullRequest) -> Self {
+        Self::Loaded {
+            title: pr.title.clone(),
+            state: pull_request_state_label(pr).to_string(),
+        }
+    }
+}
+
+/// Overlay state for browsing and editing `ignores.txt`.
+#[derive(Default)]
+pub struct IgnoreManager {
+    pub selected: usize,
+    pub editor: Option<TextArea<'static>>,
+    pub details: HashMap<String, RuleDetail>,
+}
+
+impl IgnoreManager {
+    pub fn select_next(&mut self, len: usize) {
+        if self.selected + 1 < len {
+            self.selected += 1;
+        }
+    }
+
+    pub fn select_prev(&mut self) {
+        self.selected = self.selected.saturating_sub(1);
+    }
+
+    pub fn clamp_selection(&mut self, len: usize) {
+        self.selected = self.selected.min(len.saturating_sub(1));
+    }
+
+    pub fn start_edit(&mut self, line: &str) {
+        let mut editor = TextArea::new(vec![line.to_string()]);
+        editor.set_style(Style::default().bg(Color::DarkGray));
+        editor.move_cursor(CursorMove::End);
+        self.editor = Some(editor);
+    }
+
+    pub fn editor_text(&self) -> Option<String> {
+        self.editor
+            .as_ref()
+            .map(|editor| editor.lines().join(" ").trim().to_string())
+    }
+}
+
+fn pull_request_state_label(pr: &PrettyPullRequest) -> &'static str {
+    match pr.state.as_str() {
+        "MERGED" => "Merged",
+        "CLOSED" => "Closed",
+        _ if pr.is_draft => "Draft",
+        _ => "Open",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{IgnoreManager, RuleDetail};
+    use crate::github::PrettyPullRequest;
+
+    fn sample_pr(state: &str, is_draft: bool) -> PrettyPullRequest {
+        PrettyPullRequest {
+            url: "https://github.com/acme/widgets/pull/1".to_string(),
+            title: "Add feature".to_string(),
+            state: state.to_string(),
+            is_draft,
+            additions: 1,
+            deletions: 1,
+            head_ref: "feature".to_string(),
+            head_repo_owner: "acme".to_string(),
+            head_repo_name: "widgets".to_string(),
+        }
+    }
+
+    #[test]
+    fn selection_stays_in_bounds() {
+        let mut manager = IgnoreManager::default();
+        manager.select_prev();
+        assert_eq!(manager.selected, 0);
+        manager.select_next(2);
+        manager.select_next(2);
+        assert_eq!(manager.selected, 1);
+        manager.clamp_selection(1);
+        assert_eq!(manager.selected, 0);
+    }
+
+    #[test]
+    fn detail_labels_pull_request_state() {
+        let detail = RuleDetail::from_pull_request(&sample_pr("OPEN", true));
+        assert_eq!(
+            detail,
+            RuleDetail::Loaded {
+                title: "Add feature".to_string(),
+                state: "Draft".to_string(),
+            }
+        );
+        let detail = RuleDetail::from_pull_request(&sample_pr("MERGED", false));
+        assert!(matches!(detail, RuleDetail::Loaded { state, .. } if state == "Merged"));
+    }
+
+    #[test]
+    fn editor_text_joins_and_trims() {
+        let mut manager = IgnoreManager::default();
+        assert!(manager.editor_text().is_none());
+        manager.start_edit("  repo:acme/* # noisy ");
+        assert_eq!(
+            manager.editor_text().as_deref(),
+            Some("repo:acme/* # noisy")
+        );
+    }
+}
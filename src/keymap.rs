@@ -0,0 +1,530 @@
+use std::collections::{BTreeMap, HashMap};
+
+use anyhow::{anyhow, Context, Result};
+
+use crate::types::Action;
+
+// Keys with fixed meanings in the command line that can never be rebound.
+const RESERVED_KEYS: [char; 18] = [
+    ' ', ',', '-', 'U', 'R', 'I', '/', '#', '@', '~', '%', '&', '^', '*', '<', '>', '.', ':',
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Target {
+    Unread,
+    ReviewRequired,
+    Approved,
+    ChangesRequested,
+    Conflicts,
+    WaitingOnCi,
+    Merged,
+    Closed,
+    Draft,
+    Pinned,
+}
+
+impl Target {
+    pub const ALL: [Target; 10] = [
+        Target::Unread,
+        Target::ReviewRequired,
+        Target::Approved,
+        Target::ChangesRequested,
+        Target::Conflicts,
+        Target::WaitingOnCi,
+        Target::Merged,
+        Target::Closed,
+        Target::Draft,
+        Target::Pinned,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Unread => "unread",
+            Self::ReviewRequired => "review_required",
+            Self::Approved => "approved",
+            Self::ChangesRequested => "changes_requested",
+            Self::Conflicts => "conflicts",
+            Self::WaitingOnCi => "waiting_on_ci",
+            Self::Merged => "merged",
+            Self::Closed => "closed",
+            Self::Draft => "draft",
+            Self::Pinned => "pinned",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|target| target.name() == name)
+    }
+
+    fn default_char(self) -> char {
+        match self {
+            Self::Unread => 'u',
+            Self::ReviewRequired => '?',
+            Self::Approved => 'a',
+            Self::ChangesRequested => 'x',
+            Self::Conflicts => '!',
+            Self::WaitingOnCi => 'w',
+            Self::Merged => 'm',
+            Self::Closed => 'c',
+            Self::Draft => 'f',
+            Self::Pinned => 'T',
+        }
+    }
+
+    fn help(self) -> KeyHelp {
+        let (label, short, description, example) = match self {
+            Self::Unread => ("unread", "unread", "Unread notifications", Action::Read),
+            Self::ReviewRequired => (
+                "pending review",
+                "review",
+                "PRs still waiting for a review",
+                Action::Review,
+            ),
+            Self::Approved => ("approved", "appr", "Approved PRs", Action::Open),
+            Self::ChangesRequested => (
+                "changes requested",
+                "chg",
+                "PRs with changes requested",
+                Action::Open,
+            ),
+            Self::Conflicts => (
+                "conflicts",
+                "conf",
+                "PRs with merge conflicts",
+                Action::Open,
+            ),
+            Self::WaitingOnCi => (
+                "approved+CI pending",
+                "appr+CI pend",
+                "Approved PRs whose CI is still running",
+                Action::Open,
+            ),
+            Self::Merged => ("merged", "merged", "Merged PRs", Action::Done),
+            Self::Closed => ("closed", "closed", "Closed PRs and issues", Action::Done),
+            Self::Draft => ("draft", "draft", "Draft PRs", Action::Done),
+            Self::Pinned => ("pinned", "pin", "Pinned items", Action::Open),
+        };
+        KeyHelp {
+            label,
+            short,
+            description,
+            example: Example::Action(example),
+        }
+    }
+}
+
+/// Legend labels and help text for an action or target; the legend and help overlay both read these.
+#[derive(Debug, Clone, Copy)]
+struct KeyHelp {
+    label: &'static str,
+    /// Label for narrow terminals.
+    short: &'static str,
+    description: &'static str,
+    example: Example,
+}
+
+/// The other half of an example command: targets for an action, an action for a target.
+#[derive(Debug, Clone, Copy)]
+enum Example {
+    Targets(&'static str),
+    Action(Action),
+}
+
+fn action_help(action: Action) -> KeyHelp {
+    let (label, short, description, example) = match action {
+        Action::Open => (
+            "open/read",
+            "open/read",
+            "Open in the browser and mark read",
+            "1-3",
+        ),
+        Action::PrettyYank => ("pretty yank", "pretty", "Copy a PR summary", "2"),
+        Action::Yank => ("yank", "yank", "Copy the URL", "2"),
+        Action::Read => ("read", "read", "Mark read", "1 4"),
+        Action::Done => ("done", "done", "Mark done, removing it from the inbox", "5"),
+        Action::Unsubscribe => (
+            "unsub/ignore",
+            "unsub/ign",
+            "Unsubscribe and mark done; my PRs are ignored instead",
+            "6",
+        ),
+        Action::Review => (
+            "review+analyze",
+            "rev+anlz",
+            "Review the PR in the local clone, with analysis",
+            "3",
+        ),
+        Action::ReviewNoAnalyze => ("review", "review", "Review the PR in the local clone", "3"),
+        Action::Branch => ("branch", "branch", "Copy the PR branch name", "2"),
+        Action::Worktree => (
+            "worktree",
+            "wtree",
+            "Check the PR head out into a git worktree",
+            "2",
+        ),
+        Action::Pin => ("pin", "pin", "Pin or unpin", "1"),
+        Action::Custom(_) => ("custom", "custom", "Custom action from the config", "1"),
+    };
+    KeyHelp {
+        label,
+        short,
+        description,
+        example: Example::Targets(example),
+    }
+}
+
+/// Keys handled outside the command parser, for the help overlay.
+pub const FIXED_KEYS: [(&str, &str); 13] = [
+    ("Enter", "Run the queued commands"),
+    ("U Enter", "Undo the last batch"),
+    (".", "Repeat the last command (on an empty line)"),
+    ("Up / Down", "Recall older / newer commands"),
+    ("Ctrl+R", "Search command history"),
+    ("/", "Filter the list"),
+    (":", "Run a colon command (on an empty line)"),
+    ("R", "Refresh"),
+    ("I", "Manage the ignore list"),
+    (
+        "Esc",
+        "Clear the command line; on an empty line, clear the filter",
+    ),
+    ("Ctrl+U", "Clear the command line"),
+    (
+        "F1",
+        "Show this help (also `:help`, for terminals that keep F1)",
+    ),
+    ("Ctrl+C", "Quit"),
+];
+
+/// One line of the help overlay, with keys from the active keymap.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HelpEntry {
+    pub key: char,
+    pub name: String,
+    pub description: &'static str,
+    pub example: String,
+}
+
+/// The active key for every action and target, in legend order.
+#[derive(Debug, Clone)]
+pub struct Keymap {
+    actions: Vec<(Action, char)>,
+    targets: Vec<(Target, char)>,
+    custom_names: Vec<String>,
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        Self {
+            actions: Action::ALL
+                .into_iter()
+                .filter_map(|action| action.as_char().map(|ch| (action, ch)))
+                .collect(),
+            targets: Target::ALL
+                .into_iter()
+                .map(|target| (target, target.default_char()))
+                .collect(),
+            custom_names: Vec::new(),
+        }
+    }
+}
+
+impl Keymap {
+    /// Applies `[keys]` overrides and custom action keys, rejecting ambiguous bindings.
+    pub fn from_overrides(
+        actions: &BTreeMap<String, String>,
+        targets: &BTreeMap<String, String>,
+        custom: &[(String, String)],
+    ) -> Result<Self> {
+        let mut keymap = Self::default();
+
+        for (name, value) in actions {
+            let action = Action::from_name(name)
+                .ok_or_else(|| anyhow!("unknown action '{}'", name))
+                .with_context(|| format!("keys.actions.{}", name))?;
+            let key = parse_key(value).with_context(|| format!("keys.actions.{}", name))?;
+            for (bound, ch) in &mut keymap.actions {
+                if *bound == action {
+                    *ch = key;
+                }
+            }
+        }
+        for (name, value) in targets {
+            let target = Target::from_name(name)
+                .ok_or_else(|| anyhow!("unknown target '{}'", name))
+                .with_context(|| format!("keys.targets.{}", name))?;
+            let key = parse_key(value).with_context(|| format!("keys.targets.{}", name))?;
+            for (bound, ch) in &mut keymap.targets {
+                if *bound == target {
+                    *ch = key;
+                }
+            }
+        }
+
+        for (idx, (name, value)) in custom.iter().enumerate() {
+            let key = parse_key(value).with_context(|| format!("custom action '{}'", name))?;
+            keymap.actions.push((Action::Custom(idx), key));
+            keymap.custom_names.push(name.clone());
+        }
+
+        keymap.validate()?;
+        Ok(keymap)
+    }
+
+    fn validate(&self) -> Result<()> {
+        let mut seen: HashMap<char, String> = HashMap::new();
+        let bindings = self
+            .actions
+            .iter()
+            .map(|(action, ch)| (*ch, format!("action {}", self.action_name(*action))))
+            .chain(
+                self.targets
+                    .iter()
+                    .map(|(target, ch)| (*ch, format!("target {}", target.name()))),
+            );
+
+        for (ch, label) in bindings {
+            if let Some(existing) = seen.get(&ch) {
+                return Err(anyhow!(
+                    "key '{}' is bound to both {} and {}",
+                    ch,
+                    existing,
+                    label
+                ));
+            }
+            seen.insert(ch, label);
+        }
+        Ok(())
+    }
+
+    pub fn action_name(&self, action: Action) -> &str {
+        match action {
+            Action::Custom(idx) => self
+                .custom_names
+                .get(idx)
+                .map(String::as_str)
+                .unwrap_or("custom"),
+            _ => action.name(),
+        }
+    }
+
+    pub fn action(&self, ch: char) -> Option<Action> {
+        self.actions
+            .iter()
+            .find(|(_, key)| *key == ch)
+            .map(|(action, _)| *action)
+    }
+
+    pub fn target(&self, ch: char) -> Option<Target> {
+        self.targets
+            .iter()
+            .find(|(_, key)| *key == ch)
+            .map(|(target, _)| *target)
+    }
+
+    pub fn is_target_char(&self, ch: char) -> bool {
+        self.target(ch).is_some()
+    }
+
+    pub fn target_char(&self, target: Target) -> char {
+        self.targets
+            .iter()
+            .find(|(bound, _)| *bound == target)
+            .map(|(_, ch)| *ch)
+            .unwrap_or_else(|| target.default_char())
+    }
+
+    /// Actions then targets with their current keys and an example using them.
+    pub fn help_entries(&self) -> (Vec<HelpEntry>, Vec<HelpEntry>) {
+        let entry = |key: char, name: String, help: KeyHelp| HelpEntry {
+            key,
+            name,
+            description: help.description,
+            example: match help.example {
+                Example::Targets(targets) => format!("{}{}", targets, key),
+                Example::Action(action) => match self.action_key(action) {
+                    Some(action_key) => format!("{}{}", key, action_key),
+                    None => key.to_string(),
+                },
+            },
+        };
+        let actions = self
+            .actions
+            .iter()
+            .map(|(action, ch)| {
+                let name = match action {
+                    Action::Custom(_) => self.action_name(*action).to_string(),
+                    _ => action_help(*action).label.to_string(),
+                };
+                entry(*ch, name, action_help(*action))
+            })
+            .collect();
+        let targets = self
+            .targets
+            .iter()
+            .map(|(target, ch)| entry(*ch, target.help().label.to_string(), target.help()))
+            .collect();
+        (actions, targets)
+    }
+
+    pub fn action_key(&self, action: Action) -> Option<char> {
+        self.actions
+            .iter()
+            .find(|(bound, _)| *bound == action)
+            .map(|(_, ch)| *ch)
+    }
+
+    /// Legend lines (commands, targets) from most to least descriptive.
+    pub fn legend_variants(&self) -> Vec<(String, String)> {
+        let full_actions = self.action_entries(|help| help.label);
+        let compact_actions = self.action_entries(|help| help.short);
+        let full_targets = self.target_entries(|help| help.label);
+        let compact_targets = self.target_entries(|help| help.short);
+        let action_keys: Vec<String> = self
+            .actions
+            .iter()
+            .map(|(_, ch)| ch.to_string())
+            .chain(std::iter::once("U".to_string()))
+            .collect();
+        let target_keys: Vec<String> = self.targets.iter().map(|(_, ch)| ch.to_string()).collect();
+
+        vec![
+            (
+                format!("Commands: {}  U undo  :help (F1)", full_actions.join("  ")),
+                format!("Targets: 1-3, 1 2 3, {}", full_targets.join(", ")),
+            ),
+            (
+                format!("Cmds: {}  U undo  :help", compact_actions.join("  ")),
+                format!("Targets: 1-3/1 2 3, {}", compact_targets.join(", ")),
+            ),
+            (
+                format!("Cmds {}", action_keys.join("/")),
+                format!("Tgt 1-3/1 2 3 {}", target_keys.join(" ")),
+            ),
+            (
+                action_keys.join(" "),
+                format!("1-3 {}", target_keys.join(" ")),
+            ),
+        ]
+    }
+
+    fn action_entries(&self, pick: fn(KeyHelp) -> &'static str) -> Vec<String> {
+        self.actions
+            .iter()
+            .map(|(action, ch)| match action {
+                Action::Custom(_) => format!("{} {}", ch, self.action_name(*action)),
+                _ => format!("{} {}", ch, pick(action_help(*action))),
+            })
+            .collect()
+    }
+
+    fn target_entries(&self, pick: fn(KeyHelp) -> &'static str) -> Vec<String> {
+        self.targets
+            .iter()
+            .map(|(target, ch)| format!("{} {}", ch, pick(target.help())))
+            .collect()
+    }
+}
+
+fn parse_key(value: &str) -> Result<char> {
+    let mut chars = value.chars();
+    let (Some(ch), None) = (chars.next(), chars.next()) else {
+        return Err(anyhow!("expected a single character, got '{}'", value));
+    };
+    if ch.is_ascii_digit() || ch.is_whitespace() || RESERVED_KEYS.contains(&ch) {
+        return Err(anyhow!("'{}' is reserved", ch));
+    }
+    Ok(ch)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Keymap, Target};
+    use crate::types::Action;
+    use std::collections::BTreeMap;
+
+    fn overrides(pairs: &[(&str, &str)]) -> BTreeMap<String, String> {
+        pairs
+            .iter()
+            .map(|(name, key)| (name.to_string(), key.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn default_legend_matches_builtin_keys() {
+        let variants = Keymap::default().legend_variants();
+        assert_eq!(
+            variants[0].0,
+            "Commands: o open/read  y pretty yank  Y yank  r read  d done  q unsub/ignore  p review+analyze  P review  b branch  W worktree  t pin  U undo  :help (F1)"
+        );
+        assert_eq!(
+            variants[0].1,
+            "Targets: 1-3, 1 2 3, u unread, ? pending review, a approved, x changes requested, ! conflicts, w approved+CI pending, m merged, c closed, f draft, T pinned"
+        );
+        assert_eq!(variants[2].0, "Cmds o/y/Y/r/d/q/p/P/b/W/t/U");
+        assert_eq!(variants[3].1, "1-3 u ? a x ! w m c f T");
+    }
+
+    #[test]
+    fn overrides_move_keys() {
+        let keymap = Keymap::from_overrides(
+            &overrides(&[("unsubscribe", "Q")]),
+            &overrides(&[("merged", "M")]),
+            &[],
+        )
+        .unwrap();
+        assert_eq!(keymap.action('Q'), Some(Action::Unsubscribe));
+        assert_eq!(keymap.action('q'), None);
+        assert_eq!(keymap.target('M'), Some(Target::Merged));
+        assert!(!keymap.is_target_char('m'));
+        assert!(keymap.legend_variants()[0].0.contains("Q unsub/ignore"));
+
+        // Examples follow rebound keys on both sides.
+        let (actions, targets) = keymap.help_entries();
+        let unsubscribe = actions.iter().find(|entry| entry.key == 'Q').unwrap();
+        assert_eq!(unsubscribe.example, "6Q");
+        let merged = targets.iter().find(|entry| entry.key == 'M').unwrap();
+        assert_eq!(merged.name, "merged");
+        assert_eq!(merged.example, "Md");
+    }
+
+    #[test]
+    fn rejects_conflicts_and_reserved_keys() {
+        let err = Keymap::from_overrides(&overrides(&[("read", "x")]), &BTreeMap::new(), &[])
+            .unwrap_err();
+        assert!(err.to_string().contains("action read"));
+        assert!(err.to_string().contains("target changes_requested"));
+
+        // Swapping two keys is fine as long as the final map is unambiguous.
+        assert!(Keymap::from_overrides(
+            &overrides(&[("read", "d"), ("done", "r")]),
+            &BTreeMap::new(),
+            &[]
+        )
+        .is_ok());
+
+        for key in ["U", "5", "-", "ab", ""] {
+            let result =
+                Keymap::from_overrides(&overrides(&[("read", key)]), &BTreeMap::new(), &[]);
+            assert!(result.is_err(), "{key:?} should be rejected");
+        }
+        assert!(
+            Keymap::from_overrides(&overrides(&[("merge", "z")]), &BTreeMap::new(), &[]).is_err()
+        );
+    }
+
+    #[test]
+    fn custom_actions_get_keys_and_labels() {
+        let custom = vec![("checkout".to_string(), "g".to_string())];
+        let keymap = Keymap::from_overrides(&BTreeMap::new(), &BTreeMap::new(), &custom).unwrap();
+        assert_eq!(keymap.action('g'), Some(Action::Custom(0)));
+        assert!(keymap.legend_variants()[0].0.contains("g checkout  U undo"));
+        let (actions, _) = keymap.help_entries();
+        assert_eq!(actions.last().unwrap().name, "checkout");
+        assert_eq!(actions.last().unwrap().example, "1g");
+
+        let clash = vec![("checkout".to_string(), "o".to_string())];
+        let err = Keymap::from_overrides(&BTreeMap::new(), &BTreeMap::new(), &clash).unwrap_err();
+        assert!(err.to_string().contains("action checkout"));
+    }
+}
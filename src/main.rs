@@ -1,14 +1,30 @@
+mod cli;
 mod commands;
+mod config;
+mod custom_actions;
+mod daemon;
+mod desktop;
+mod ex;
+mod filter;
 mod github;
+mod history;
+mod hooks;
 mod ignore;
+mod ignore_manager;
+mod keymap;
+mod pins;
+mod repos;
+mod status;
+mod store;
 mod types;
 mod ui;
 mod util;
+mod worktree;
 
 use std::{
-    collections::{HashMap, HashSet},
-    io::{self, Stdout},
-    path::{Path, PathBuf},
+    collections::{BTreeMap, HashMap, HashSet},
+    io::{self, Stdout, Write},
+    path::PathBuf,
     process::Command,
     sync::Arc,
     time::Duration,
@@ -33,24 +49,66 @@ use tokio_stream::StreamExt;
 use tui_textarea::{CursorMove, TextArea};
 
 use crate::{
-    commands::is_target_char,
+    cli::CliCommand,
+    config::{
+        load_settings, CliOverrides, CloneMode, ConfirmSettings, ReviewSettings, ReviewWorkdir,
+        Settings, WorktreeOpen,
+    },
+    custom_actions::{
+        render_command, render_with, run_in_background, shell_quote, CustomAction, TemplateValues,
+    },
+    desktop::{deliver_alerts, AlertTracker, DesktopSettings},
+    ex::{complete_ex, parse_ex, ExCommand, SortOrder},
+    filter::Filter,
     github::{
         fetch_notifications_and_my_prs_cached, fetch_pretty_pull_request, mark_as_done,
         mark_as_read, mark_as_unread, parse_pull_request_key, subscribe_to_thread, unsubscribe,
         PrettyPullRequest,
     },
-    ignore::{append_ignored_pr, load_ignored_prs, remove_ignored_pr},
+    history::{History, ReverseSearch},
+    hooks::{deliver, HookSettings, HookTracker},
+    ignore::{
+        append_ignore_rule, append_ignored_pr, load_ignored_prs, remove_ignored_pr,
+        replace_ignore_rule, snooze_url, IgnoreCandidate, IgnoreList, IgnoreMatcher,
+    },
+    ignore_manager::{IgnoreManager, RuleDetail},
+    keymap::Keymap,
+    pins::{load_pinned_urls, retain_unread_or_pinned, set_pinned_url},
+    repos::{clone_repo, find_local_repo, repo_dir_for_full_name},
+    status::{write_status_cache, StatusSummary},
     types::{Action, MyPullRequest, Notification},
     util::{copy_to_clipboard, format_relative_time, gh_auth_token, open_in_browser},
+    worktree::{prepare_worktree, WorktreeRequest},
 };
 
 #[derive(Parser, Debug)]
 #[command(author, version, about = "GitHub notifications TUI")]
 struct Args {
-    #[arg(long, default_value_t = 60)]
-    interval: u64,
+    #[arg(long, help = "Poll interval in seconds [default: 60]")]
+    interval: Option<u64>,
     #[arg(long, help = "Show only unread notifications")]
     unread_only: bool,
+    #[arg(
+        long,
+        conflicts_with = "unread_only",
+        help = "Show read notifications too, overriding unread_only in the config"
+    )]
+    all: bool,
+    #[command(subcommand)]
+    command: Option<CliCommand>,
+}
+
+impl Args {
+    fn overrides(&self) -> CliOverrides {
+        CliOverrides {
+            interval: self.interval,
+            unread_only: match (self.unread_only, self.all) {
+                (true, _) => Some(true),
+                (_, true) => Some(false),
+                _ => None,
+            },
+        }
+    }
 }
 
 #[derive(Debug)]
@@ -63,6 +121,18 @@ enum AppEvent {
     CommandResult(ExecSummary),
     UndoResult(UndoSummary),
     Review(Vec<ReviewRequest>),
+    Shell(Vec<ShellRequest>),
+    Worktree(Vec<WorktreeRequest>),
+    IgnoreDetail {
+        url: String,
+        detail: RuleDetail,
+    },
+}
+
+#[derive(Debug, Clone)]
+struct ShellRequest {
+    name: String,
+    command: String,
 }
 
 #[derive(Debug, Clone)]
@@ -76,6 +146,29 @@ pub struct AppState {
     pub notifications: Vec<Notification>,
     pub my_prs: Vec<MyPullRequest>,
     pub input: TextArea<'static>,
+    pub filter: Option<Filter>,
+    // Open while the `/` prompt is being edited; the filter applies live as it's typed.
+    pub filter_input: Option<TextArea<'static>>,
+    pub filter_error: Option<String>,
+    // Open while a `:` command is being typed.
+    pub ex_input: Option<TextArea<'static>>,
+    pub ex_error: Option<String>,
+    pub sort: SortOrder,
+    // Set by `:reload-config`; the event loop owns the settings and reloads them.
+    pub reload_config: bool,
+    // Why the command line doesn't parse, shown in place of the status while typing.
+    pub command_error: Option<String>,
+    pub confirm: ConfirmSettings,
+    // Scroll offset while the help overlay is open.
+    pub help: Option<u16>,
+    // Batch waiting for confirmation; the overlay lists it until Enter or Esc.
+    pub preview: Option<Preview>,
+    // Terminal area at the last draw, so overlay scrolling stops at the last line.
+    pub screen: Rect,
+    pub history: History,
+    // Open while Ctrl+R is searching the history; the command line shows the match.
+    pub history_search: Option<ReverseSearch>,
+    pub macros: BTreeMap<String, String>,
     pub pending: HashMap<usize, Vec<Action>>,
     pub executing: HashSet<String>,
     pub status: Option<String>,
@@ -84,7 +177,16 @@ pub struct AppState {
     pub include_read: bool,
     pub relative_times: Vec<String>,
     pub my_pr_relative_times: Vec<String>,
-    pub ignored_prs: HashSet<String>,
+    pub ignore_list: IgnoreList,
+    // Items filtered out by the ignore list on the last fetch, kept for the ignore manager counts.
+    pub hidden_notifications: Vec<Notification>,
+    pub hidden_my_prs: Vec<MyPullRequest>,
+    pub ignore_manager: Option<IgnoreManager>,
+    pub pinned_urls: HashSet<String>,
+    pub theme: ui::Theme,
+    pub keymap: Keymap,
+    pub custom_actions: Vec<CustomAction>,
+    pub review_description: String,
     notification_overrides: HashMap<String, NotificationOverride>,
     last_undo: Option<UndoBatch>,
     undo_in_flight: bool,
@@ -92,12 +194,27 @@ pub struct AppState {
 }
 
 impl AppState {
-    fn new(include_read: bool, ignored_prs: HashSet<String>) -> Self {
+    fn new(include_read: bool, ignore_list: IgnoreList) -> Self {
         let input = Self::new_input();
         Self {
             notifications: Vec::new(),
             my_prs: Vec::new(),
             input,
+            filter: None,
+            filter_input: None,
+            filter_error: None,
+            ex_input: None,
+            ex_error: None,
+            sort: SortOrder::default(),
+            reload_config: false,
+            command_error: None,
+            confirm: ConfirmSettings::default(),
+            help: None,
+            preview: None,
+            screen: Rect::default(),
+            history: History::default(),
+            history_search: None,
+            macros: BTreeMap::new(),
             pending: HashMap::new(),
             executing: HashSet::new(),
             status: None,
@@ -106,7 +223,15 @@ impl AppState {
             include_read,
             relative_times: Vec::new(),
             my_pr_relative_times: Vec::new(),
-            ignored_prs,
+            ignore_list,
+            hidden_notifications: Vec::new(),
+            hidden_my_prs: Vec::new(),
+            ignore_manager: None,
+            pinned_urls: HashSet::new(),
+            theme: ui::Theme::default(),
+            keymap: Keymap::default(),
+            custom_actions: Vec::new(),
+            review_description: String::new(),
             notification_overrides: HashMap::new(),
             last_undo: None,
             undo_in_flight: false,
@@ -134,13 +259,25 @@ impl AppState {
             .collect();
     }
 
-    fn set_data(&mut self, mut notifications: Vec<Notification>, mut my_prs: Vec<MyPullRequest>) {
-        sort_by_updated_at(&mut notifications, |notification| &notification.updated_at);
-        my_prs.retain(|pr| !self.ignored_prs.contains(&pr.url));
-        sort_by_updated_at(&mut my_prs, |pr| &pr.updated_at);
+    fn set_data(&mut self, notifications: Vec<Notification>, my_prs: Vec<MyPullRequest>) {
+        let now = chrono::Utc::now().naive_utc();
+        let (hidden_notifications, mut notifications): (Vec<_>, Vec<_>) =
+            notifications.into_iter().partition(|notification| {
+                self.ignore_list
+                    .ignores(&IgnoreCandidate::from_notification(notification), now)
+            });
+        sort_entries(&mut notifications, self.sort, notification_sort_fields);
+        let (hidden_my_prs, mut my_prs): (Vec<_>, Vec<_>) = my_prs.into_iter().partition(|pr| {
+            self.ignore_list
+                .ignores(&IgnoreCandidate::from_pull_request(pr), now)
+        });
+        self.hidden_notifications = hidden_notifications;
+        self.hidden_my_prs = hidden_my_prs;
+        sort_entries(&mut my_prs, self.sort, pr_sort_fields);
         let notifications = self.apply_notification_overrides(notifications);
         self.notifications = notifications;
         self.my_prs = my_prs;
+        self.apply_pins();
         self.loading = false;
         self.refresh_relative_times();
         self.update_pending();
@@ -178,7 +315,7 @@ impl AppState {
                     if server_unread {
                         notification.unread = false;
                     }
-                    if !self.include_read {
+                    if !self.include_read && !self.pinned_urls.contains(&notification.subject.url) {
                         continue;
                     }
                     if !server_unread {
@@ -199,24 +336,161 @@ impl AppState {
         merged
     }
 
+    fn apply_pins(&mut self) {
+        for notification in &mut self.notifications {
+            notification.pinned = self.pinned_urls.contains(&notification.subject.url);
+        }
+        for pr in &mut self.my_prs {
+            pr.pinned = self.pinned_urls.contains(&pr.url);
+        }
+    }
+
     fn update_pending(&mut self) {
-        self.pending =
-            ui::build_pending_map(&self.command_text(), &self.notifications, &self.my_prs);
+        let command_text = match self.expanded_command() {
+            Ok(text) => text,
+            Err(err) => {
+                self.pending.clear();
+                self.command_error = Some(err.to_string());
+                return;
+            }
+        };
+        if is_undo_command(&command_text) {
+            self.pending.clear();
+            self.command_error = None;
+            return;
+        }
+        match ui::build_pending_map(
+            &command_text,
+            &self.notifications,
+            &self.my_prs,
+            &self.keymap,
+            self.filter.as_ref(),
+        ) {
+            Ok(pending) => {
+                self.pending = pending;
+                self.command_error = None;
+            }
+            Err(err) => {
+                self.pending.clear();
+                self.command_error = Some(err.to_string());
+            }
+        }
+    }
+
+    fn open_filter(&mut self) {
+        let mut input = Self::new_input();
+        if let Some(filter) = &self.filter {
+            input.insert_str(filter.query());
+        }
+        self.filter_input = Some(input);
+        self.filter_error = None;
+    }
+
+    /// Re-parses the `/` prompt, keeping the last valid filter while the text is invalid.
+    fn apply_filter_input(&mut self) {
+        let Some(input) = &self.filter_input else {
+            return;
+        };
+        let query = input.lines().first().cloned().unwrap_or_default();
+        match Filter::parse(&query) {
+            Ok(filter) => {
+                self.filter = (!filter.is_empty()).then_some(filter);
+                self.filter_error = None;
+            }
+            Err(err) => self.filter_error = Some(err.to_string()),
+        }
+        self.update_pending();
+    }
+
+    fn set_sort(&mut self, sort: SortOrder) {
+        self.sort = sort;
+        sort_entries(&mut self.notifications, sort, notification_sort_fields);
+        sort_entries(&mut self.my_prs, sort, pr_sort_fields);
+        self.refresh_relative_times();
+        self.update_pending();
+    }
+
+    fn open_ex(&mut self) {
+        self.ex_input = Some(Self::new_input());
+        self.ex_error = None;
+    }
+
+    fn ex_text(&self) -> String {
+        self.ex_input
+            .as_ref()
+            .and_then(|input| input.lines().first().cloned())
+            .unwrap_or_default()
+    }
+
+    /// Checks the `:` prompt as it's typed so mistakes show before Enter.
+    fn apply_ex_input(&mut self) {
+        let text = self.ex_text();
+        self.ex_error = if text.trim().is_empty() {
+            None
+        } else {
+            parse_ex(&text, &self.macros)
+                .err()
+                .map(|err| err.to_string())
+        };
+    }
+
+    fn clear_filter(&mut self) {
+        self.filter = None;
+        self.filter_input = None;
+        self.filter_error = None;
+        self.update_pending();
     }
 
     fn clear_commands(&mut self) {
         self.input = Self::new_input();
         self.pending.clear();
+        self.command_error = None;
+        self.history.stop_browsing();
+    }
+
+    /// Moves the preview by `lines`, stopping once its last item is on screen.
+    fn scroll_preview(&mut self, lines: i32) {
+        let Some(preview) = self.preview.as_mut() else {
+            return;
+        };
+        let max_scroll = ui::preview_max_scroll(preview.pending.len(), self.screen);
+        preview.scroll = (i32::from(preview.scroll) + lines).clamp(0, i32::from(max_scroll)) as u16;
+    }
+
+    fn scroll_help(&mut self, lines: i32) {
+        let Some(scroll) = self.help else {
+            return;
+        };
+        let max_scroll = ui::help_max_scroll(&self.keymap, &self.macros, self.screen);
+        self.help = Some((i32::from(scroll) + lines).clamp(0, i32::from(max_scroll)) as u16);
+    }
+
+    /// Replaces the command line, e.g. with a history entry.
+    fn set_command(&mut self, text: &str) {
+        self.input = Self::new_input();
+        self.input.insert_str(text);
+        self.update_pending();
     }
 
     fn command_text(&self) -> String {
         self.input.lines().first().cloned().unwrap_or_default()
     }
+
+    /// The command line with `:name` macros replaced by their commands.
+    fn expanded_command(&self) -> Result<String> {
+        commands::expand_macros(&self.command_text(), &self.macros)
+    }
 }
 
 #[tokio::main]
 async fn main() -> Result<()> {
     let args = Args::parse();
+    // Load config before touching the terminal so validation errors print plainly.
+    let overrides = args.overrides();
+    let settings = load_settings(&overrides)?;
+    if let Some(command) = args.command {
+        return cli::run(command, &settings).await;
+    }
     let token = gh_auth_token()?;
 
     enable_raw_mode().context("failed to enable raw mode")?;
@@ -226,7 +500,7 @@ async fn main() -> Result<()> {
     let backend = CrosstermBackend::new(stdout);
     let mut terminal = Terminal::new(backend).context("failed to create terminal")?;
 
-    let result = run_app(&mut terminal, args, token).await;
+    let result = run_app(&mut terminal, settings, overrides, token).await;
 
     disable_raw_mode().ok();
     execute!(terminal.backend_mut(), LeaveAlternateScreen).ok();
@@ -237,7 +511,8 @@ async fn main() -> Result<()> {
 
 async fn run_app(
     terminal: &mut Terminal<CrosstermBackend<Stdout>>,
-    args: Args,
+    mut settings: Settings,
+    overrides: CliOverrides,
     token: String,
 ) -> Result<()> {
     let client = Arc::new(reqwest::Client::new());
@@ -246,33 +521,68 @@ async fn run_app(
     let (event_tx, mut event_rx) = mpsc::channel::<AppEvent>(4);
     let (refresh_tx, refresh_rx) = mpsc::channel::<()>(1);
 
-    spawn_poller(
-        client.clone(),
-        token.clone(),
-        args.interval,
-        !args.unread_only,
-        event_tx.clone(),
-        refresh_rx,
-    );
+    let poller = Poller {
+        client: client.clone(),
+        token: token.clone(),
+        interval_secs: settings.interval,
+        include_read: !settings.unread_only,
+        hooks: settings.hooks.clone(),
+        desktop: settings.desktop.clone(),
+        event_tx: event_tx.clone(),
+    };
+    // Share a running daemon's poller instead of polling GitHub from every TUI.
+    let subscription = match daemon::socket_path(&settings) {
+        Ok(path) => daemon::subscribe(&path)
+            .await
+            .ok()
+            .flatten()
+            .map(|subscription| (path, subscription)),
+        Err(_) => None,
+    };
+    match subscription {
+        Some((path, subscription)) => {
+            spawn_daemon_client(poller, path, subscription, refresh_rx);
+        }
+        None => spawn_poller(poller, refresh_rx),
+    }
 
-    let (ignored_prs, ignore_error) = match load_ignored_prs() {
+    let (ignore_list, ignore_error) = match load_ignored_prs() {
         Ok(list) => (list, None),
-        Err(err) => (HashSet::new(), Some(err)),
+        Err(err) => (IgnoreList::default(), Some(err)),
     };
 
-    let mut app = AppState::new(!args.unread_only, ignored_prs);
+    let mut app = AppState::new(!settings.unread_only, ignore_list);
+    apply_settings(&mut app, &settings);
     if let Some(err) = ignore_error {
-        app.status = Some(format!("Failed to load ignore list: {}", err));
+        app.status = Some(format!("Failed to load ignore list: {:#}", err));
+        app.status_sticky = true;
+    } else if let Some(warning) = ignore_warning_status(&app.ignore_list) {
+        app.status = Some(warning);
         app.status_sticky = true;
     }
+    match load_pinned_urls() {
+        Ok(pinned) => app.pinned_urls = pinned,
+        Err(err) => {
+            app.status = Some(format!("Failed to load pin list: {}", err));
+            app.status_sticky = true;
+        }
+    }
+    match History::load() {
+        Ok(history) => app.history = history,
+        Err(err) => {
+            app.status = Some(format!("Failed to load command history: {}", err));
+            app.status_sticky = true;
+        }
+    }
     let mut events = Some(EventStream::new());
     let mut tick = tokio::time::interval(Duration::from_millis(500));
     tick.set_missed_tick_behavior(MissedTickBehavior::Skip);
 
     loop {
-        terminal
+        app.screen = terminal
             .draw(|f| ui::draw(f, &app))
-            .context("render failed")?;
+            .context("render failed")?
+            .area;
 
         tokio::select! {
             maybe_event = events
@@ -283,12 +593,37 @@ async fn run_app(
                     if handle_input(event, &mut app, &refresh_tx, &event_tx, &client, &token)? {
                         break;
                     }
+                    if std::mem::take(&mut app.reload_config) {
+                        match load_settings(&overrides) {
+                            Ok(reloaded) => {
+                                settings = reloaded;
+                                apply_settings(&mut app, &settings);
+                                // The poller, daemon, hooks and alerts were started with the old values.
+                                app.status = Some(
+                                    "Reloaded config (interval, hooks and desktop alerts apply after a restart)"
+                                        .to_string(),
+                                );
+                                app.status_sticky = false;
+                            }
+                            Err(err) => {
+                                app.status = Some(format!("Failed to reload config: {:#}", err));
+                                app.status_sticky = true;
+                            }
+                        }
+                    }
                 }
             }
             Some(app_event) = event_rx.recv() => {
                 match app_event {
                     AppEvent::Data { notifications, my_prs } => {
                         app.set_data(notifications, my_prs);
+                        let summary = StatusSummary::new(
+                            &app.notifications,
+                            &app.my_prs,
+                            chrono::Utc::now().timestamp(),
+                        );
+                        // Feeds `ghn status --cached`; a failed write only means a later API call.
+                        tokio::task::spawn_blocking(move || write_status_cache(&summary));
                         if !app.status_sticky {
                             app.status = None;
                         }
@@ -307,8 +642,58 @@ async fn run_app(
                     AppEvent::UndoResult(result) => {
                         handle_undo_result(&mut app, &refresh_tx, result);
                     }
+                    AppEvent::IgnoreDetail { url, detail } => {
+                        if let Some(manager) = app.ignore_manager.as_mut() {
+                            manager.details.insert(url, detail);
+                        }
+                    }
+                    AppEvent::Shell(requests) => {
+                        // Same hand-off as ReviewPR: the command owns the terminal until it exits.
+                        events.take();
+
+                        let mut last = None;
+                        for request in requests {
+                            if let Err(err) = run_shell_in_foreground(terminal, &request) {
+                                app.status = Some(err.to_string());
+                                app.status_sticky = true;
+                                last = None;
+                                break;
+                            }
+                            last = Some(request.name);
+                        }
+
+                        if let Some(name) = last {
+                            app.status = Some(format!("{} finished", name));
+                            app.status_sticky = false;
+                        }
+                        force_redraw(terminal, &app)?;
+                        events = Some(EventStream::new());
+                    }
+                    AppEvent::Worktree(requests) => {
+                        events.take();
+
+                        let mut last = None;
+                        for request in requests {
+                            match run_worktree(terminal, &settings, &request) {
+                                Ok(path) => last = Some(path),
+                                Err(err) => {
+                                    app.status = Some(format!("{:#}", err));
+                                    app.status_sticky = true;
+                                    last = None;
+                                    break;
+                                }
+                            }
+                        }
+
+                        if let Some(path) = last {
+                            app.status = Some(format!("Worktree ready at {}", path.display()));
+                            app.status_sticky = false;
+                        }
+                        force_redraw(terminal, &app)?;
+                        events = Some(EventStream::new());
+                    }
                     AppEvent::Review(requests) => {
-                        // Release crossterm's global event reader while nvim owns the terminal.
+                        // Release crossterm's global event reader while the reviewer owns the terminal.
                         events.take();
 
                         let total = requests.len();
@@ -316,7 +701,7 @@ async fn run_app(
                         let mut failed = false;
 
                         for request in requests {
-                            let result = run_review_in_nvim(terminal, &request);
+                            let result = run_review(terminal, &settings.review, &request);
                             match result {
                                 Ok(()) => {
                                     completed += 1;
@@ -332,9 +717,12 @@ async fn run_app(
 
                         if !failed {
                             app.status = if total == 1 {
-                                Some("ReviewPR finished".to_string())
+                                Some(format!("{} finished", settings.review.name()))
                             } else {
-                                Some(format!("ReviewPR finished for {completed} targets"))
+                                Some(format!(
+                                    "{} finished for {completed} targets",
+                                    settings.review.name()
+                                ))
                             };
                             app.status_sticky = false;
                         }
@@ -353,90 +741,700 @@ async fn run_app(
     Ok(())
 }
 
-fn spawn_poller(
+struct Poller {
     client: Arc<reqwest::Client>,
     token: Arc<String>,
     interval_secs: u64,
     include_read: bool,
+    hooks: HookSettings,
+    desktop: DesktopSettings,
     event_tx: mpsc::Sender<AppEvent>,
+}
+
+/// Copies the settings the UI reads into the app, at startup and on `:reload-config`.
+fn apply_settings(app: &mut AppState, settings: &Settings) {
+    app.theme = settings.theme.clone();
+    app.keymap = settings.keymap.clone();
+    app.custom_actions = settings.custom_actions.clone();
+    app.review_description = settings.review.description();
+    app.confirm = settings.confirm.clone();
+    app.macros = settings.macros.clone();
+    app.update_pending();
+}
+
+/// What an inbox with these settings lists: read threads only when `include_read` or pinned,
+/// and nothing the ignore list hides. Hooks and alerts see just this, so scripts get the same
+/// events whether the TUI or the daemon polled.
+fn watched_inbox(
+    notifications: &[Notification],
+    my_prs: &[MyPullRequest],
+    include_read: bool,
+    pinned: &HashSet<String>,
+    ignore_list: &IgnoreList,
+    now: chrono::NaiveDateTime,
+) -> (Vec<Notification>, Vec<MyPullRequest>) {
+    let notifications = notifications
+        .iter()
+        .filter(|n| include_read || n.unread || pinned.contains(&n.subject.url))
+        .filter(|n| !ignore_list.ignores(&IgnoreCandidate::from_notification(n), now))
+        .cloned()
+        .collect();
+    let my_prs = my_prs
+        .iter()
+        .filter(|pr| !ignore_list.ignores(&IgnoreCandidate::from_pull_request(pr), now))
+        .cloned()
+        .collect();
+    (notifications, my_prs)
+}
+
+fn spawn_poller(poller: Poller, refresh_rx: mpsc::Receiver<()>) {
+    tokio::spawn(poll(poller, refresh_rx));
+}
+
+async fn poll(poller: Poller, mut refresh_rx: mpsc::Receiver<()>) {
+    let mut interval = tokio::time::interval(Duration::from_secs(poller.interval_secs));
+    interval.set_missed_tick_behavior(MissedTickBehavior::Skip);
+    let mut viewer_login: Option<String> = None;
+    let mut hooks = HookTracker::new(poller.hooks.clone());
+    // Alerts live here rather than in the app so a daemon, not each TUI, sends them.
+    let mut alerts = AlertTracker::new(poller.desktop.clone());
+
+    loop {
+        let pinned = if poller.include_read {
+            HashSet::new()
+        } else {
+            load_pinned_urls().unwrap_or_default()
+        };
+        // Pinned threads stay listed once read, so fetch read ones too while anything is pinned.
+        let result = fetch_notifications_and_my_prs_cached(
+            &poller.client,
+            &poller.token,
+            poller.include_read || !pinned.is_empty(),
+            viewer_login.as_deref(),
+        )
+        .await;
+        match result {
+            Ok(mut payload) => {
+                if !poller.include_read {
+                    retain_unread_or_pinned(&mut payload.notifications, &pinned);
+                }
+                let next_login = payload.viewer_login.trim();
+                if !next_login.is_empty() && next_login != "unknown" {
+                    viewer_login = Some(next_login.to_string());
+                }
+                let ignore_list = load_ignored_prs().unwrap_or_default();
+                let (watched, watched_prs) = watched_inbox(
+                    &payload.notifications,
+                    &payload.my_prs,
+                    poller.include_read,
+                    &pinned,
+                    &ignore_list,
+                    chrono::Utc::now().naive_utc(),
+                );
+                let events = hooks.update(&watched, &watched_prs);
+                hooks.retain_fetched(&payload.notifications, &payload.my_prs);
+                if !events.is_empty() {
+                    let client = poller.client.clone();
+                    let settings = hooks.settings().clone();
+                    let event_tx = poller.event_tx.clone();
+                    tokio::spawn(async move {
+                        if let Err(err) = deliver(&client, &settings, events).await {
+                            let message = format!("Event hook failed: {:#}", err);
+                            let _ = event_tx.send(AppEvent::Error(message)).await;
+                        }
+                    });
+                }
+                let new_alerts = alerts.update(&watched, &watched_prs, chrono::Local::now().time());
+                if !new_alerts.is_empty() {
+                    let command = alerts.command().map(str::to_string);
+                    let event_tx = poller.event_tx.clone();
+                    tokio::spawn(async move {
+                        if let Err(err) = deliver_alerts(command, new_alerts).await {
+                            let message = format!("Desktop notification failed: {:#}", err);
+                            let _ = event_tx.send(AppEvent::Error(message)).await;
+                        }
+                    });
+                }
+                let _ = poller
+                    .event_tx
+                    .send(AppEvent::Data {
+                        notifications: payload.notifications,
+                        my_prs: payload.my_prs,
+                    })
+                    .await;
+            }
+            Err(err) => {
+                let _ = poller.event_tx.send(AppEvent::Error(err.to_string())).await;
+            }
+        }
+
+        tokio::select! {
+            _ = interval.tick() => {},
+            _ = refresh_rx.recv() => {},
+        }
+    }
+}
+
+/// Feeds the app from `ghn daemon`, falling back to polling directly if it goes away.
+fn spawn_daemon_client(
+    poller: Poller,
+    path: PathBuf,
+    mut subscription: daemon::Subscription,
     mut refresh_rx: mpsc::Receiver<()>,
 ) {
     tokio::spawn(async move {
-        let mut interval = tokio::time::interval(Duration::from_secs(interval_secs));
-        interval.set_missed_tick_behavior(MissedTickBehavior::Skip);
-        let mut viewer_login: Option<String> = None;
-
         loop {
-            let result = fetch_notifications_and_my_prs_cached(
-                &client,
-                &token,
-                include_read,
-                viewer_login.as_deref(),
-            )
+            tokio::select! {
+                snapshot = subscription.next() => {
+                    let snapshot = match snapshot {
+                        Ok(Some(snapshot)) => snapshot,
+                        Ok(None) | Err(_) => break,
+                    };
+                    let event = match snapshot.error.clone() {
+                        Some(error) => AppEvent::Error(error),
+                        None => {
+                            let pinned = load_pinned_urls().unwrap_or_default();
+                            let (notifications, my_prs) =
+                                snapshot.into_inbox(poller.include_read, &pinned);
+                            AppEvent::Data { notifications, my_prs }
+                        }
+                    };
+                    let _ = poller.event_tx.send(event).await;
+                }
+                Some(()) = refresh_rx.recv() => {
+                    let _ = daemon::request(&path, &daemon::Request::Refresh).await;
+                }
+            }
+        }
+
+        let _ = poller
+            .event_tx
+            .send(AppEvent::Error(
+                "ghn daemon stopped; polling GitHub directly".to_string(),
+            ))
             .await;
+        poll(poller, refresh_rx).await;
+    });
+}
+
+fn handle_input(
+    event: Event,
+    app: &mut AppState,
+    refresh_tx: &mpsc::Sender<()>,
+    app_event_tx: &mpsc::Sender<AppEvent>,
+    client: &reqwest::Client,
+    token: &str,
+) -> Result<bool> {
+    if let Event::Key(key) = event {
+        if key.kind != KeyEventKind::Press {
+            return Ok(false);
+        }
+
+        if app.ignore_manager.is_some() {
+            if key.modifiers.contains(KeyModifiers::CONTROL) && key.code == KeyCode::Char('c') {
+                return Ok(true);
+            }
+            handle_ignore_manager_input(app, key, refresh_tx);
+            return Ok(false);
+        }
+
+        if app.help.is_some() {
+            match key.code {
+                KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                    return Ok(true)
+                }
+                KeyCode::Esc | KeyCode::Char('q') | KeyCode::F(1) => app.help = None,
+                KeyCode::Down | KeyCode::Char('j') => app.scroll_help(1),
+                KeyCode::Up | KeyCode::Char('k') => app.scroll_help(-1),
+                KeyCode::PageDown | KeyCode::Char(' ') => app.scroll_help(10),
+                KeyCode::PageUp => app.scroll_help(-10),
+                _ => {}
+            }
+            return Ok(false);
+        }
+
+        if app.preview.is_some() {
+            match key.code {
+                KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                    return Ok(true)
+                }
+                KeyCode::Enter | KeyCode::Char('y') => {
+                    submit_commands(app, app_event_tx, client, token, true)?;
+                }
+                KeyCode::Esc | KeyCode::Char('n') | KeyCode::Char('q') => {
+                    // Keep the command so it can be adjusted.
+                    app.preview = None;
+                }
+                KeyCode::Down | KeyCode::Char('j') => app.scroll_preview(1),
+                KeyCode::Up | KeyCode::Char('k') => app.scroll_preview(-1),
+                KeyCode::PageDown => app.scroll_preview(10),
+                KeyCode::PageUp => app.scroll_preview(-10),
+                _ => {}
+            }
+            return Ok(false);
+        }
+
+        if app.filter_input.is_some() {
+            if key.modifiers.contains(KeyModifiers::CONTROL) && key.code == KeyCode::Char('c') {
+                return Ok(true);
+            }
+            handle_filter_input(app, key);
+            return Ok(false);
+        }
+
+        if app.ex_input.is_some() {
+            if key.modifiers.contains(KeyModifiers::CONTROL) && key.code == KeyCode::Char('c') {
+                return Ok(true);
+            }
+            return handle_ex_input(app, key, refresh_tx, app_event_tx, client, token);
+        }
+
+        if app.history_search.is_some() {
+            if key.modifiers.contains(KeyModifiers::CONTROL) && key.code == KeyCode::Char('c') {
+                return Ok(true);
+            }
+            handle_history_search(app, key);
+            return Ok(false);
+        }
+
+        match key.code {
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => return Ok(true),
+            // `?` is the pending review target, so help lives on F1 and `:help`; the legend shows
+            // `:help` since some terminals keep F1 for themselves.
+            KeyCode::F(1) => app.help = Some(0),
+            KeyCode::Char('.') if app.command_text().is_empty() => {
+                // Re-parsed against what's listed now, so targets like `md` pick up new items.
+                match app.history.last().map(str::to_string) {
+                    Some(last) => {
+                        app.set_command(&last);
+                        submit_commands(app, app_event_tx, client, token, false)?;
+                    }
+                    None => {
+                        app.status = Some("No command to repeat".to_string());
+                        app.status_sticky = false;
+                    }
+                }
+            }
+            KeyCode::Char('I') if !in_named_target(app) => {
+                open_ignore_manager(app, app_event_tx, client, token);
+            }
+            KeyCode::Char('/') if !in_named_target(app) => app.open_filter(),
+            // Mid-line, `:` starts a macro name instead.
+            KeyCode::Char(':') if app.command_text().is_empty() => app.open_ex(),
+            KeyCode::Char('R') if !in_named_target(app) => {
+                let _ = refresh_tx.try_send(());
+                app.status = Some("Refreshing...".to_string());
+                app.status_sticky = false;
+            }
+            KeyCode::Enter => {
+                submit_commands(app, app_event_tx, client, token, false)?;
+            }
+            KeyCode::Char('m') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                submit_commands(app, app_event_tx, client, token, false)?;
+            }
+            KeyCode::Esc => {
+                // A second Esc on an empty line drops the active filter.
+                if app.command_text().is_empty() && app.filter.is_some() {
+                    app.clear_filter();
+                }
+                app.clear_commands();
+            }
+            _ => {
+                handle_text_input(app, key);
+            }
+        }
+    }
+
+    Ok(false)
+}
+
+fn handle_filter_input(app: &mut AppState, key: crossterm::event::KeyEvent) {
+    let Some(input) = app.filter_input.as_mut() else {
+        return;
+    };
+
+    match key.code {
+        KeyCode::Esc => app.clear_filter(),
+        KeyCode::Enter => {
+            // Invalid text stays open so the error can be fixed in place.
+            if app.filter_error.is_none() {
+                app.filter_input = None;
+            }
+        }
+        KeyCode::Char('m') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+            if app.filter_error.is_none() {
+                app.filter_input = None;
+            }
+        }
+        KeyCode::Char('u') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+            input.delete_line_by_head();
+            app.apply_filter_input();
+        }
+        _ => {
+            if input.input(key) {
+                app.apply_filter_input();
+            }
+        }
+    }
+}
+
+fn handle_ex_input(
+    app: &mut AppState,
+    key: crossterm::event::KeyEvent,
+    refresh_tx: &mpsc::Sender<()>,
+    app_event_tx: &mpsc::Sender<AppEvent>,
+    client: &reqwest::Client,
+    token: &str,
+) -> Result<bool> {
+    let Some(input) = app.ex_input.as_mut() else {
+        return Ok(false);
+    };
+
+    let control = key.modifiers.contains(KeyModifiers::CONTROL);
+    match key.code {
+        KeyCode::Esc => app.ex_input = None,
+        KeyCode::Backspace if input.is_empty() => app.ex_input = None,
+        KeyCode::Tab => {
+            let (completed, candidates) = complete_ex(&app.ex_text(), &app.macros);
+            let mut input = AppState::new_input();
+            input.insert_str(&completed);
+            app.ex_input = Some(input);
+            app.apply_ex_input();
+            if !candidates.is_empty() {
+                app.status = Some(candidates.join("  "));
+                app.status_sticky = false;
+            }
+        }
+        KeyCode::Enter => return submit_ex(app, refresh_tx, app_event_tx, client, token),
+        KeyCode::Char('m') if control => {
+            return submit_ex(app, refresh_tx, app_event_tx, client, token)
+        }
+        KeyCode::Char('u') if control => {
+            input.delete_line_by_head();
+            app.apply_ex_input();
+        }
+        _ => {
+            if input.input(key) {
+                app.apply_ex_input();
+            }
+        }
+    }
+    Ok(false)
+}
+
+/// Runs the `:` prompt, returning whether to quit. Invalid text stays open to be fixed.
+fn submit_ex(
+    app: &mut AppState,
+    refresh_tx: &mpsc::Sender<()>,
+    app_event_tx: &mpsc::Sender<AppEvent>,
+    client: &reqwest::Client,
+    token: &str,
+) -> Result<bool> {
+    let text = app.ex_text();
+    if text.trim().is_empty() {
+        app.ex_input = None;
+        return Ok(false);
+    }
+    let command = match parse_ex(&text, &app.macros) {
+        Ok(command) => command,
+        Err(err) => {
+            app.ex_error = Some(err.to_string());
+            return Ok(false);
+        }
+    };
+    app.ex_input = None;
+
+    let status = match command {
+        ExCommand::Quit => return Ok(true),
+        ExCommand::Help => {
+            app.help = Some(0);
+            return Ok(false);
+        }
+        ExCommand::Macro(name) => {
+            app.set_command(&format!("{}{}", commands::MACRO_SIGIL, name));
+            submit_commands(app, app_event_tx, client, token, false)?;
+            return Ok(false);
+        }
+        ExCommand::Filter(filter) => {
+            app.filter_input = None;
+            app.filter_error = None;
+            app.filter = (!filter.is_empty()).then_some(filter);
+            app.update_pending();
+            match &app.filter {
+                Some(filter) => format!("Filtering by {}", filter.query()),
+                None => "Filter cleared".to_string(),
+            }
+        }
+        ExCommand::Sort(order) => {
+            app.set_sort(order);
+            format!("Sorted by {}", order.name())
+        }
+        ExCommand::Snooze { targets, seconds } => {
+            match snooze_targets(app, refresh_tx, &targets, seconds) {
+                Ok(status) => status,
+                Err(err) => {
+                    app.status = Some(format!("Failed to snooze: {:#}", err));
+                    app.status_sticky = true;
+                    return Ok(false);
+                }
+            }
+        }
+        ExCommand::IgnoreRepo(rule) => {
+            let result = append_ignore_rule(&rule).and_then(|added| {
+                app.ignore_list = load_ignored_prs()?;
+                Ok(added)
+            });
             match result {
-                Ok(payload) => {
-                    let next_login = payload.viewer_login.trim();
-                    if !next_login.is_empty() && next_login != "unknown" {
-                        viewer_login = Some(next_login.to_string());
+                Ok(added) => {
+                    let _ = refresh_tx.try_send(());
+                    if added {
+                        format!("Ignoring {}", rule)
+                    } else {
+                        format!("Already ignored: {}", rule)
                     }
-                    let _ = event_tx
-                        .send(AppEvent::Data {
-                            notifications: payload.notifications,
-                            my_prs: payload.my_prs,
-                        })
-                        .await;
                 }
                 Err(err) => {
-                    let _ = event_tx.send(AppEvent::Error(err.to_string())).await;
+                    app.status = Some(format!("Failed to update ignore list: {:#}", err));
+                    app.status_sticky = true;
+                    return Ok(false);
+                }
+            }
+        }
+        ExCommand::ReloadConfig => {
+            app.reload_config = true;
+            "Reloading config...".to_string()
+        }
+        ExCommand::Export(format) => {
+            let result = ui::resolve_targets(
+                &commands::ALL_TARGET.to_string(),
+                &app.notifications,
+                &app.my_prs,
+                &app.keymap,
+                app.filter.as_ref(),
+            )
+            .and_then(|visible| cli::render_export(app, format, &visible))
+            .and_then(|(text, count)| copy_to_clipboard(&text).map(|_| count));
+            match result {
+                Ok(count) => format!("Copied {} items as {}", count, format.name()),
+                Err(err) => {
+                    app.status = Some(format!("Failed to export: {:#}", err));
+                    app.status_sticky = true;
+                    return Ok(false);
                 }
             }
+        }
+    };
+    app.status = Some(status);
+    app.status_sticky = false;
+    Ok(false)
+}
+
+fn snooze_targets(
+    app: &mut AppState,
+    refresh_tx: &mpsc::Sender<()>,
+    targets: &str,
+    seconds: i64,
+) -> Result<String> {
+    let indices = ui::resolve_targets(
+        targets,
+        &app.notifications,
+        &app.my_prs,
+        &app.keymap,
+        app.filter.as_ref(),
+    )?;
+    if indices.is_empty() {
+        return Ok("No items to snooze".to_string());
+    }
+    let until = chrono::Utc::now() + chrono::Duration::seconds(seconds);
+    for index in &indices {
+        let Some(entry) = entry_for_index(*index, &app.notifications, &app.my_prs) else {
+            continue;
+        };
+        snooze_url(entry.url(), until.naive_utc())?;
+    }
+    app.ignore_list = load_ignored_prs()?;
+    // Re-fetch so the snoozed items drop out, as after editing the ignore list.
+    let _ = refresh_tx.try_send(());
+    Ok(format!(
+        "Snoozed {} items until {}",
+        indices.len(),
+        until.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M")
+    ))
+}
 
-            tokio::select! {
-                _ = interval.tick() => {},
-                _ = refresh_rx.recv() => {},
+fn handle_history_search(app: &mut AppState, key: crossterm::event::KeyEvent) {
+    let Some(mut search) = app.history_search.take() else {
+        return;
+    };
+
+    let control = key.modifiers.contains(KeyModifiers::CONTROL);
+    match key.code {
+        KeyCode::Esc => {
+            app.set_command(&search.draft);
+            return;
+        }
+        KeyCode::Char('g') if control => {
+            app.set_command(&search.draft);
+            return;
+        }
+        // Keep the match on the command line to edit or submit.
+        KeyCode::Enter => return,
+        KeyCode::Char('m') if control => return,
+        KeyCode::Char('r') if control => {
+            let before = search.found.unwrap_or(usize::MAX);
+            if let Some(found) = app.history.search(&search.query, before) {
+                search.found = Some(found);
             }
         }
-    });
+        KeyCode::Backspace => {
+            search.query.pop();
+            search.found = app.history.search(&search.query, usize::MAX);
+        }
+        KeyCode::Char(ch) if !control => {
+            search.query.push(ch);
+            search.found = app.history.search(&search.query, usize::MAX);
+        }
+        _ => {}
+    }
+
+    let shown = match search.found.and_then(|index| app.history.get(index)) {
+        Some(entry) if !search.query.is_empty() => entry.to_string(),
+        _ => search.draft.clone(),
+    };
+    app.set_command(&shown);
+    app.history_search = Some(search);
 }
 
-fn handle_input(
-    event: Event,
+fn open_ignore_manager(
     app: &mut AppState,
-    refresh_tx: &mpsc::Sender<()>,
     app_event_tx: &mpsc::Sender<AppEvent>,
     client: &reqwest::Client,
     token: &str,
-) -> Result<bool> {
-    if let Event::Key(key) = event {
-        if key.kind != KeyEventKind::Press {
-            return Ok(false);
+) {
+    let mut manager = IgnoreManager::default();
+
+    // Titles and states are only fetched while the overlay is open, one request per ignored PR.
+    for rule in app.ignore_list.rules() {
+        let IgnoreMatcher::Url(url) = &rule.matcher else {
+            continue;
+        };
+        let Some(key) = parse_pull_request_key(url) else {
+            continue;
+        };
+        if manager.details.contains_key(url) {
+            continue;
         }
+        manager.details.insert(url.clone(), RuleDetail::Loading);
+
+        let client = client.clone();
+        let token = token.to_string();
+        let url = url.clone();
+        let app_event_tx = app_event_tx.clone();
+        tokio::spawn(async move {
+            let detail = match fetch_pretty_pull_request(&client, &token, &key).await {
+                Ok(pr) => RuleDetail::from_pull_request(&pr),
+                Err(err) => RuleDetail::Failed(summarize_error(&err)),
+            };
+            let _ = app_event_tx
+                .send(AppEvent::IgnoreDetail { url, detail })
+                .await;
+        });
+    }
+
+    app.ignore_manager = Some(manager);
+}
+
+fn handle_ignore_manager_input(
+    app: &mut AppState,
+    key: crossterm::event::KeyEvent,
+    refresh_tx: &mpsc::Sender<()>,
+) {
+    let rule_count = app.ignore_list.len();
+    let Some(manager) = app.ignore_manager.as_mut() else {
+        return;
+    };
 
+    if let Some(editor) = manager.editor.as_mut() {
         match key.code {
-            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => return Ok(true),
-            KeyCode::Down | KeyCode::Up => {}
-            KeyCode::Char('R') => {
-                let _ = refresh_tx.try_send(());
-                app.status = Some("Refreshing...".to_string());
-                app.status_sticky = false;
-            }
+            KeyCode::Esc => manager.editor = None,
             KeyCode::Enter => {
-                submit_commands(app, app_event_tx, client, token)?;
+                let replacement = manager.editor_text().unwrap_or_default();
+                let Some(rule) = app.ignore_list.rules().get(manager.selected) else {
+                    manager.editor = None;
+                    return;
+                };
+                let line = rule.line.clone();
+                let replacement = (!replacement.is_empty()).then_some(replacement);
+                manager.editor = None;
+                update_ignore_rule(app, refresh_tx, &line, replacement.as_deref());
             }
-            KeyCode::Char('m') if key.modifiers.contains(KeyModifiers::CONTROL) => {
-                submit_commands(app, app_event_tx, client, token)?;
+            _ => {
+                editor.input(key);
             }
-            KeyCode::Esc => {
-                app.clear_commands();
+        }
+        return;
+    }
+
+    match key.code {
+        KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('I') => app.ignore_manager = None,
+        KeyCode::Down | KeyCode::Char('j') => manager.select_next(rule_count),
+        KeyCode::Up | KeyCode::Char('k') => manager.select_prev(),
+        KeyCode::Char('e') | KeyCode::Enter => {
+            if let Some(rule) = app.ignore_list.rules().get(manager.selected) {
+                manager.start_edit(&rule.line);
             }
-            _ => {
-                handle_text_input(app, key);
+        }
+        KeyCode::Char('x') | KeyCode::Char('d') | KeyCode::Delete => {
+            if let Some(rule) = app.ignore_list.rules().get(manager.selected) {
+                let line = rule.line.clone();
+                update_ignore_rule(app, refresh_tx, &line, None);
             }
         }
+        _ => {}
     }
+}
 
-    Ok(false)
+/// Names the first skipped ignore rule, and how many others were skipped.
+fn ignore_warning_status(list: &IgnoreList) -> Option<String> {
+    match list.warnings() {
+        [] => None,
+        [only] => Some(format!("Skipped invalid rule: {}", only)),
+        [first, rest @ ..] => Some(format!(
+            "Skipped {} invalid rules: {} (and {} more)",
+            rest.len() + 1,
+            first,
+            rest.len()
+        )),
+    }
+}
+
+fn update_ignore_rule(
+    app: &mut AppState,
+    refresh_tx: &mpsc::Sender<()>,
+    line: &str,
+    replacement: Option<&str>,
+) {
+    let result = replace_ignore_rule(line, replacement).and_then(|_| load_ignored_prs());
+    match result {
+        Ok(list) => {
+            app.ignore_list = list;
+            if let Some(manager) = app.ignore_manager.as_mut() {
+                manager.clamp_selection(app.ignore_list.len());
+            }
+            app.status = Some(if replacement.is_some() {
+                "Updated ignore rule".to_string()
+            } else {
+                "Removed ignore rule".to_string()
+            });
+            app.status_sticky = false;
+            // Re-fetch so un-ignored items reappear and the hidden counts reflect the new rules.
+            let _ = refresh_tx.try_send(());
+        }
+        Err(err) => {
+            app.status = Some(format!("Failed to update ignore list: {:#}", err));
+            app.status_sticky = true;
+        }
+    }
 }
 
 fn submit_commands(
@@ -444,20 +1442,66 @@ fn submit_commands(
     app_event_tx: &mpsc::Sender<AppEvent>,
     client: &reqwest::Client,
     token: &str,
+    confirmed: bool,
 ) -> Result<()> {
-    let command_text = app.command_text();
+    let command_text = match app.expanded_command() {
+        Ok(text) => text,
+        Err(err) => {
+            app.status = Some(format!("Invalid command: {}", err));
+            app.status_sticky = false;
+            return Ok(());
+        }
+    };
     if is_undo_command(&command_text) {
         return submit_undo(app, app_event_tx, client, token);
     }
 
-    let pending = ui::build_pending_map(&app.command_text(), &app.notifications, &app.my_prs);
+    let pending = match ui::build_pending_map(
+        &command_text,
+        &app.notifications,
+        &app.my_prs,
+        &app.keymap,
+        app.filter.as_ref(),
+    ) {
+        Ok(pending) => pending,
+        Err(err) => {
+            // Leave the input in place so the mistake can be fixed.
+            app.status = Some(format!("Invalid command: {}", err));
+            app.status_sticky = false;
+            return Ok(());
+        }
+    };
     if pending.is_empty() {
+        app.preview = None;
         app.status = Some("No commands to run".to_string());
         app.status_sticky = false;
         app.clear_commands();
         return Ok(());
     }
 
+    // A refresh while the preview was open can move items, so confirm only the entries shown.
+    let previewed = app.preview.take();
+    let preview = Preview::new(pending, &app.notifications, &app.my_prs);
+    if (!confirmed || previewed.as_ref().map(|shown| &shown.entries) != Some(&preview.entries))
+        && app
+            .confirm
+            .requires_confirmation(&preview.pending, &app.custom_actions)
+    {
+        if confirmed {
+            app.status = Some("Targets changed since the preview; check again".to_string());
+            app.status_sticky = false;
+        }
+        app.preview = Some(preview);
+        return Ok(());
+    }
+    let pending = preview.pending;
+
+    // The typed line is kept rather than its expansion so macro edits apply on recall.
+    if let Err(err) = app.history.push(&app.command_text()) {
+        app.status = Some(format!("Failed to save command history: {}", err));
+        app.status_sticky = true;
+    }
+
     let (review_requests, pending) =
         match split_review_action(&pending, &app.notifications, &app.my_prs) {
             Ok(value) => value,
@@ -469,6 +1513,43 @@ fn submit_commands(
             }
         };
 
+    let (shell_requests, pending) = match split_foreground_actions(
+        &pending,
+        &app.notifications,
+        &app.my_prs,
+        &app.custom_actions,
+    ) {
+        Ok(value) => value,
+        Err(err) => {
+            app.status = Some(err.to_string());
+            app.status_sticky = true;
+            app.clear_commands();
+            return Ok(());
+        }
+    };
+
+    let (worktree_targets, pending) =
+        match split_worktree_action(&pending, &app.notifications, &app.my_prs) {
+            Ok(value) => value,
+            Err(err) => {
+                app.status = Some(err.to_string());
+                app.status_sticky = true;
+                app.clear_commands();
+                return Ok(());
+            }
+        };
+
+    let foreground_status = if !review_requests.is_empty() {
+        format!("Opening {}...", app.review_description)
+    } else if !worktree_targets.is_empty() {
+        "Fetching pull request heads...".to_string()
+    } else {
+        shell_requests
+            .first()
+            .map(|request| format!("Running {}...", request.name))
+            .unwrap_or_default()
+    };
+
     if !review_requests.is_empty() {
         let app_event_tx = app_event_tx.clone();
         tokio::spawn(async move {
@@ -476,8 +1557,24 @@ fn submit_commands(
         });
     }
 
+    if !shell_requests.is_empty() {
+        let app_event_tx = app_event_tx.clone();
+        tokio::spawn(async move {
+            let _ = app_event_tx.send(AppEvent::Shell(shell_requests)).await;
+        });
+    }
+
+    if !worktree_targets.is_empty() {
+        let app_event_tx = app_event_tx.clone();
+        tokio::spawn(async move {
+            let _ = app_event_tx
+                .send(AppEvent::Worktree(worktree_targets))
+                .await;
+        });
+    }
+
     if pending.is_empty() {
-        app.status = Some("Opening ReviewPR in nvim...".to_string());
+        app.status = Some(foreground_status);
         app.status_sticky = false;
         app.clear_commands();
         return Ok(());
@@ -490,6 +1587,7 @@ fn submit_commands(
 
     let notifications_snapshot = app.notifications.clone();
     let my_prs_snapshot = app.my_prs.clone();
+    let custom_actions = app.custom_actions.clone();
     let action_total: usize = pending.values().map(Vec::len).sum();
     app.executing.clear();
     apply_optimistic_update(app, &pending);
@@ -510,6 +1608,7 @@ fn submit_commands(
             &pending,
             &notifications_snapshot,
             &my_prs_snapshot,
+            &custom_actions,
         )
         .await;
         match result {
@@ -573,6 +1672,97 @@ fn submit_undo(
 
 type SplitReviewActionResult = (Vec<ReviewRequest>, HashMap<usize, Vec<Action>>);
 
+type SplitForegroundResult = (Vec<ShellRequest>, HashMap<usize, Vec<Action>>);
+
+type SplitWorktreeResult = (Vec<WorktreeRequest>, HashMap<usize, Vec<Action>>);
+
+fn split_worktree_action(
+    commands: &HashMap<usize, Vec<Action>>,
+    notifications: &[Notification],
+    my_prs: &[MyPullRequest],
+) -> Result<SplitWorktreeResult> {
+    let mut command_indices: Vec<usize> = commands.keys().copied().collect();
+    command_indices.sort_unstable();
+
+    let mut targets = Vec::new();
+    let mut filtered = HashMap::new();
+    for index in command_indices {
+        let actions = &commands[&index];
+        if actions.contains(&Action::Worktree) {
+            let entry = entry_for_index(index, notifications, my_prs)
+                .ok_or_else(|| anyhow!("Worktree target is out of range"))?;
+            let key = parse_pull_request_key(entry.url())
+                .ok_or_else(|| anyhow!("Worktrees only support pull request URLs"))?;
+            // Only the number is needed: heads are fetched from `refs/pull/<n>/head` on the base
+            // repository, which also covers forks.
+            targets.push(WorktreeRequest {
+                repo_full_name: entry.repo_full_name().to_string(),
+                number: key.number,
+            });
+        }
+
+        let remaining: Vec<Action> = actions
+            .iter()
+            .copied()
+            .filter(|action| *action != Action::Worktree)
+            .collect();
+        if !remaining.is_empty() {
+            filtered.insert(index, remaining);
+        }
+    }
+
+    Ok((targets, filtered))
+}
+
+// Foreground custom actions hand the terminal to the command, so they run outside the batch.
+fn split_foreground_actions(
+    commands: &HashMap<usize, Vec<Action>>,
+    notifications: &[Notification],
+    my_prs: &[MyPullRequest],
+    custom_actions: &[CustomAction],
+) -> Result<SplitForegroundResult> {
+    let is_foreground = |action: &Action| match action {
+        Action::Custom(idx) => custom_actions
+            .get(*idx)
+            .is_some_and(|custom| custom.foreground),
+        _ => false,
+    };
+
+    let mut command_indices: Vec<usize> = commands.keys().copied().collect();
+    command_indices.sort_unstable();
+
+    let mut requests = Vec::new();
+    let mut filtered = HashMap::new();
+    for index in command_indices {
+        let actions = &commands[&index];
+        for action in actions.iter().filter(|action| is_foreground(action)) {
+            let Action::Custom(idx) = action else {
+                continue;
+            };
+            let custom = &custom_actions[*idx];
+            let entry = entry_for_index(index, notifications, my_prs)
+                .ok_or_else(|| anyhow!("{} target is out of range", custom.name))?;
+            let command = render_command(&custom.command, &template_values(&entry))
+                .with_context(|| custom.name.clone())?;
+            requests.push(ShellRequest {
+                name: custom.name.clone(),
+                command,
+            });
+        }
+
+        let remaining: Vec<Action> = actions
+            .iter()
+            .copied()
+            .filter(|action| !is_foreground(action))
+            .collect();
+        if !remaining.is_empty() {
+            filtered.insert(index, remaining);
+        }
+    }
+
+    Ok((requests, filtered))
+}
+
 fn split_review_action(
     commands: &HashMap<usize, Vec<Action>>,
     notifications: &[Notification],
@@ -651,6 +1841,18 @@ fn split_review_action(
     Ok((review_requests, filtered))
 }
 
+// Inside named targets like `@acme/Infra` and macros like `:Review`, keys such as `R` and `/` are text rather than shortcuts.
+fn in_named_target(app: &AppState) -> bool {
+    let (_, col) = app.input.cursor();
+    let before: String = app.command_text().chars().take(col).collect();
+    before
+        .rsplit(commands::ends_named_target)
+        .next()
+        .is_some_and(|word| {
+            commands::is_named_target(word) || word.starts_with(commands::MACRO_SIGIL)
+        })
+}
+
 fn handle_text_input(app: &mut AppState, key: crossterm::event::KeyEvent) {
     if key.modifiers.contains(KeyModifiers::CONTROL) && matches!(key.code, KeyCode::Char('u')) {
         // tui-textarea maps Ctrl+U to Undo by default; override to clear to line head.
@@ -659,6 +1861,30 @@ fn handle_text_input(app: &mut AppState, key: crossterm::event::KeyEvent) {
         return;
     }
 
+    if key.modifiers.contains(KeyModifiers::CONTROL) && matches!(key.code, KeyCode::Char('r')) {
+        app.history_search = Some(ReverseSearch {
+            draft: app.command_text(),
+            ..ReverseSearch::default()
+        });
+        return;
+    }
+
+    match key.code {
+        KeyCode::Up => {
+            if let Some(entry) = app.history.older(&app.command_text()).map(str::to_string) {
+                app.set_command(&entry);
+            }
+            return;
+        }
+        KeyCode::Down => {
+            if let Some(line) = app.history.newer() {
+                app.set_command(&line);
+            }
+            return;
+        }
+        _ => {}
+    }
+
     if key.modifiers.contains(KeyModifiers::SUPER) {
         match key.code {
             KeyCode::Backspace => {
@@ -685,17 +1911,6 @@ fn handle_text_input(app: &mut AppState, key: crossterm::event::KeyEvent) {
         return;
     }
 
-    if let KeyCode::Char(ch) = key.code {
-        if key.modifiers.is_empty()
-            && !(ch.is_ascii_digit()
-                || Action::from_char(ch).is_some()
-                || is_target_char(ch)
-                || matches!(ch, ' ' | ',' | '-' | 'U'))
-        {
-            return;
-        }
-    }
-
     if app.input.input(key) {
         app.update_pending();
     }
@@ -705,6 +1920,29 @@ fn is_undo_command(input: &str) -> bool {
     input.trim() == "U"
 }
 
+/// Sorts on `(updated_at, repo, title)` fields; repo and title ties stay newest first.
+fn sort_entries<T>(items: &mut [T], order: SortOrder, fields: impl Fn(&T) -> (&str, &str, &str)) {
+    sort_by_updated_at(items, |item| fields(item).0);
+    match order {
+        SortOrder::Updated => {}
+        SortOrder::Age => items.reverse(),
+        SortOrder::Repo => items.sort_by_key(|item| fields(item).1.to_lowercase()),
+        SortOrder::Title => items.sort_by_key(|item| fields(item).2.to_lowercase()),
+    }
+}
+
+fn notification_sort_fields(notification: &Notification) -> (&str, &str, &str) {
+    (
+        &notification.updated_at,
+        &notification.repository.full_name,
+        &notification.subject.title,
+    )
+}
+
+fn pr_sort_fields(pr: &MyPullRequest) -> (&str, &str, &str) {
+    (&pr.updated_at, &pr.repository.full_name, &pr.subject.title)
+}
+
 fn sort_by_updated_at<T>(items: &mut [T], updated_at: impl Fn(&T) -> &str) {
     items.sort_by(|a, b| {
         let a_ts = parse_updated_at(updated_at(a));
@@ -735,7 +1973,8 @@ struct NotificationOverride {
 struct UndoSnapshot {
     notifications: Vec<Notification>,
     my_prs: Vec<MyPullRequest>,
-    ignored_prs: HashSet<String>,
+    ignore_list: IgnoreList,
+    pinned_urls: HashSet<String>,
     notification_overrides: HashMap<String, NotificationOverride>,
 }
 
@@ -784,7 +2023,8 @@ fn snapshot_state(app: &AppState) -> UndoSnapshot {
     UndoSnapshot {
         notifications: app.notifications.clone(),
         my_prs: app.my_prs.clone(),
-        ignored_prs: app.ignored_prs.clone(),
+        ignore_list: app.ignore_list.clone(),
+        pinned_urls: app.pinned_urls.clone(),
         notification_overrides: app.notification_overrides.clone(),
     }
 }
@@ -792,7 +2032,8 @@ fn snapshot_state(app: &AppState) -> UndoSnapshot {
 fn restore_snapshot(app: &mut AppState, snapshot: &UndoSnapshot) {
     app.notifications = snapshot.notifications.clone();
     app.my_prs = snapshot.my_prs.clone();
-    app.ignored_prs = snapshot.ignored_prs.clone();
+    app.ignore_list = snapshot.ignore_list.clone();
+    app.pinned_urls = snapshot.pinned_urls.clone();
     app.notification_overrides = snapshot.notification_overrides.clone();
     app.refresh_relative_times();
 }
@@ -810,6 +2051,7 @@ fn apply_optimistic_update(app: &mut AppState, commands: &HashMap<usize, Vec<Act
         match display_order.get(index.saturating_sub(1)).copied() {
             Some(ui::DisplayEntryKey::Notification(idx)) => {
                 let mut remove = false;
+                let mut read = false;
                 let mut override_state = None;
                 let notification_id = {
                     let Some(notification) = app.notifications.get_mut(idx) else {
@@ -821,9 +2063,7 @@ fn apply_optimistic_update(app: &mut AppState, commands: &HashMap<usize, Vec<Act
                         match action {
                             Action::Open | Action::Read => {
                                 notification.unread = false;
-                                if !app.include_read {
-                                    remove = true;
-                                }
+                                read = true;
                                 record_override_state(
                                     &mut override_state,
                                     NotificationOverrideState::Read,
@@ -841,10 +2081,26 @@ fn apply_optimistic_update(app: &mut AppState, commands: &HashMap<usize, Vec<Act
                             | Action::PrettyYank
                             | Action::Review
                             | Action::ReviewNoAnalyze
-                            | Action::Branch => {}
+                            | Action::Branch
+                            | Action::Worktree
+                            | Action::Pin
+                            | Action::Custom(_) => {}
                         }
                     }
 
+                    if actions.contains(&Action::Pin) {
+                        notification.pinned = !notification.pinned;
+                        toggle_pinned_url(
+                            &mut app.pinned_urls,
+                            &notification.subject.url,
+                            notification.pinned,
+                        );
+                    }
+                    // Pinned threads stay in an unread-only view after being read.
+                    if read && !app.include_read && !notification.pinned {
+                        remove = true;
+                    }
+
                     if remove {
                         remove_notifications.push(idx);
                     }
@@ -861,13 +2117,17 @@ fn apply_optimistic_update(app: &mut AppState, commands: &HashMap<usize, Vec<Act
                 }
             }
             Some(ui::DisplayEntryKey::MyPullRequest(idx)) => {
-                if idx >= app.my_prs.len() {
+                let Some(pr) = app.my_prs.get_mut(idx) else {
                     continue;
+                };
+                if actions.contains(&Action::Pin) {
+                    pr.pinned = !pr.pinned;
+                    toggle_pinned_url(&mut app.pinned_urls, &pr.url, pr.pinned);
                 }
                 let ignore = actions.contains(&Action::Unsubscribe);
                 if ignore {
                     if let Some(pr) = app.my_prs.get(idx) {
-                        app.ignored_prs.insert(pr.url.clone());
+                        app.ignore_list.insert(pr.url.clone());
                     }
                 }
                 if ignore {
@@ -900,6 +2160,14 @@ fn apply_optimistic_update(app: &mut AppState, commands: &HashMap<usize, Vec<Act
     app.refresh_relative_times();
 }
 
+fn toggle_pinned_url(pinned_urls: &mut HashSet<String>, url: &str, pinned: bool) {
+    if pinned {
+        pinned_urls.insert(url.to_string());
+    } else {
+        pinned_urls.remove(url);
+    }
+}
+
 fn apply_undo_optimistic_update(app: &mut AppState, commands: &HashMap<usize, Vec<Action>>) {
     let display_order = ui::display_order(&app.notifications, &app.my_prs);
 
@@ -1015,6 +2283,7 @@ async fn execute_commands(
     commands: &HashMap<usize, Vec<Action>>,
     notifications: &[Notification],
     my_prs: &[MyPullRequest],
+    custom_actions: &[CustomAction],
 ) -> Result<ExecSummary> {
     let mut tasks = Vec::new();
     let (yank_urls, yank_count) = collect_yank_targets(commands, notifications, my_prs);
@@ -1037,9 +2306,14 @@ async fn execute_commands(
             let client = client.clone();
             let token = token.to_string();
             let url = url.clone();
+            let custom = match action {
+                Action::Custom(idx) => custom_actions.get(idx).cloned(),
+                _ => None,
+            };
 
             tasks.push(tokio::spawn(async move {
-                let result = execute_action(&client, &token, action, &entry, &url).await;
+                let result =
+                    execute_action(&client, &token, action, &entry, &url, custom.as_ref()).await;
                 (action, result)
             }));
         }
@@ -1238,6 +2512,7 @@ async fn execute_undo(client: &reqwest::Client, token: &str, batch: &UndoBatch)
         MarkUnread { thread_id: String },
         Resubscribe { thread_id: String },
         Unignore { url: String },
+        Repin { url: String, pinned: bool },
     }
 
     let mut tasks = Vec::new();
@@ -1253,7 +2528,7 @@ async fn execute_undo(client: &reqwest::Client, token: &str, batch: &UndoBatch)
             None => continue,
         };
 
-        match entry {
+        match &entry {
             EntrySnapshot::Notification(notification) => {
                 let mut mark_unread = false;
                 let mut resubscribe = false;
@@ -1271,7 +2546,10 @@ async fn execute_undo(client: &reqwest::Client, token: &str, batch: &UndoBatch)
                         | Action::PrettyYank
                         | Action::Review
                         | Action::ReviewNoAnalyze
-                        | Action::Branch => {}
+                        | Action::Branch
+                        | Action::Worktree
+                        | Action::Pin
+                        | Action::Custom(_) => {}
                     }
                 }
 
@@ -1296,6 +2574,13 @@ async fn execute_undo(client: &reqwest::Client, token: &str, batch: &UndoBatch)
                 }
             }
         }
+
+        if actions.contains(&Action::Pin) {
+            tasks.push(UndoWork::Repin {
+                url: entry.url().to_string(),
+                pinned: entry.pinned(),
+            });
+        }
     }
 
     let attempted = !tasks.is_empty();
@@ -1314,6 +2599,9 @@ async fn execute_undo(client: &reqwest::Client, token: &str, batch: &UndoBatch)
             UndoWork::Unignore { url } => {
                 tokio::spawn(async move { remove_ignored_pr(&url).map(|_| ()) })
             }
+            UndoWork::Repin { url, pinned } => {
+                tokio::spawn(async move { set_pinned_url(&url, pinned).map(|_| ()) })
+            }
         };
         futures.push(future);
     }
@@ -1381,6 +2669,36 @@ fn clean_error_message(message: &str) -> String {
     text
 }
 
+/// A batch shown for confirmation, with its targets pinned to the items they were on.
+#[derive(Debug)]
+pub struct Preview {
+    pub pending: HashMap<usize, Vec<Action>>,
+    // Actions by thread id (or URL for my PRs), which survive a refresh reordering the list.
+    entries: BTreeMap<String, Vec<Action>>,
+    pub scroll: u16,
+}
+
+impl Preview {
+    fn new(
+        pending: HashMap<usize, Vec<Action>>,
+        notifications: &[Notification],
+        my_prs: &[MyPullRequest],
+    ) -> Self {
+        let entries = pending
+            .iter()
+            .filter_map(|(index, actions)| {
+                let entry = entry_for_index(*index, notifications, my_prs)?;
+                Some((entry.identity().to_string(), actions.clone()))
+            })
+            .collect();
+        Self {
+            pending,
+            entries,
+            scroll: 0,
+        }
+    }
+}
+
 #[derive(Clone)]
 enum EntrySnapshot {
     Notification(Notification),
@@ -1388,6 +2706,13 @@ enum EntrySnapshot {
 }
 
 impl EntrySnapshot {
+    fn identity(&self) -> &str {
+        match self {
+            EntrySnapshot::Notification(notification) => &notification.id,
+            EntrySnapshot::MyPullRequest(pr) => &pr.subject.url,
+        }
+    }
+
     fn url(&self) -> &str {
         match self {
             EntrySnapshot::Notification(notification) => &notification.subject.url,
@@ -1408,58 +2733,54 @@ impl EntrySnapshot {
             EntrySnapshot::MyPullRequest(pr) => &pr.repository.full_name,
         }
     }
-}
 
-fn entry_for_index(
-    index: usize,
-    notifications: &[Notification],
-    my_prs: &[MyPullRequest],
-) -> Option<EntrySnapshot> {
-    match ui::display_entry_key(index, notifications, my_prs)? {
-        ui::DisplayEntryKey::Notification(idx) => notifications
-            .get(idx)
-            .cloned()
-            .map(EntrySnapshot::Notification),
-        ui::DisplayEntryKey::MyPullRequest(idx) => {
-            my_prs.get(idx).cloned().map(EntrySnapshot::MyPullRequest)
+    fn pinned(&self) -> bool {
+        match self {
+            EntrySnapshot::Notification(notification) => notification.pinned,
+            EntrySnapshot::MyPullRequest(pr) => pr.pinned,
         }
     }
 }
 
-fn home_dir() -> Result<PathBuf> {
-    if let Ok(value) = std::env::var("HOME") {
-        let trimmed = value.trim();
-        if !trimmed.is_empty() {
-            return Ok(PathBuf::from(trimmed));
-        }
-    }
-
-    if let Ok(value) = std::env::var("USERPROFILE") {
-        let trimmed = value.trim();
-        if !trimmed.is_empty() {
-            return Ok(PathBuf::from(trimmed));
-        }
+fn template_values(entry: &EntrySnapshot) -> TemplateValues {
+    let url = entry.url();
+    let (title, thread_id) = match entry {
+        EntrySnapshot::Notification(notification) => (
+            notification.subject.title.clone(),
+            Some(notification.id.clone()),
+        ),
+        EntrySnapshot::MyPullRequest(pr) => (pr.subject.title.clone(), None),
+    };
+    let number = url
+        .rsplit('/')
+        .next()
+        .filter(|segment| !segment.is_empty() && segment.chars().all(|ch| ch.is_ascii_digit()))
+        .map(str::to_string);
+
+    TemplateValues {
+        url: url.to_string(),
+        repo: entry.repo_full_name().to_string(),
+        number,
+        branch: entry.branch_name().map(str::to_string),
+        title,
+        thread_id,
     }
-
-    Err(anyhow!("HOME/USERPROFILE is not set"))
 }
 
-fn repo_dir_for_full_name(base: &Path, full_name: &str) -> Result<PathBuf> {
-    let mut parts = full_name.split('/');
-    let owner = parts
-        .next()
-        .map(str::trim)
-        .filter(|value| !value.is_empty());
-    let repo = parts
-        .next()
-        .map(str::trim)
-        .filter(|value| !value.is_empty());
-
-    if owner.is_none() || repo.is_none() || parts.next().is_some() {
-        return Err(anyhow!("invalid repository name: {}", full_name));
+fn entry_for_index(
+    index: usize,
+    notifications: &[Notification],
+    my_prs: &[MyPullRequest],
+) -> Option<EntrySnapshot> {
+    match ui::display_entry_key(index, notifications, my_prs)? {
+        ui::DisplayEntryKey::Notification(idx) => notifications
+            .get(idx)
+            .cloned()
+            .map(EntrySnapshot::Notification),
+        ui::DisplayEntryKey::MyPullRequest(idx) => {
+            my_prs.get(idx).cloned().map(EntrySnapshot::MyPullRequest)
+        }
     }
-
-    Ok(base.join(owner.unwrap()).join(repo.unwrap()))
 }
 
 struct TuiGuard<'a> {
@@ -1503,42 +2824,186 @@ impl Drop for TuiGuard<'_> {
     }
 }
 
-fn reviewpr_command(request: &ReviewRequest) -> String {
-    let mut command = format!("ReviewPR {}", request.pr_url);
-    if request.analyze {
-        command.push_str(" --analyze");
+fn reviewpr_command(review: &ReviewSettings, request: &ReviewRequest) -> String {
+    let mut command = format!("{} {}", review.command, request.pr_url);
+    if request.analyze && !review.analyze_flag.is_empty() {
+        command.push(' ');
+        command.push_str(&review.analyze_flag);
     }
     command
 }
 
-fn run_review_in_nvim(
+#[derive(Debug, PartialEq, Eq)]
+struct ReviewInvocation {
+    program: String,
+    args: Vec<String>,
+    dir: PathBuf,
+}
+
+fn review_invocation(
+    review: &ReviewSettings,
+    request: &ReviewRequest,
+    dir: PathBuf,
+) -> Result<ReviewInvocation> {
+    let Some(launch) = &review.launch else {
+        return Ok(ReviewInvocation {
+            program: review.editor.clone(),
+            args: vec!["-c".to_string(), reviewpr_command(review, request)],
+            dir,
+        });
+    };
+
+    let (owner, name) = request
+        .repo_full_name
+        .split_once('/')
+        .unwrap_or((&request.repo_full_name, ""));
+    let command = render_with(launch, |placeholder| {
+        let value = match placeholder {
+            "url" => request.pr_url.as_str(),
+            "repo" => request.repo_full_name.as_str(),
+            "owner" => owner,
+            "name" => name,
+            "number" => request.pr_url.rsplit('/').next().unwrap_or(""),
+            "dir" => return Ok(shell_quote(&dir.to_string_lossy())),
+            // The flag is trusted config text, so it may expand to several words or nothing.
+            "analyze" if request.analyze => return Ok(review.analyze_flag.clone()),
+            "analyze" => return Ok(String::new()),
+            other => return Err(anyhow!("unknown placeholder {{{}}}", other)),
+        };
+        Ok(shell_quote(value))
+    })?;
+
+    Ok(ReviewInvocation {
+        program: "sh".to_string(),
+        args: vec!["-c".to_string(), command],
+        dir,
+    })
+}
+
+fn run_review(
     terminal: &mut Terminal<CrosstermBackend<Stdout>>,
+    review: &ReviewSettings,
     request: &ReviewRequest,
 ) -> Result<()> {
     if !request.pr_url.contains("/pull/") {
-        return Err(anyhow!("ReviewPR only supports pull request URLs"));
+        return Err(anyhow!("{} only supports pull request URLs", review.name()));
     }
 
-    let base = home_dir()?.join("Developer");
-    let repo_dir = repo_dir_for_full_name(&base, &request.repo_full_name)?;
-    if !repo_dir.is_dir() {
-        return Err(anyhow!(
-            "repository directory not found: {}",
-            repo_dir.display()
-        ));
+    let local_dir = match review.workdir {
+        ReviewWorkdir::Repo => find_local_repo(&review.search_roots, &request.repo_full_name)?,
+        ReviewWorkdir::Current => {
+            Some(std::env::current_dir().context("failed to read current directory")?)
+        }
+    };
+
+    let mut guard = TuiGuard::suspend(terminal)?;
+    let dir = match local_dir {
+        Some(dir) => dir,
+        None => clone_for_review(review, &request.repo_full_name)?,
+    };
+    let invocation = review_invocation(review, request, dir)?;
+    let status = Command::new(&invocation.program)
+        .current_dir(&invocation.dir)
+        .args(&invocation.args)
+        .status()
+        .with_context(|| format!("failed to launch {}", invocation.program))?;
+    guard.restore()?;
+
+    if !status.success() {
+        return Err(anyhow!("{} exited with status {}", review.name(), status));
+    }
+
+    Ok(())
+}
+
+/// Runs on the suspended terminal so the prompt and clone progress are visible.
+fn clone_for_review(review: &ReviewSettings, full_name: &str) -> Result<PathBuf> {
+    let dest = repo_dir_for_full_name(&review.clone_root, full_name)?;
+    let not_found = || {
+        let roots: Vec<String> = review
+            .search_roots
+            .iter()
+            .map(|root| root.display().to_string())
+            .collect();
+        anyhow!("{} not found under {}", full_name, roots.join(", "))
+    };
+
+    match review.clone {
+        CloneMode::Never => return Err(not_found()),
+        CloneMode::Ask => {
+            print!(
+                "{} is not cloned locally. Clone into {}? [y/N] ",
+                full_name,
+                dest.display()
+            );
+            io::stdout().flush().ok();
+            let mut answer = String::new();
+            io::stdin()
+                .read_line(&mut answer)
+                .context("failed to read answer")?;
+            if !matches!(answer.trim(), "y" | "Y" | "yes") {
+                return Err(not_found());
+            }
+        }
+        CloneMode::Always => {}
+    }
+
+    clone_repo(full_name, &dest)?;
+    Ok(dest)
+}
+
+fn run_worktree(
+    terminal: &mut Terminal<CrosstermBackend<Stdout>>,
+    settings: &Settings,
+    request: &WorktreeRequest,
+) -> Result<PathBuf> {
+    let local_dir = find_local_repo(&settings.review.search_roots, &request.repo_full_name)?;
+
+    let mut guard = TuiGuard::suspend(terminal)?;
+    let repo_dir = match local_dir {
+        Some(dir) => dir,
+        None => clone_for_review(&settings.review, &request.repo_full_name)?,
+    };
+    let path = prepare_worktree(&repo_dir, &settings.worktree.dir, request)
+        .with_context(|| format!("failed to prepare worktree for #{}", request.number))?;
+
+    let program = match settings.worktree.open {
+        WorktreeOpen::None => None,
+        WorktreeOpen::Editor => Some(settings.review.editor.clone()),
+        WorktreeOpen::Shell => Some(std::env::var("SHELL").unwrap_or_else(|_| "sh".to_string())),
+    };
+    if let Some(program) = program {
+        let mut command = Command::new(&program);
+        command.current_dir(&path);
+        if settings.worktree.open == WorktreeOpen::Editor {
+            command.arg(".");
+        }
+        let status = command
+            .status()
+            .with_context(|| format!("failed to launch {}", program))?;
+        if !status.success() {
+            return Err(anyhow!("{} exited with status {}", program, status));
+        }
     }
+    guard.restore()?;
 
+    Ok(path)
+}
+
+fn run_shell_in_foreground(
+    terminal: &mut Terminal<CrosstermBackend<Stdout>>,
+    request: &ShellRequest,
+) -> Result<()> {
     let mut guard = TuiGuard::suspend(terminal)?;
-    let status = Command::new("nvim")
-        .current_dir(&repo_dir)
+    let status = Command::new("sh")
         .arg("-c")
-        .arg(reviewpr_command(request))
+        .arg(&request.command)
         .status()
-        .context("failed to launch nvim")?;
+        .with_context(|| format!("failed to run {}", request.name))?;
     guard.restore()?;
 
     if !status.success() {
-        return Err(anyhow!("nvim exited with status {}", status));
+        return Err(anyhow!("{} exited with status {}", request.name, status));
     }
 
     Ok(())
@@ -1567,6 +3032,7 @@ async fn execute_action(
     action: Action,
     entry: &EntrySnapshot,
     url: &str,
+    custom: Option<&CustomAction>,
 ) -> Result<ActionOutcome> {
     let refresh = false;
     match action {
@@ -1628,6 +3094,21 @@ async fn execute_action(
                 "ReviewPR should be triggered via the 'p' or 'P' action in the UI"
             ));
         }
+        Action::Worktree => {
+            return Err(anyhow!(
+                "Worktree checkout should be triggered via the 'W' action in the UI"
+            ));
+        }
+        Action::Pin => {
+            // Pins are keyed by subject URL so they survive read/done and re-fetches.
+            set_pinned_url(url, !entry.pinned())?;
+        }
+        Action::Custom(_) => {
+            let custom = custom.ok_or_else(|| anyhow!("Custom action is not configured"))?;
+            let command = render_command(&custom.command, &template_values(entry))?;
+            let custom = custom.clone();
+            tokio::task::spawn_blocking(move || run_in_background(&custom, &command)).await??;
+        }
     }
 
     Ok(ActionOutcome { refresh })
@@ -1645,15 +3126,24 @@ mod tests {
     use super::{
         apply_optimistic_update, apply_undo_optimistic_update, clean_error_message,
         collect_pretty_yank_targets, collect_yank_targets, command_status, entry_for_index,
-        format_pretty_pull_request, handle_text_input, is_api_action, parse_updated_at,
-        repo_dir_for_full_name, reviewpr_command, sort_by_updated_at, split_review_action,
-        undo_status, AppState, EntrySnapshot, ExecSummary, NotificationOverride,
-        NotificationOverrideState, PrettyPullRequest, ReviewRequest, UndoSummary,
+        format_pretty_pull_request, handle_ex_input, handle_history_search, handle_text_input,
+        ignore_warning_status, is_api_action, parse_updated_at, pr_sort_fields, review_invocation,
+        reviewpr_command, sort_by_updated_at, sort_entries, split_foreground_actions,
+        split_review_action, split_worktree_action, submit_commands, undo_status, watched_inbox,
+        AppState, Args, EntrySnapshot, ExecSummary, IgnoreCandidate, NotificationOverride,
+        NotificationOverrideState, PrettyPullRequest, Preview, ReviewRequest, UndoSummary,
     };
+    use clap::Parser;
     use crossterm::event::{KeyCode, KeyEvent, KeyEventKind, KeyEventState, KeyModifiers};
     use std::collections::{HashMap, HashSet};
     use std::path::PathBuf;
+    use tokio::sync::mpsc;
 
+    use crate::config::{CloneMode, ReviewSettings, ReviewWorkdir};
+    use crate::custom_actions::CustomAction;
+    use crate::ex::SortOrder;
+    use crate::filter::Filter;
+    use crate::ignore::IgnoreList;
     use crate::types::{Action, MyPullRequest, Notification, Repository, Subject};
 
     fn key_event(code: KeyCode, modifiers: KeyModifiers) -> KeyEvent {
@@ -1665,12 +3155,26 @@ mod tests {
         }
     }
 
+    fn sample_review_settings() -> ReviewSettings {
+        ReviewSettings {
+            search_roots: vec![PathBuf::from("/tmp/base")],
+            clone: CloneMode::Never,
+            clone_root: PathBuf::from("/tmp/base"),
+            editor: "nvim".to_string(),
+            command: "ReviewPR".to_string(),
+            launch: None,
+            analyze_flag: "--analyze".to_string(),
+            workdir: ReviewWorkdir::Repo,
+        }
+    }
+
     fn sample_notification(unread: bool) -> Notification {
         Notification {
             id: "thread-1".to_string(),
             node_id: "node-1".to_string(),
             subject_id: Some("subject-1".to_string()),
             unread,
+            pinned: false,
             reason: "mention".to_string(),
             updated_at: "2024-01-01T00:00:00Z".to_string(),
             subject: Subject {
@@ -1704,6 +3208,7 @@ mod tests {
         MyPullRequest {
             id: "pr-1".to_string(),
             updated_at: updated_at.to_string(),
+            pinned: false,
             subject: Subject {
                 title: "My PR".to_string(),
                 url: url.to_string(),
@@ -1726,7 +3231,7 @@ mod tests {
 
     #[test]
     fn ctrl_u_clears_line() {
-        let mut app = AppState::new(true, HashSet::new());
+        let mut app = AppState::new(true, IgnoreList::default());
         for ch in ['1', 'o', '2', 'r'] {
             handle_text_input(&mut app, key_event(KeyCode::Char(ch), KeyModifiers::NONE));
         }
@@ -1740,7 +3245,7 @@ mod tests {
 
     #[test]
     fn cmd_left_inserts_at_start() {
-        let mut app = AppState::new(true, HashSet::new());
+        let mut app = AppState::new(true, IgnoreList::default());
         for ch in ['1', 'o', '2'] {
             handle_text_input(&mut app, key_event(KeyCode::Char(ch), KeyModifiers::NONE));
         }
@@ -1752,7 +3257,7 @@ mod tests {
 
     #[test]
     fn cmd_backspace_clears_line() {
-        let mut app = AppState::new(true, HashSet::new());
+        let mut app = AppState::new(true, IgnoreList::default());
         for ch in ['1', 'o', '2'] {
             handle_text_input(&mut app, key_event(KeyCode::Char(ch), KeyModifiers::NONE));
         }
@@ -1762,15 +3267,77 @@ mod tests {
     }
 
     #[test]
-    fn ignores_unrecognized_chars() {
-        let mut app = AppState::new(true, HashSet::new());
+    fn reports_unrecognized_chars() {
+        let mut app = AppState::new(true, IgnoreList::default());
         handle_text_input(&mut app, key_event(KeyCode::Char('z'), KeyModifiers::NONE));
+        assert_eq!(app.command_text(), "z");
+        assert_eq!(app.command_error.as_deref(), Some("unknown key 'z'"));
+
+        handle_text_input(&mut app, key_event(KeyCode::Backspace, KeyModifiers::NONE));
+        assert_eq!(app.command_error, None);
+    }
+
+    #[test]
+    fn up_and_down_recall_history() {
+        let mut app = AppState::new(true, IgnoreList::default());
+        app.history.push("1o").unwrap();
+        app.history.push("2d").unwrap();
+        handle_text_input(&mut app, key_event(KeyCode::Char('3'), KeyModifiers::NONE));
+
+        handle_text_input(&mut app, key_event(KeyCode::Up, KeyModifiers::NONE));
+        assert_eq!(app.command_text(), "2d");
+        handle_text_input(&mut app, key_event(KeyCode::Up, KeyModifiers::NONE));
+        assert_eq!(app.command_text(), "1o");
+        handle_text_input(&mut app, key_event(KeyCode::Down, KeyModifiers::NONE));
+        handle_text_input(&mut app, key_event(KeyCode::Down, KeyModifiers::NONE));
+        assert_eq!(app.command_text(), "3");
+    }
+
+    #[test]
+    fn ctrl_r_searches_history() {
+        let mut app = AppState::new(true, IgnoreList::default());
+        for entry in ["md cd", "1o", "fd r"] {
+            app.history.push(entry).unwrap();
+        }
+        handle_text_input(
+            &mut app,
+            key_event(KeyCode::Char('r'), KeyModifiers::CONTROL),
+        );
+        handle_history_search(&mut app, key_event(KeyCode::Char('d'), KeyModifiers::NONE));
+        assert_eq!(app.command_text(), "fd r");
+        handle_history_search(
+            &mut app,
+            key_event(KeyCode::Char('r'), KeyModifiers::CONTROL),
+        );
+        assert_eq!(app.command_text(), "md cd");
+        handle_history_search(&mut app, key_event(KeyCode::Enter, KeyModifiers::NONE));
+        assert!(app.history_search.is_none());
+        assert_eq!(app.command_text(), "md cd");
+
+        app.clear_commands();
+        handle_text_input(
+            &mut app,
+            key_event(KeyCode::Char('r'), KeyModifiers::CONTROL),
+        );
+        handle_history_search(&mut app, key_event(KeyCode::Char('o'), KeyModifiers::NONE));
+        assert_eq!(app.command_text(), "1o");
+        handle_history_search(&mut app, key_event(KeyCode::Esc, KeyModifiers::NONE));
         assert_eq!(app.command_text(), "");
     }
 
+    #[test]
+    fn macros_expand_while_typing() {
+        let mut app = AppState::new(true, IgnoreList::default());
+        app.macros.insert("triage".to_string(), "md cd".to_string());
+        app.set_command(":triage");
+        assert_eq!(app.command_error, None);
+        app.set_command(":nope");
+        assert_eq!(app.command_error.as_deref(), Some("unknown macro ':nope'"));
+    }
+
     #[test]
     fn allows_range_and_separator_chars() {
-        let mut app = AppState::new(true, HashSet::new());
+        let mut app = AppState::new(true, IgnoreList::default());
         for ch in ['1', '-', '3', ',', '2', 'q'] {
             handle_text_input(&mut app, key_event(KeyCode::Char(ch), KeyModifiers::NONE));
         }
@@ -1779,7 +3346,7 @@ mod tests {
 
     #[test]
     fn allows_review_and_unread_targets() {
-        let mut app = AppState::new(true, HashSet::new());
+        let mut app = AppState::new(true, IgnoreList::default());
         for ch in ['u', '?'] {
             handle_text_input(&mut app, key_event(KeyCode::Char(ch), KeyModifiers::NONE));
         }
@@ -1788,14 +3355,14 @@ mod tests {
 
     #[test]
     fn allows_review_without_analyze_input() {
-        let mut app = AppState::new(true, HashSet::new());
+        let mut app = AppState::new(true, IgnoreList::default());
         handle_text_input(&mut app, key_event(KeyCode::Char('P'), KeyModifiers::NONE));
         assert_eq!(app.command_text(), "P");
     }
 
     #[test]
     fn allows_undo_command_input() {
-        let mut app = AppState::new(true, HashSet::new());
+        let mut app = AppState::new(true, IgnoreList::default());
         handle_text_input(&mut app, key_event(KeyCode::Char('U'), KeyModifiers::NONE));
         assert_eq!(app.command_text(), "U");
     }
@@ -1876,6 +3443,64 @@ mod tests {
         assert_eq!(filtered.get(&2), Some(&vec![Action::Read]));
     }
 
+    #[test]
+    fn split_foreground_actions_renders_templates() {
+        let notifications = vec![sample_notification(true)];
+        let custom_actions = vec![
+            CustomAction {
+                name: "checkout".to_string(),
+                command: "gh pr checkout {number} -R {repo} # {thread_id}".to_string(),
+                foreground: true,
+                destructive: false,
+            },
+            CustomAction {
+                name: "chat".to_string(),
+                command: "chat post {url}".to_string(),
+                foreground: false,
+                destructive: false,
+            },
+        ];
+        let mut commands = HashMap::new();
+        commands.insert(1, vec![Action::Custom(0), Action::Custom(1), Action::Read]);
+
+        let (requests, remaining) =
+            split_foreground_actions(&commands, &notifications, &[], &custom_actions).unwrap();
+        assert_eq!(requests.len(), 1);
+        assert_eq!(
+            requests[0].command,
+            "gh pr checkout '42' -R 'acme/widgets' # 'thread-1'"
+        );
+        assert_eq!(
+            remaining.get(&1),
+            Some(&vec![Action::Custom(1), Action::Read])
+        );
+
+        // My PRs have no notification thread, so {thread_id} cannot be filled.
+        let my_prs = vec![sample_my_pr()];
+        let mut commands = HashMap::new();
+        commands.insert(1, vec![Action::Custom(0)]);
+        assert!(split_foreground_actions(&commands, &[], &my_prs, &custom_actions).is_err());
+    }
+
+    #[test]
+    fn split_worktree_action_requires_pull_requests() {
+        let my_prs = vec![sample_my_pr()];
+        let mut commands = HashMap::new();
+        commands.insert(1, vec![Action::Worktree, Action::Pin]);
+
+        let (targets, remaining) = split_worktree_action(&commands, &[], &my_prs).unwrap();
+        assert_eq!(targets.len(), 1);
+        assert_eq!(targets[0].repo_full_name, "acme/widgets");
+        assert_eq!(targets[0].number, 100);
+        assert_eq!(remaining.get(&1), Some(&vec![Action::Pin]));
+
+        let mut issue = sample_notification(true);
+        issue.subject.url = "https://github.com/acme/widgets/issues/7".to_string();
+        let mut commands = HashMap::new();
+        commands.insert(1, vec![Action::Worktree]);
+        assert!(split_worktree_action(&commands, &[issue], &[]).is_err());
+    }
+
     #[test]
     fn split_review_action_does_not_mark_my_pr_as_read() {
         let notifications = Vec::new();
@@ -1903,7 +3528,7 @@ mod tests {
         };
 
         assert_eq!(
-            reviewpr_command(&request),
+            reviewpr_command(&sample_review_settings(), &request),
             "ReviewPR https://github.com/acme/widgets/pull/42 --analyze"
         );
     }
@@ -1917,11 +3542,68 @@ mod tests {
         };
 
         assert_eq!(
-            reviewpr_command(&request),
+            reviewpr_command(&sample_review_settings(), &request),
             "ReviewPR https://github.com/acme/widgets/pull/42"
         );
     }
 
+    #[test]
+    fn review_invocation_renders_launch_template() {
+        let review = ReviewSettings {
+            launch: Some(
+                "tmux new-window -c {dir} \"gh pr diff {number} -R {repo} {analyze}\"".to_string(),
+            ),
+            analyze_flag: "--name-only".to_string(),
+            workdir: ReviewWorkdir::Current,
+            ..sample_review_settings()
+        };
+        let request = ReviewRequest {
+            repo_full_name: "acme/widgets".to_string(),
+            pr_url: "https://github.com/acme/widgets/pull/42".to_string(),
+            analyze: true,
+        };
+
+        let invocation = review_invocation(&review, &request, PathBuf::from("/work")).unwrap();
+        assert_eq!(invocation.program, "sh");
+        assert_eq!(invocation.dir, PathBuf::from("/work"));
+        assert_eq!(
+            invocation.args,
+            vec![
+                "-c".to_string(),
+                "tmux new-window -c '/work' \"gh pr diff '42' -R 'acme/widgets' --name-only\""
+                    .to_string()
+            ]
+        );
+
+        let request = ReviewRequest {
+            analyze: false,
+            ..request
+        };
+        let invocation = review_invocation(&review, &request, PathBuf::from("/work")).unwrap();
+        assert!(invocation.args[1].ends_with("-R 'acme/widgets' \""));
+    }
+
+    #[test]
+    fn review_invocation_defaults_to_reviewpr_in_nvim() {
+        let request = ReviewRequest {
+            repo_full_name: "acme/widgets".to_string(),
+            pr_url: "https://github.com/acme/widgets/pull/42".to_string(),
+            analyze: true,
+        };
+        let dir = PathBuf::from("/tmp/base/acme/widgets");
+        let invocation =
+            review_invocation(&sample_review_settings(), &request, dir.clone()).unwrap();
+        assert_eq!(invocation.program, "nvim");
+        assert_eq!(invocation.dir, dir);
+        assert_eq!(
+            invocation.args,
+            vec![
+                "-c".to_string(),
+                "ReviewPR https://github.com/acme/widgets/pull/42 --analyze".to_string()
+            ]
+        );
+    }
+
     #[test]
     fn command_status_success_message() {
         let result = ExecSummary {
@@ -2019,7 +3701,7 @@ mod tests {
 
     #[test]
     fn open_marks_read_in_optimistic_update() {
-        let mut app = AppState::new(true, HashSet::new());
+        let mut app = AppState::new(true, IgnoreList::default());
         app.notifications = vec![sample_notification(true)];
 
         let mut commands = HashMap::new();
@@ -2031,7 +3713,7 @@ mod tests {
 
     #[test]
     fn open_removes_in_unread_only_view() {
-        let mut app = AppState::new(false, HashSet::new());
+        let mut app = AppState::new(false, IgnoreList::default());
         app.notifications = vec![sample_notification(true)];
 
         let mut commands = HashMap::new();
@@ -2043,7 +3725,7 @@ mod tests {
 
     #[test]
     fn open_records_read_override() {
-        let mut app = AppState::new(true, HashSet::new());
+        let mut app = AppState::new(true, IgnoreList::default());
         app.notifications = vec![sample_notification(true)];
         let id = app.notifications[0].id.clone();
 
@@ -2057,7 +3739,7 @@ mod tests {
 
     #[test]
     fn done_records_suppress_override() {
-        let mut app = AppState::new(true, HashSet::new());
+        let mut app = AppState::new(true, IgnoreList::default());
         app.notifications = vec![sample_notification(true)];
         let id = app.notifications[0].id.clone();
 
@@ -2071,7 +3753,7 @@ mod tests {
 
     #[test]
     fn undo_marks_notifications_unread() {
-        let mut app = AppState::new(true, HashSet::new());
+        let mut app = AppState::new(true, IgnoreList::default());
         app.notifications = vec![sample_notification(false)];
 
         let mut commands = HashMap::new();
@@ -2191,8 +3873,11 @@ mod tests {
         let pr = PrettyPullRequest {
             url: "https://github.com/acme/widgets/pull/42".to_string(),
             title: "Add feature".to_string(),
+            state: "OPEN".to_string(),
+            is_draft: false,
             additions: 10,
             deletions: 2,
+            head_ref: "feature".to_string(),
             head_repo_owner: "octocat".to_string(),
             head_repo_name: "widgets".to_string(),
         };
@@ -2203,23 +3888,9 @@ mod tests {
         );
     }
 
-    #[test]
-    fn repo_dir_for_full_name_builds_path() {
-        let base = PathBuf::from("/tmp/base");
-        let path = repo_dir_for_full_name(&base, "acme/widgets").unwrap();
-        assert_eq!(path, base.join("acme").join("widgets"));
-    }
-
-    #[test]
-    fn repo_dir_for_full_name_rejects_invalid() {
-        let base = PathBuf::from("/tmp/base");
-        assert!(repo_dir_for_full_name(&base, "acme").is_err());
-        assert!(repo_dir_for_full_name(&base, "acme/widgets/extra").is_err());
-    }
-
     #[test]
     fn unsubscribe_ignores_my_pr_optimistically() {
-        let mut app = AppState::new(true, HashSet::new());
+        let mut app = AppState::new(true, IgnoreList::default());
         app.my_prs = vec![sample_my_pr()];
 
         let mut commands = HashMap::new();
@@ -2228,13 +3899,123 @@ mod tests {
         apply_optimistic_update(&mut app, &commands);
         assert!(app.my_prs.is_empty());
         assert!(app
-            .ignored_prs
+            .ignore_list
             .contains("https://github.com/acme/widgets/pull/100"));
     }
 
+    #[test]
+    fn pin_toggles_optimistically() {
+        let mut app = AppState::new(true, IgnoreList::default());
+        app.notifications = vec![sample_notification(true)];
+        app.my_prs = vec![sample_my_pr()];
+
+        let mut commands = HashMap::new();
+        commands.insert(1, vec![Action::Pin]);
+        apply_optimistic_update(&mut app, &commands);
+        assert!(app.notifications[0].pinned);
+        assert!(app
+            .pinned_urls
+            .contains("https://github.com/acme/widgets/pull/42"));
+
+        // Pinned items render first, so the same thread is still #1.
+        let mut commands = HashMap::new();
+        commands.insert(1, vec![Action::Read, Action::Pin]);
+        apply_optimistic_update(&mut app, &commands);
+        assert!(!app.notifications[0].pinned);
+        assert!(!app.notifications[0].unread);
+        assert!(app.pinned_urls.is_empty());
+    }
+
+    #[test]
+    fn reading_a_pinned_item_keeps_it_in_the_unread_only_view() {
+        let mut app = AppState::new(false, IgnoreList::default());
+        let mut pinned = sample_notification(true);
+        pinned.subject.url = "https://github.com/acme/widgets/pull/100".to_string();
+        app.pinned_urls.insert(pinned.subject.url.clone());
+        app.set_data(vec![pinned.clone()], Vec::new());
+
+        let mut commands = HashMap::new();
+        commands.insert(1, vec![Action::Read]);
+        apply_optimistic_update(&mut app, &commands);
+        assert_eq!(app.notifications.len(), 1);
+        assert!(!app.notifications[0].unread);
+
+        // The next poll returns it read, and the Read override must not drop it either.
+        pinned.unread = false;
+        app.set_data(vec![pinned], Vec::new());
+        assert_eq!(app.notifications.len(), 1);
+        assert!(app.notifications[0].pinned);
+
+        // Unpinned items still leave the unread-only view once read.
+        let mut commands = HashMap::new();
+        commands.insert(1, vec![Action::Read, Action::Pin]);
+        apply_optimistic_update(&mut app, &commands);
+        assert!(app.notifications.is_empty());
+    }
+
+    #[test]
+    fn watched_inbox_matches_what_the_view_lists() {
+        let unread = sample_notification(true);
+        let mut read = sample_notification(false);
+        read.id = "read".to_string();
+        read.subject.url = "https://github.com/acme/widgets/pull/7".to_string();
+        let mut ignored = sample_notification(true);
+        ignored.id = "ignored".to_string();
+        ignored.subject.url = "https://github.com/acme/widgets/pull/8".to_string();
+        let notifications = vec![unread, read, ignored];
+        let ignore_list = IgnoreList::parse(
+            "https://github.com/acme/widgets/pull/8\nhttps://github.com/acme/widgets/pull/100\n",
+        );
+        let now = chrono::NaiveDate::from_ymd_opt(2024, 1, 1)
+            .unwrap()
+            .and_hms_opt(0, 0, 0)
+            .unwrap();
+        let ids = |notifications: &[crate::Notification]| -> Vec<String> {
+            notifications.iter().map(|n| n.id.clone()).collect()
+        };
+
+        let (watched, my_prs) = watched_inbox(
+            &notifications,
+            &[sample_my_pr()],
+            false,
+            &HashSet::new(),
+            &ignore_list,
+            now,
+        );
+        assert_eq!(ids(&watched), vec![notifications[0].id.clone()]);
+        assert!(my_prs.is_empty());
+
+        let pinned = HashSet::from(["https://github.com/acme/widgets/pull/7".to_string()]);
+        let (watched, _) = watched_inbox(&notifications, &[], false, &pinned, &ignore_list, now);
+        assert_eq!(ids(&watched).len(), 2);
+        let (watched, _) = watched_inbox(
+            &notifications,
+            &[],
+            true,
+            &HashSet::new(),
+            &ignore_list,
+            now,
+        );
+        assert_eq!(ids(&watched)[1], "read");
+        assert_eq!(watched.len(), 2);
+    }
+
+    #[test]
+    fn set_data_marks_pinned_entries() {
+        let mut app = AppState::new(true, IgnoreList::default());
+        app.pinned_urls
+            .insert("https://github.com/acme/widgets/pull/100".to_string());
+        let mut read = sample_notification(false);
+        read.subject.url = "https://github.com/acme/widgets/pull/100".to_string();
+
+        app.set_data(vec![read], vec![sample_my_pr()]);
+        assert!(app.notifications[0].pinned);
+        assert!(app.my_prs[0].pinned);
+    }
+
     #[test]
     fn unsubscribe_removes_notification_optimistically() {
-        let mut app = AppState::new(true, HashSet::new());
+        let mut app = AppState::new(true, IgnoreList::default());
         app.notifications = vec![sample_notification(true)];
 
         let mut commands = HashMap::new();
@@ -2246,7 +4027,7 @@ mod tests {
 
     #[test]
     fn set_data_filters_ignored_prs() {
-        let mut ignored = HashSet::new();
+        let mut ignored = IgnoreList::default();
         ignored.insert("https://github.com/acme/widgets/pull/100".to_string());
 
         let mut app = AppState::new(true, ignored);
@@ -2267,9 +4048,35 @@ mod tests {
         );
     }
 
+    #[test]
+    fn set_data_applies_ignore_rules_to_notifications() {
+        let ignored = IgnoreList::parse("repo:acme/*\nauthor:dependabot[bot]\n");
+        let mut app = AppState::new(true, ignored);
+
+        let mut bot = sample_notification(true);
+        bot.repository.full_name = "other/tools".to_string();
+        bot.subject.author = Some("dependabot[bot]".to_string());
+        let mut kept = sample_notification(true);
+        kept.id = "thread-2".to_string();
+        kept.repository.full_name = "other/tools".to_string();
+
+        app.set_data(vec![sample_notification(true), bot, kept], Vec::new());
+        assert_eq!(app.notifications.len(), 1);
+        assert_eq!(app.notifications[0].id, "thread-2");
+        assert_eq!(app.hidden_notifications.len(), 2);
+
+        let candidates: Vec<_> = app
+            .hidden_notifications
+            .iter()
+            .map(IgnoreCandidate::from_notification)
+            .collect();
+        let now = chrono::Utc::now().naive_utc();
+        assert_eq!(app.ignore_list.hidden_counts(&candidates, now), vec![1, 1]);
+    }
+
     #[test]
     fn set_data_preserves_read_override_on_stale_fetch() {
-        let mut app = AppState::new(true, HashSet::new());
+        let mut app = AppState::new(true, IgnoreList::default());
         let notification = sample_notification(true);
         let marked_at = parse_updated_at(&notification.updated_at) + 60;
         let id = notification.id.clone();
@@ -2289,7 +4096,7 @@ mod tests {
 
     #[test]
     fn set_data_clears_read_override_on_new_activity() {
-        let mut app = AppState::new(true, HashSet::new());
+        let mut app = AppState::new(true, IgnoreList::default());
         let mut notification = sample_notification(true);
         let marked_at = parse_updated_at(&notification.updated_at);
         notification.updated_at = "2024-01-02T00:00:00Z".to_string();
@@ -2310,7 +4117,7 @@ mod tests {
 
     #[test]
     fn set_data_suppresses_done_until_new_activity() {
-        let mut app = AppState::new(true, HashSet::new());
+        let mut app = AppState::new(true, IgnoreList::default());
         let notification = sample_notification(true);
         let marked_at = parse_updated_at(&notification.updated_at) + 60;
         let id = notification.id.clone();
@@ -2368,6 +4175,165 @@ mod tests {
         assert_eq!(prs[1].url, "https://github.com/acme/widgets/pull/1");
     }
 
+    #[test]
+    fn sort_entries_orders_by_age_repo_and_title() {
+        let mut prs = vec![
+            sample_my_pr_with_url(
+                "https://github.com/acme/widgets/pull/1",
+                "2024-01-01T00:00:00Z",
+            ),
+            sample_my_pr_with_url(
+                "https://github.com/acme/widgets/pull/2",
+                "2024-01-03T00:00:00Z",
+            ),
+        ];
+        prs[0].subject.title = "Add feature".to_string();
+        prs[1].subject.title = "bump deps".to_string();
+
+        sort_entries(&mut prs, SortOrder::Age, pr_sort_fields);
+        assert_eq!(prs[0].url, "https://github.com/acme/widgets/pull/1");
+        sort_entries(&mut prs, SortOrder::Repo, pr_sort_fields);
+        assert_eq!(prs[0].url, "https://github.com/acme/widgets/pull/2");
+        sort_entries(&mut prs, SortOrder::Title, pr_sort_fields);
+        assert_eq!(prs[0].subject.title, "Add feature");
+    }
+
+    #[test]
+    fn ex_prompt_runs_and_reports_errors() {
+        let (refresh_tx, _refresh_rx) = mpsc::channel(1);
+        let (event_tx, _event_rx) = mpsc::channel(1);
+        let client = reqwest::Client::new();
+        let mut app = AppState::new(true, IgnoreList::default());
+        app.set_data(vec![sample_notification(true)], Vec::new());
+
+        let run = |app: &mut AppState, text: &str| {
+            app.open_ex();
+            for ch in text.chars() {
+                handle_ex_input(
+                    app,
+                    key_event(KeyCode::Char(ch), KeyModifiers::NONE),
+                    &refresh_tx,
+                    &event_tx,
+                    &client,
+                    "token",
+                )
+                .unwrap();
+            }
+            handle_ex_input(
+                app,
+                key_event(KeyCode::Enter, KeyModifiers::NONE),
+                &refresh_tx,
+                &event_tx,
+                &client,
+                "token",
+            )
+            .unwrap()
+        };
+
+        assert!(!run(&mut app, "sort zz"));
+        assert!(app.ex_input.is_some());
+        assert_eq!(
+            app.ex_error.as_deref(),
+            Some("unknown sort order 'zz' (updated, age, repo, title)")
+        );
+
+        assert!(!run(&mut app, "sort age"));
+        assert!(app.ex_input.is_none());
+        assert_eq!(app.sort, SortOrder::Age);
+
+        assert!(!run(&mut app, "filter is:read"));
+        assert_eq!(app.filter.as_ref().map(Filter::query), Some("is:read"));
+        assert!(!run(&mut app, "filter"));
+        assert!(app.filter.is_none());
+
+        assert!(!run(&mut app, "reload-config"));
+        assert!(app.reload_config);
+        assert!(!run(&mut app, "help"));
+        assert_eq!(app.help, Some(0));
+        assert!(run(&mut app, "q"));
+    }
+
+    #[test]
+    fn all_flag_overrides_unread_only_from_config() {
+        let overrides = |args: &[&str]| {
+            Args::try_parse_from(std::iter::once("ghn").chain(args.iter().copied()))
+                .unwrap()
+                .overrides()
+                .unread_only
+        };
+        assert_eq!(overrides(&[]), None);
+        assert_eq!(overrides(&["--unread-only"]), Some(true));
+        assert_eq!(overrides(&["--all"]), Some(false));
+        assert!(Args::try_parse_from(["ghn", "--all", "--unread-only"]).is_err());
+    }
+
+    #[test]
+    fn ignore_warning_status_names_skipped_lines() {
+        assert_eq!(ignore_warning_status(&IgnoreList::default()), None);
+        let list = IgnoreList::parse("repo:\nrepo:acme/*\nauthor:\n");
+        assert_eq!(
+            ignore_warning_status(&list).unwrap(),
+            format!(
+                "Skipped 2 invalid rules: {} (and 1 more)",
+                list.warnings()[0]
+            )
+        );
+    }
+
+    #[test]
+    fn confirming_a_preview_checks_the_items_not_the_numbers() {
+        let (event_tx, _event_rx) = mpsc::channel(1);
+        let client = reqwest::Client::new();
+        let mut app = AppState::new(true, IgnoreList::default());
+        app.set_data(vec![sample_notification(true)], Vec::new());
+        app.set_command("1q");
+        submit_commands(&mut app, &event_tx, &client, "token", false).unwrap();
+        assert!(app.preview.is_some());
+
+        // A refresh puts a different thread at number 1 while the preview is open.
+        let mut other = sample_notification(true);
+        other.id = "thread-2".to_string();
+        app.set_data(vec![other], Vec::new());
+        submit_commands(&mut app, &event_tx, &client, "token", true).unwrap();
+        assert_eq!(
+            app.status.as_deref(),
+            Some("Targets changed since the preview; check again")
+        );
+        assert!(app.preview.is_some());
+        assert_eq!(app.command_text(), "1q");
+    }
+
+    #[test]
+    fn preview_scrolling_stops_at_the_last_item() {
+        let mut app = AppState::new(true, IgnoreList::default());
+        app.screen = ratatui::layout::Rect::new(0, 0, 100, 20);
+        let pending: HashMap<usize, Vec<Action>> =
+            (1..=15).map(|index| (index, vec![Action::Done])).collect();
+        app.preview = Some(Preview::new(pending, &[], &[]));
+        app.scroll_preview(10);
+        assert_eq!(app.preview.as_ref().unwrap().scroll, 4);
+        app.scroll_preview(-1);
+        assert_eq!(app.preview.as_ref().unwrap().scroll, 3);
+        app.scroll_preview(-10);
+        assert_eq!(app.preview.as_ref().unwrap().scroll, 0);
+    }
+
+    #[test]
+    fn help_scrolling_stops_at_the_last_line() {
+        let mut app = AppState::new(true, IgnoreList::default());
+        app.screen = ratatui::layout::Rect::new(0, 0, 100, 20);
+        let max_scroll = crate::ui::help_max_scroll(&app.keymap, &app.macros, app.screen);
+        assert!(max_scroll > 0);
+        app.help = Some(0);
+        app.scroll_help(1000);
+        assert_eq!(app.help, Some(max_scroll));
+        // One step back moves the view immediately, with no overshoot to unwind.
+        app.scroll_help(-1);
+        assert_eq!(app.help, Some(max_scroll - 1));
+        app.scroll_help(-1000);
+        assert_eq!(app.help, Some(0));
+    }
+
     #[test]
     fn entry_for_index_maps_notifications_and_prs() {
         let notifications = vec![sample_notification(true), sample_notification(false)];
@@ -0,0 +1,134 @@
+use std::{
+    collections::HashSet,
+    path::{Path, PathBuf},
+    sync::{Mutex, OnceLock},
+};
+
+use anyhow::Result;
+
+use crate::{
+    store::{config_home, read_lines, write_lines},
+    types::Notification,
+};
+
+const PINS_RELATIVE_PATH: &str = "ghn/pins.txt";
+const PIN_LIST: &str = "pin list";
+
+static PINS_LOCK: OnceLock<Mutex<()>> = OnceLock::new();
+
+fn pins_lock() -> &'static Mutex<()> {
+    PINS_LOCK.get_or_init(|| Mutex::new(()))
+}
+
+pub fn pins_path() -> Result<PathBuf> {
+    Ok(config_home()?.join(PINS_RELATIVE_PATH))
+}
+
+pub fn load_pinned_urls() -> Result<HashSet<String>> {
+    let path = pins_path()?;
+    read_pinned_urls(&path)
+}
+
+fn read_pinned_urls(path: &Path) -> Result<HashSet<String>> {
+    Ok(read_lines(path, PIN_LIST)?
+        .iter()
+        .map(|line| line.trim())
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(str::to_string)
+        .collect())
+}
+
+/// Drops read threads for an unread-only view, keeping pinned ones so a pin outlives reading it.
+pub fn retain_unread_or_pinned(notifications: &mut Vec<Notification>, pinned: &HashSet<String>) {
+    notifications
+        .retain(|notification| notification.unread || pinned.contains(&notification.subject.url));
+}
+
+/// Pins or unpins a subject URL, returning whether the file changed.
+pub fn set_pinned_url(url: &str, pinned: bool) -> Result<bool> {
+    // Serialize read/write to keep the file consistent when multiple actions run concurrently.
+    let _guard = pins_lock().lock().expect("pin list lock poisoned");
+    let path = pins_path()?;
+    write_pinned_url(&path, url, pinned)
+}
+
+fn write_pinned_url(path: &Path, url: &str, pinned: bool) -> Result<bool> {
+    let mut lines = read_lines(path, PIN_LIST)?;
+    let present = lines.iter().any(|line| line.trim() == url);
+    if present == pinned {
+        return Ok(false);
+    }
+
+    if pinned {
+        lines.push(url.to_string());
+    } else {
+        lines.retain(|line| line.trim() != url);
+    }
+
+    write_lines(path, &lines, PIN_LIST)?;
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{read_pinned_urls, write_pinned_url};
+    use std::fs;
+    use std::path::PathBuf;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    fn temp_pins_path(label: &str) -> PathBuf {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_nanos();
+        std::env::temp_dir()
+            .join(format!("ghn-pins-{}-{}", label, now))
+            .join("pins.txt")
+    }
+
+    #[test]
+    fn pin_and_unpin_roundtrip() {
+        let path = temp_pins_path("roundtrip");
+        let url = "https://github.com/acme/widgets/pull/1";
+
+        assert!(write_pinned_url(&path, url, true).unwrap());
+        assert!(!write_pinned_url(&path, url, true).unwrap());
+        assert!(read_pinned_urls(&path).unwrap().contains(url));
+
+        assert!(write_pinned_url(&path, url, false).unwrap());
+        assert!(!write_pinned_url(&path, url, false).unwrap());
+        assert!(read_pinned_urls(&path).unwrap().is_empty());
+
+        let _ = fs::remove_dir_all(path.parent().unwrap());
+    }
+
+    #[test]
+    fn unpin_keeps_comments_and_other_pins() {
+        let path = temp_pins_path("comments");
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        let contents = [
+            "# watching",
+            "https://github.com/acme/widgets/pull/1",
+            "https://github.com/acme/widgets/pull/2",
+        ]
+        .join("\n");
+        fs::write(&path, contents).unwrap();
+
+        assert!(write_pinned_url(&path, "https://github.com/acme/widgets/pull/1", false).unwrap());
+
+        let updated = fs::read_to_string(&path).unwrap();
+        let lines: Vec<&str> = updated.lines().collect();
+        assert_eq!(
+            lines,
+            vec!["# watching", "https://github.com/acme/widgets/pull/2"]
+        );
+
+        let _ = fs::remove_dir_all(path.parent().unwrap());
+    }
+
+    #[test]
+    fn read_pinned_urls_returns_empty_when_missing() {
+        let path = temp_pins_path("missing");
+        assert!(read_pinned_urls(&path).unwrap().is_empty());
+    }
+}
@@ -0,0 +1,280 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+use anyhow::{anyhow, Context, Result};
+
+// Deep enough for `<root>/<owner>/<repo>` and ghq's `<root>/github.com/<owner>/<repo>`.
+const SCAN_DEPTH: usize = 3;
+const SKIPPED_DIRS: [&str; 3] = ["node_modules", "target", "vendor"];
+
+pub fn split_full_name(full_name: &str) -> Result<(&str, &str)> {
+    let mut parts = full_name.split('/');
+    let owner = parts
+        .next()
+        .map(str::trim)
+        .filter(|value| !value.is_empty());
+    let repo = parts
+        .next()
+        .map(str::trim)
+        .filter(|value| !value.is_empty());
+
+    match (owner, repo, parts.next()) {
+        (Some(owner), Some(repo), None) => Ok((owner, repo)),
+        _ => Err(anyhow!("invalid repository name: {}", full_name)),
+    }
+}
+
+pub fn repo_dir_for_full_name(base: &Path, full_name: &str) -> Result<PathBuf> {
+    let (owner, repo) = split_full_name(full_name)?;
+    Ok(base.join(owner).join(repo))
+}
+
+/// Looks for a clone of `owner/repo` under `roots`.
+///
+/// Conventional layouts are checked first, then each root is scanned for a checkout whose
+/// remotes point at the repository.
+pub fn find_local_repo(roots: &[PathBuf], full_name: &str) -> Result<Option<PathBuf>> {
+    let (owner, repo) = split_full_name(full_name)?;
+
+    for root in roots {
+        for candidate in [
+            root.join(owner).join(repo),
+            root.join("github.com").join(owner).join(repo),
+        ] {
+            if candidate.is_dir() {
+                return Ok(Some(candidate));
+            }
+        }
+    }
+
+    for root in roots {
+        if let Some(found) = scan_for_remote(root, owner, repo, SCAN_DEPTH) {
+            return Ok(Some(found));
+        }
+    }
+
+    Ok(None)
+}
+
+/// Clones with `gh` so private repositories use the existing auth.
+pub fn clone_repo(full_name: &str, dest: &Path) -> Result<()> {
+    if let Some(parent) = dest.parent() {
+        fs::create_dir_all(parent)
+            .with_context(|| format!("failed to create {}", parent.display()))?;
+    }
+    let status = Command::new("gh")
+        .args(["repo", "clone", full_name])
+        .arg(dest)
+        .status()
+        .context("failed to run gh repo clone")?;
+    if !status.success() {
+        return Err(anyhow!(
+            "gh repo clone {} exited with {}",
+            full_name,
+            status
+        ));
+    }
+    Ok(())
+}
+
+fn scan_for_remote(dir: &Path, owner: &str, repo: &str, depth: usize) -> Option<PathBuf> {
+    let git_config = dir.join(".git").join("config");
+    if git_config.is_file() {
+        let contents = fs::read_to_string(&git_config).ok()?;
+        let matches = remote_urls(&contents)
+            .iter()
+            .any(|url| remote_matches(url, owner, repo));
+        return matches.then(|| dir.to_path_buf());
+    }
+    if depth == 0 {
+        return None;
+    }
+
+    let mut children: Vec<PathBuf> = fs::read_dir(dir)
+        .ok()?
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.file_type().map(|kind| kind.is_dir()).unwrap_or(false))
+        .filter(|entry| {
+            let name = entry.file_name();
+            let name = name.to_string_lossy();
+            !name.starts_with('.') && !SKIPPED_DIRS.contains(&name.as_ref())
+        })
+        .map(|entry| entry.path())
+        .collect();
+    children.sort();
+
+    children
+        .iter()
+        .find_map(|child| scan_for_remote(child, owner, repo, depth - 1))
+}
+
+fn remote_urls(git_config: &str) -> Vec<String> {
+    remotes(git_config)
+        .into_iter()
+        .map(|(_, url)| url)
+        .collect()
+}
+
+/// `(name, url)` for each remote in a `.git/config`.
+fn remotes(git_config: &str) -> Vec<(String, String)> {
+    let mut remotes = Vec::new();
+    let mut current: Option<String> = None;
+    for line in git_config.lines() {
+        let line = line.trim();
+        if line.starts_with('[') {
+            current = line
+                .strip_prefix("[remote ")
+                .and_then(|rest| rest.strip_suffix(']'))
+                .map(|name| name.trim().trim_matches('"').to_string());
+            continue;
+        }
+        let Some(name) = &current else {
+            continue;
+        };
+        if let Some((key, value)) = line.split_once('=') {
+            if key.trim() == "url" {
+                remotes.push((name.clone(), value.trim().to_string()));
+            }
+        }
+    }
+    remotes
+}
+
+/// The clone's remote for `owner/repo`, where `refs/pull/*` live; `origin` when none matches.
+pub fn base_remote(repo_dir: &Path, full_name: &str) -> Result<String> {
+    let (owner, repo) = split_full_name(full_name)?;
+    let contents = fs::read_to_string(repo_dir.join(".git").join("config")).unwrap_or_default();
+    Ok(remotes(&contents)
+        .into_iter()
+        .find(|(_, url)| remote_matches(url, owner, repo))
+        .map_or_else(|| "origin".to_string(), |(name, _)| name))
+}
+
+/// Matches https, ssh and scp-style GitHub remotes, with or without `.git`.
+fn remote_matches(url: &str, owner: &str, repo: &str) -> bool {
+    let path = url
+        .strip_prefix("git@github.com:")
+        .or_else(|| url.split_once("github.com/").map(|(_, rest)| rest));
+    let Some(path) = path else {
+        return false;
+    };
+    let path = path.trim_end_matches('/');
+    let path = path.strip_suffix(".git").unwrap_or(path);
+    match path.split_once('/') {
+        Some((remote_owner, remote_repo)) => {
+            remote_owner.eq_ignore_ascii_case(owner) && remote_repo.eq_ignore_ascii_case(repo)
+        }
+        None => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        base_remote, find_local_repo, remote_matches, remote_urls, repo_dir_for_full_name,
+    };
+    use std::fs;
+    use std::path::PathBuf;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    fn temp_root(label: &str) -> PathBuf {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_nanos();
+        std::env::temp_dir().join(format!("ghn-repos-{}-{}", label, now))
+    }
+
+    #[test]
+    fn repo_dir_for_full_name_builds_path() {
+        let base = PathBuf::from("/tmp/base");
+        let path = repo_dir_for_full_name(&base, "acme/widgets").unwrap();
+        assert_eq!(path, base.join("acme").join("widgets"));
+    }
+
+    #[test]
+    fn repo_dir_for_full_name_rejects_invalid() {
+        let base = PathBuf::from("/tmp/base");
+        assert!(repo_dir_for_full_name(&base, "acme").is_err());
+        assert!(repo_dir_for_full_name(&base, "acme/widgets/extra").is_err());
+    }
+
+    #[test]
+    fn remote_matching_accepts_common_url_forms() {
+        for url in [
+            "git@github.com:acme/widgets.git",
+            "https://github.com/acme/widgets",
+            "https://github.com/Acme/Widgets.git/",
+            "ssh://git@github.com/acme/widgets.git",
+        ] {
+            assert!(remote_matches(url, "acme", "widgets"), "{url}");
+        }
+        assert!(!remote_matches(
+            "git@github.com:acme/widgets-docs.git",
+            "acme",
+            "widgets"
+        ));
+        assert!(!remote_matches(
+            "https://gitlab.com/acme/widgets",
+            "acme",
+            "widgets"
+        ));
+    }
+
+    #[test]
+    fn remote_urls_reads_only_remote_sections() {
+        let config = "[core]\n\turl = nope\n[remote \"origin\"]\n\turl = git@github.com:acme/widgets.git\n\tfetch = +refs/heads/*:refs/remotes/origin/*\n[remote \"fork\"]\n\turl = https://github.com/me/widgets\n";
+        assert_eq!(
+            remote_urls(config),
+            vec![
+                "git@github.com:acme/widgets.git".to_string(),
+                "https://github.com/me/widgets".to_string()
+            ]
+        );
+    }
+
+    #[test]
+    fn base_remote_picks_the_remote_for_the_repo() {
+        let root = temp_root("remote");
+        fs::create_dir_all(root.join(".git")).unwrap();
+        fs::write(
+            root.join(".git/config"),
+            "[remote \"origin\"]\n\turl = https://github.com/me/widgets\n[remote \"upstream\"]\n\turl = git@github.com:acme/widgets.git\n",
+        )
+        .unwrap();
+        assert_eq!(base_remote(&root, "acme/widgets").unwrap(), "upstream");
+        assert_eq!(base_remote(&root, "acme/gadgets").unwrap(), "origin");
+
+        let _ = fs::remove_dir_all(root);
+    }
+
+    #[test]
+    fn find_local_repo_checks_layouts_then_remotes() {
+        let root = temp_root("find");
+        let ghq = root.join("ghq");
+        fs::create_dir_all(ghq.join("github.com/acme/widgets")).unwrap();
+        let checkout = root.join("src/work/gadgets-fork");
+        fs::create_dir_all(checkout.join(".git")).unwrap();
+        fs::write(
+            checkout.join(".git/config"),
+            "[remote \"upstream\"]\n\turl = https://github.com/acme/gadgets.git\n",
+        )
+        .unwrap();
+
+        let roots = vec![root.join("missing"), ghq.clone(), root.join("src")];
+        assert_eq!(
+            find_local_repo(&roots, "acme/widgets").unwrap(),
+            Some(ghq.join("github.com/acme/widgets"))
+        );
+        assert_eq!(
+            find_local_repo(&roots, "acme/gadgets").unwrap(),
+            Some(checkout)
+        );
+        assert_eq!(find_local_repo(&roots, "acme/other").unwrap(), None);
+
+        let _ = fs::remove_dir_all(root);
+    }
+}
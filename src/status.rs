@@ -0,0 +1,261 @@
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{anyhow, Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    custom_actions::{render_with, validate_placeholders},
+    types::{CiStatus, MyPullRequest, Notification},
+    ui::{self, NotificationBucket},
+};
+
+const STATUS_CACHE_RELATIVE_PATH: &str = "ghn/status.json";
+
+pub const DEFAULT_STATUS_FORMAT: &str =
+    "{unread} unread, {review_requests} reviews, {failing_ci} failing";
+
+const COUNTERS: [&str; 4] = ["total", "unread", "failing_ci", "review_requests"];
+
+/// Inbox counts for status bars, also cached on disk by the TUI after every poll.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StatusSummary {
+    /// Unix timestamp of the fetch the counts came from.
+    pub generated_at: i64,
+    pub buckets: BTreeMap<String, usize>,
+    pub total: usize,
+    pub unread: usize,
+    pub failing_ci: usize,
+    pub review_requests: usize,
+}
+
+impl StatusSummary {
+    pub fn new(
+        notifications: &[Notification],
+        my_prs: &[MyPullRequest],
+        generated_at: i64,
+    ) -> Self {
+        let mut buckets: BTreeMap<String, usize> = NotificationBucket::ALL
+            .iter()
+            .map(|bucket| (bucket.name().to_string(), 0))
+            .collect();
+        for (bucket, keys) in ui::build_bucket_key_sections(notifications, my_prs) {
+            *buckets.entry(bucket.name().to_string()).or_default() += keys.len();
+        }
+
+        Self {
+            generated_at,
+            buckets,
+            total: notifications.len() + my_prs.len(),
+            unread: notifications.iter().filter(|n| n.unread).count(),
+            failing_ci: my_prs
+                .iter()
+                .filter(|pr| matches!(pr.subject.ci_status, Some(CiStatus::Failure)))
+                .count(),
+            // Read requests have been handled, so they drop out like the TUI's unread markers.
+            review_requests: notifications
+                .iter()
+                .filter(|n| n.unread && n.reason == "review_requested")
+                .count(),
+        }
+    }
+
+    fn value(&self, name: &str) -> Option<usize> {
+        match name {
+            "total" => Some(self.total),
+            "unread" => Some(self.unread),
+            "failing_ci" => Some(self.failing_ci),
+            "review_requests" => Some(self.review_requests),
+            bucket => self.buckets.get(bucket).copied(),
+        }
+    }
+}
+
+pub fn validate_format(format: &str) -> Result<()> {
+    let mut placeholders: Vec<&str> = COUNTERS.to_vec();
+    placeholders.extend(NotificationBucket::ALL.iter().map(|bucket| bucket.name()));
+    validate_placeholders(format, &placeholders)
+}
+
+pub fn render_status(format: &str, summary: &StatusSummary) -> Result<String> {
+    validate_format(format)?;
+    render_with(format, |name| {
+        summary
+            .value(name)
+            .map(|count| count.to_string())
+            .ok_or_else(|| anyhow!("unknown placeholder {{{}}}", name))
+    })
+}
+
+pub fn cache_home() -> Result<PathBuf> {
+    if let Ok(value) = std::env::var("XDG_CACHE_HOME") {
+        let trimmed = value.trim();
+        if !trimmed.is_empty() {
+            return Ok(PathBuf::from(trimmed));
+        }
+    }
+
+    if let Ok(value) = std::env::var("LOCALAPPDATA") {
+        let trimmed = value.trim();
+        if !trimmed.is_empty() {
+            return Ok(PathBuf::from(trimmed));
+        }
+    }
+
+    if let Ok(value) = std::env::var("HOME") {
+        let trimmed = value.trim();
+        if !trimmed.is_empty() {
+            return Ok(PathBuf::from(trimmed).join(".cache"));
+        }
+    }
+
+    Err(anyhow!(
+        "unable to resolve cache directory (set XDG_CACHE_HOME or HOME)"
+    ))
+}
+
+pub fn status_cache_path() -> Result<PathBuf> {
+    Ok(cache_home()?.join(STATUS_CACHE_RELATIVE_PATH))
+}
+
+pub fn write_status_cache(summary: &StatusSummary) -> Result<()> {
+    write_cache_file(&status_cache_path()?, summary)
+}
+
+/// Returns the cached summary if it exists and is at most `max_age_secs` old.
+pub fn read_status_cache(max_age_secs: u64, now: i64) -> Result<Option<StatusSummary>> {
+    read_cache_file(&status_cache_path()?, max_age_secs, now)
+}
+
+fn write_cache_file(path: &Path, summary: &StatusSummary) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .with_context(|| format!("failed to create cache directory: {}", parent.display()))?;
+    }
+    // Write then rename so a status bar never reads a half-written file.
+    let tmp = path.with_extension("json.tmp");
+    fs::write(&tmp, serde_json::to_vec(summary)?)
+        .with_context(|| format!("failed to write status cache: {}", tmp.display()))?;
+    fs::rename(&tmp, path)
+        .with_context(|| format!("failed to write status cache: {}", path.display()))?;
+    Ok(())
+}
+
+fn read_cache_file(path: &Path, max_age_secs: u64, now: i64) -> Result<Option<StatusSummary>> {
+    let contents = match fs::read(path) {
+        Ok(contents) => contents,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(err) => {
+            return Err(err)
+                .with_context(|| format!("failed to read status cache: {}", path.display()));
+        }
+    };
+    // A corrupt or outdated cache is treated as missing rather than an error.
+    let Ok(summary) = serde_json::from_slice::<StatusSummary>(&contents) else {
+        return Ok(None);
+    };
+    let age = now.saturating_sub(summary.generated_at);
+    if age < 0 || age as u64 > max_age_secs {
+        return Ok(None);
+    }
+    Ok(Some(summary))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{read_cache_file, render_status, write_cache_file, StatusSummary};
+    use crate::types::{Notification, Repository, Subject};
+    use std::collections::BTreeMap;
+    use std::fs;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    fn sample_summary() -> StatusSummary {
+        let mut buckets = BTreeMap::new();
+        buckets.insert("needs_review".to_string(), 3);
+        buckets.insert("ready_to_merge".to_string(), 1);
+        StatusSummary {
+            generated_at: 1_000,
+            buckets,
+            total: 6,
+            unread: 4,
+            failing_ci: 2,
+            review_requests: 1,
+        }
+    }
+
+    fn review_request(id: &str, unread: bool) -> Notification {
+        Notification {
+            id: id.to_string(),
+            node_id: String::new(),
+            subject_id: None,
+            unread,
+            pinned: false,
+            reason: "review_requested".to_string(),
+            updated_at: "2024-01-01T00:00:00Z".to_string(),
+            subject: Subject {
+                title: format!("PR {}", id),
+                url: format!("https://github.com/acme/widgets/pull/{}", id),
+                kind: "PullRequest".to_string(),
+                author: None,
+                status: Vec::new(),
+                ci_status: None,
+                review_status: None,
+                merge_state_status: None,
+                head_ref: None,
+            },
+            repository: Repository {
+                name: "widgets".to_string(),
+                full_name: "acme/widgets".to_string(),
+                merge_settings: None,
+            },
+            url: String::new(),
+        }
+    }
+
+    #[test]
+    fn review_requests_count_only_unread() {
+        let notifications = [review_request("1", true), review_request("2", false)];
+        let summary = StatusSummary::new(&notifications, &[], 0);
+        assert_eq!(summary.review_requests, 1);
+        assert_eq!(summary.unread, 1);
+    }
+
+    #[test]
+    fn render_status_fills_counters_and_buckets() {
+        let summary = sample_summary();
+        assert_eq!(
+            render_status(
+                "R{needs_review} M{ready_to_merge} U{unread} F{failing_ci}",
+                &summary
+            )
+            .unwrap(),
+            "R3 M1 U4 F2"
+        );
+        assert!(render_status("{reviews}", &summary).is_err());
+    }
+
+    #[test]
+    fn cache_roundtrip_respects_max_age() {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_nanos();
+        let path = std::env::temp_dir()
+            .join(format!("ghn-status-{}", now))
+            .join("status.json");
+        let summary = sample_summary();
+
+        assert_eq!(read_cache_file(&path, 60, 1_030).unwrap(), None);
+        write_cache_file(&path, &summary).unwrap();
+        assert_eq!(read_cache_file(&path, 60, 1_030).unwrap(), Some(summary));
+        assert_eq!(read_cache_file(&path, 10, 1_030).unwrap(), None);
+
+        fs::write(&path, "not json").unwrap();
+        assert_eq!(read_cache_file(&path, 60, 1_030).unwrap(), None);
+
+        let _ = fs::remove_dir_all(path.parent().unwrap());
+    }
+}
@@ -0,0 +1,121 @@
+use std::{
+    fs::{self, File, OpenOptions},
+    io::{BufRead, BufReader, Write},
+    path::{Path, PathBuf},
+};
+
+use anyhow::{anyhow, Context, Result};
+
+/// Directory holding `ghn/`: `$XDG_CONFIG_HOME`, `%APPDATA%`, or `~/.config`.
+pub fn config_home() -> Result<PathBuf> {
+    if let Ok(value) = std::env::var("XDG_CONFIG_HOME") {
+        let trimmed = value.trim();
+        if !trimmed.is_empty() {
+            return Ok(PathBuf::from(trimmed));
+        }
+    }
+
+    if let Ok(value) = std::env::var("APPDATA") {
+        let trimmed = value.trim();
+        if !trimmed.is_empty() {
+            return Ok(PathBuf::from(trimmed));
+        }
+    }
+
+    if let Ok(value) = std::env::var("HOME") {
+        let trimmed = value.trim();
+        if !trimmed.is_empty() {
+            return Ok(PathBuf::from(trimmed).join(".config"));
+        }
+    }
+
+    if let Ok(value) = std::env::var("USERPROFILE") {
+        let trimmed = value.trim();
+        if !trimmed.is_empty() {
+            return Ok(PathBuf::from(trimmed).join(".config"));
+        }
+    }
+
+    Err(anyhow!(
+        "unable to resolve config directory (set XDG_CONFIG_HOME or HOME)"
+    ))
+}
+
+/// Every line of a list file such as the ignore or pin list; a missing file is empty.
+/// `what` names the file in errors.
+pub fn read_lines(path: &Path, what: &str) -> Result<Vec<String>> {
+    let file = match File::open(path) {
+        Ok(file) => file,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => {
+            return Ok(Vec::new());
+        }
+        Err(err) => {
+            return Err(err)
+                .with_context(|| format!("failed to open {}: {}", what, path.display()));
+        }
+    };
+
+    BufReader::new(file)
+        .lines()
+        .collect::<std::io::Result<Vec<_>>>()
+        .with_context(|| format!("failed to read {}: {}", what, path.display()))
+}
+
+/// Replaces the file with `lines`, creating its directory if needed.
+pub fn write_lines(path: &Path, lines: &[String], what: &str) -> Result<()> {
+    create_parent(path, what)?;
+    let mut file = File::create(path)
+        .with_context(|| format!("failed to open {}: {}", what, path.display()))?;
+    for line in lines {
+        writeln!(file, "{}", line)
+            .with_context(|| format!("failed to write {}: {}", what, path.display()))?;
+    }
+    Ok(())
+}
+
+pub fn append_line(path: &Path, line: &str, what: &str) -> Result<()> {
+    create_parent(path, what)?;
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .with_context(|| format!("failed to open {}: {}", what, path.display()))?;
+    writeln!(file, "{}", line)
+        .with_context(|| format!("failed to write {}: {}", what, path.display()))
+}
+
+fn create_parent(path: &Path, what: &str) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).with_context(|| {
+            format!("failed to create {} directory: {}", what, parent.display())
+        })?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{append_line, read_lines, write_lines};
+    use std::fs;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    #[test]
+    fn lines_roundtrip_through_a_new_directory() {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_nanos();
+        let dir = std::env::temp_dir().join(format!("ghn-store-{}", now));
+        let path = dir.join("nested").join("list.txt");
+
+        assert!(read_lines(&path, "list").unwrap().is_empty());
+        write_lines(&path, &["# keep".to_string(), "one".to_string()], "list").unwrap();
+        append_line(&path, "two", "list").unwrap();
+        assert_eq!(
+            read_lines(&path, "list").unwrap(),
+            vec!["# keep", "one", "two"]
+        );
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
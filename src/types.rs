@@ -1,11 +1,13 @@
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Notification {
     pub id: String,
     pub node_id: String,
     pub subject_id: Option<String>,
     pub unread: bool,
+    // Local-only flag, populated from the pin list rather than the API.
+    pub pinned: bool,
     pub reason: String,
     pub updated_at: String,
     pub subject: Subject,
@@ -13,16 +15,17 @@ pub struct Notification {
     pub url: String,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MyPullRequest {
     pub id: String,
     pub updated_at: String,
+    pub pinned: bool,
     pub subject: Subject,
     pub repository: Repository,
     pub url: String,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Subject {
     pub title: String,
     pub url: String,
@@ -35,7 +38,7 @@ pub struct Subject {
     pub head_ref: Option<String>,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum MergeMethod {
     Merge,
@@ -53,7 +56,7 @@ impl MergeMethod {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MergeSettings {
     pub default_method: Option<MergeMethod>,
     pub merge_commit_allowed: bool,
@@ -92,7 +95,7 @@ impl MergeSettings {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Repository {
     pub name: String,
     pub full_name: String,
@@ -100,7 +103,7 @@ pub struct Repository {
     pub merge_settings: Option<MergeSettings>,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum SubjectStatus {
     Draft,
     Merged,
@@ -117,21 +120,21 @@ impl SubjectStatus {
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum CiStatus {
     Success,
     Pending,
     Failure,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum ReviewStatus {
     Approved,
     ChangesRequested,
     ReviewRequired,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum MergeStateStatus {
     Behind,
     Blocked,
@@ -167,9 +170,57 @@ pub enum Action {
     Review,
     ReviewNoAnalyze,
     Branch,
+    Worktree,
+    Pin,
+    /// Index into the configured custom actions.
+    Custom(usize),
 }
 
 impl Action {
+    pub const ALL: [Action; 11] = [
+        Action::Open,
+        Action::PrettyYank,
+        Action::Yank,
+        Action::Read,
+        Action::Done,
+        Action::Unsubscribe,
+        Action::Review,
+        Action::ReviewNoAnalyze,
+        Action::Branch,
+        Action::Worktree,
+        Action::Pin,
+    ];
+
+    /// Name used for the action in the config file.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Open => "open",
+            Self::Yank => "yank",
+            Self::PrettyYank => "pretty_yank",
+            Self::Read => "read",
+            Self::Done => "done",
+            Self::Unsubscribe => "unsubscribe",
+            Self::Review => "review",
+            Self::ReviewNoAnalyze => "review_no_analyze",
+            Self::Branch => "branch",
+            Self::Worktree => "worktree",
+            Self::Pin => "pin",
+            Self::Custom(_) => "custom",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|action| action.name() == name)
+    }
+
+    /// Built-in actions `[confirm] destructive` asks about first. Unsubscribe silences future
+    /// activity; done only clears the thread until its next update, so it's left to the size
+    /// threshold.
+    pub fn is_destructive(self) -> bool {
+        matches!(self, Self::Unsubscribe)
+    }
+
+    /// Default key; the active binding comes from the keymap.
     pub fn from_char(ch: char) -> Option<Self> {
         match ch {
             'o' => Some(Self::Open),
@@ -181,12 +232,15 @@ impl Action {
             'p' => Some(Self::Review),
             'P' => Some(Self::ReviewNoAnalyze),
             'b' => Some(Self::Branch),
+            'W' => Some(Self::Worktree),
+            't' => Some(Self::Pin),
             _ => None,
         }
     }
 
-    pub fn as_char(self) -> char {
-        match self {
+    /// Default key; custom actions only have the key given in the config.
+    pub fn as_char(self) -> Option<char> {
+        let ch = match self {
             Self::Open => 'o',
             Self::Yank => 'Y',
             Self::PrettyYank => 'y',
@@ -196,7 +250,11 @@ impl Action {
             Self::Review => 'p',
             Self::ReviewNoAnalyze => 'P',
             Self::Branch => 'b',
-        }
+            Self::Worktree => 'W',
+            Self::Pin => 't',
+            Self::Custom(_) => return None,
+        };
+        Some(ch)
     }
 }
 
@@ -228,15 +286,26 @@ mod tests {
             ('p', Action::Review),
             ('P', Action::ReviewNoAnalyze),
             ('b', Action::Branch),
+            ('W', Action::Worktree),
+            ('t', Action::Pin),
         ];
 
         for (ch, action) in pairs {
             assert_eq!(Action::from_char(ch), Some(action));
-            assert_eq!(action.as_char(), ch);
+            assert_eq!(action.as_char(), Some(ch));
         }
         assert_eq!(Action::from_char('x'), None);
         assert_eq!(Action::from_char('s'), None);
         assert_eq!(Action::from_char('u'), None);
+        assert_eq!(Action::Custom(0).as_char(), None);
+    }
+
+    #[test]
+    fn action_name_roundtrip() {
+        for action in Action::ALL {
+            assert_eq!(Action::from_name(action.name()), Some(action));
+        }
+        assert_eq!(Action::from_name("merge"), None);
     }
 
     #[test]
@@ -1,14 +1,23 @@
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, HashMap, HashSet};
 
 use ratatui::{
     layout::{Constraint, Direction, Layout, Rect},
     style::{Color, Modifier, Style},
     text::{Line, Span},
-    widgets::{Block, Borders, List, ListItem, Paragraph},
+    widgets::{Block, Borders, Clear, List, ListItem, Paragraph},
     Frame,
 };
 
+use anyhow::{anyhow, Result};
+
 use crate::{
+    commands::{Targets, TARGET_SYNTAX},
+    custom_actions::{is_destructive, CustomAction},
+    ex::EX_COMMANDS,
+    filter::{Filter, FilterItem},
+    ignore::{IgnoreCandidate, IgnoreList},
+    ignore_manager::{IgnoreManager, RuleDetail},
+    keymap::{HelpEntry, Keymap, Target, FIXED_KEYS},
     types::{
         Action, CiStatus, MergeStateStatus, MyPullRequest, Notification, ReviewStatus, Subject,
         SubjectStatus,
@@ -16,19 +25,6 @@ use crate::{
     AppState,
 };
 
-const COMMANDS_FULL: &str =
-    "Commands: o open/read  y pretty yank  Y yank  r read  d done  q unsub/ignore  p review+analyze  P review  b branch  U undo";
-const COMMANDS_COMPACT: &str =
-    "Cmds: o open/read  y pretty  Y yank  r read  d done  q unsub/ign  p rev+anlz  P review  b branch  U undo";
-const COMMANDS_SHORT: &str = "Cmds o/y/Y/r/d/q/p/P/b/U";
-const COMMANDS_TINY: &str = "o y Y r d q p P b U";
-
-const TARGETS_FULL: &str =
-    "Targets: 1-3, 1 2 3, u unread, ? pending review, a approved, x changes requested, ! conflicts, w approved+CI pending, m merged, c closed, f draft";
-const TARGETS_COMPACT: &str =
-    "Targets: 1-3/1 2 3, u unread, ? review, a appr, x chg, ! conf, w appr+CI pend, m merged, c closed, f draft";
-const TARGETS_SHORT: &str = "Tgt 1-3/1 2 3 u ? a x ! w m c f";
-const TARGETS_TINY: &str = "1-3 u ? a x ! w m c f";
 const MAX_KIND_WIDTH: usize = 14;
 const MAX_TIME_WIDTH: usize = 6;
 const MIN_KIND_WIDTH: usize = 3;
@@ -45,35 +41,11 @@ const MAX_AUTHOR_WIDTH: usize = 18;
 const CI_REVIEW_GAP: usize = 1;
 const INDICATOR_KIND_GAP: usize = 1;
 
-#[derive(Clone, Copy)]
-struct LegendVariant {
-    commands: &'static str,
-    targets: &'static str,
-}
-
-const LEGEND_VARIANTS: [LegendVariant; 4] = [
-    LegendVariant {
-        commands: COMMANDS_FULL,
-        targets: TARGETS_FULL,
-    },
-    LegendVariant {
-        commands: COMMANDS_COMPACT,
-        targets: TARGETS_COMPACT,
-    },
-    LegendVariant {
-        commands: COMMANDS_SHORT,
-        targets: TARGETS_SHORT,
-    },
-    LegendVariant {
-        commands: COMMANDS_TINY,
-        targets: TARGETS_TINY,
-    },
-];
-
 trait ListItemLike {
     fn unread(&self) -> bool;
     fn subject(&self) -> &Subject;
     fn repo_full_name(&self) -> &str;
+    fn reason(&self) -> Option<&str>;
 }
 
 impl ListItemLike for Notification {
@@ -81,6 +53,10 @@ impl ListItemLike for Notification {
         self.unread
     }
 
+    fn reason(&self) -> Option<&str> {
+        Some(&self.reason)
+    }
+
     fn subject(&self) -> &Subject {
         &self.subject
     }
@@ -95,6 +71,10 @@ impl ListItemLike for MyPullRequest {
         false
     }
 
+    fn reason(&self) -> Option<&str> {
+        None
+    }
+
     fn subject(&self) -> &Subject {
         &self.subject
     }
@@ -109,6 +89,40 @@ enum BucketItem<'a> {
     MyPullRequest(&'a MyPullRequest),
 }
 
+impl BucketItem<'_> {
+    fn pinned(&self) -> bool {
+        match self {
+            Self::Notification(notification) => notification.pinned,
+            Self::MyPullRequest(pr) => pr.pinned,
+        }
+    }
+
+    /// Seconds since the last update, or `None` if the timestamp doesn't parse.
+    fn age(&self) -> Option<i64> {
+        let updated_at = match self {
+            Self::Notification(notification) => &notification.updated_at,
+            Self::MyPullRequest(pr) => &pr.updated_at,
+        };
+        chrono::DateTime::parse_from_rfc3339(updated_at)
+            .ok()
+            .map(|updated_at| (chrono::Utc::now() - updated_at.to_utc()).num_seconds())
+    }
+
+    fn visible(&self, filter: Option<&Filter>) -> bool {
+        let Some(filter) = filter else {
+            return true;
+        };
+        filter.matches(&FilterItem {
+            subject: self.subject(),
+            repo: self.repo_full_name(),
+            reason: self.reason(),
+            unread: self.unread(),
+            pinned: self.pinned(),
+            age: self.age(),
+        })
+    }
+}
+
 impl ListItemLike for BucketItem<'_> {
     fn unread(&self) -> bool {
         match self {
@@ -130,6 +144,13 @@ impl ListItemLike for BucketItem<'_> {
             Self::MyPullRequest(pr) => &pr.repository.full_name,
         }
     }
+
+    fn reason(&self) -> Option<&str> {
+        match self {
+            Self::Notification(notification) => notification.reason(),
+            Self::MyPullRequest(pr) => pr.reason(),
+        }
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -138,9 +159,47 @@ pub enum DisplayEntryKey {
     MyPullRequest(usize),
 }
 
+/// Color overrides from the `[colors]` section of the config file.
+#[derive(Debug, Clone, Default)]
+pub struct Theme {
+    actions: HashMap<Action, Color>,
+    buckets: HashMap<NotificationBucket, Color>,
+}
+
+impl Theme {
+    pub fn set_action_color(&mut self, name: &str, color: Color) -> Result<()> {
+        let action = Action::from_name(name).ok_or_else(|| anyhow!("unknown action '{}'", name))?;
+        self.actions.insert(action, color);
+        Ok(())
+    }
+
+    pub fn set_bucket_color(&mut self, name: &str, color: Color) -> Result<()> {
+        let bucket = NotificationBucket::from_name(name)
+            .ok_or_else(|| anyhow!("unknown bucket '{}'", name))?;
+        self.buckets.insert(bucket, color);
+        Ok(())
+    }
+
+    fn action_color(&self, action: Action) -> Color {
+        self.actions
+            .get(&action)
+            .copied()
+            .unwrap_or_else(|| action_color(action))
+    }
+
+    fn header_style(&self, bucket: NotificationBucket) -> Style {
+        match self.buckets.get(&bucket) {
+            Some(color) => bucket.header_style().fg(*color),
+            None => bucket.header_style(),
+        }
+    }
+}
+
 pub fn draw(f: &mut Frame, app: &AppState) {
     let size = f.area();
-    let status_lines = build_status_lines(size.width, app.status.as_deref());
+    let prompt_status = prompt_status(app);
+    let status = prompt_status.as_deref().or(app.status.as_deref());
+    let status_lines = build_status_lines(size.width, status, &app.keymap);
     let status_height = status_lines.len().max(1) as u16;
 
     let chunks = Layout::default()
@@ -155,6 +214,16 @@ pub fn draw(f: &mut Frame, app: &AppState) {
     draw_lists(f, chunks[0], app);
     draw_status(f, chunks[1], status_lines);
     draw_command(f, chunks[2], app);
+
+    if let Some(manager) = &app.ignore_manager {
+        draw_ignore_manager(f, size, app, manager);
+    }
+    if let Some(preview) = &app.preview {
+        draw_preview(f, size, app, &preview.pending, preview.scroll);
+    }
+    if let Some(scroll) = app.help {
+        draw_help(f, size, app, scroll);
+    }
 }
 
 fn draw_lists(f: &mut Frame, area: Rect, app: &AppState) {
@@ -170,6 +239,7 @@ fn draw_lists(f: &mut Frame, area: Rect, app: &AppState) {
         &app.relative_times,
         &app.my_prs,
         &app.my_pr_relative_times,
+        app.filter.as_ref(),
     );
     let sections_area = split_bucket_area(area, &sections);
 
@@ -185,6 +255,8 @@ fn draw_lists(f: &mut Frame, area: Rect, app: &AppState) {
             &app.executing,
             total_count,
             &layout_max,
+            &app.theme,
+            &app.keymap,
         );
     }
 }
@@ -192,6 +264,8 @@ fn draw_lists(f: &mut Frame, area: Rect, app: &AppState) {
 struct BucketSection<'a> {
     bucket: NotificationBucket,
     entries: Vec<BucketEntry<'a>>,
+    // Set while a `/` filter is active so the header can show how many were hidden.
+    total: Option<usize>,
 }
 
 impl BucketSection<'_> {
@@ -220,10 +294,16 @@ fn draw_bucket_section(
     executing: &HashSet<String>,
     total_count: usize,
     layout_max: &LayoutMax,
+    theme: &Theme,
+    keymap: &Keymap,
 ) {
+    let count = match section.total {
+        Some(total) => format!("{}/{}", section.entries.len(), total),
+        None => section.entries.len().to_string(),
+    };
     let title = Line::from(Span::styled(
-        format!("{} ({})", section.bucket.title(), section.entries.len()),
-        section.bucket.header_style(),
+        format!("{} ({})", section.bucket.title(), count),
+        theme.header_style(section.bucket),
     ));
     let block = Block::default().title(title).borders(Borders::ALL);
     let inner_area = block.inner(area);
@@ -242,6 +322,8 @@ fn draw_bucket_section(
                 executing,
                 &widths,
                 idx + 1 < section.entries.len(),
+                theme,
+                keymap,
             )
         })
         .collect();
@@ -298,6 +380,7 @@ fn relative_time_for_key<'a>(
     .unwrap_or("?")
 }
 
+#[allow(clippy::too_many_arguments)]
 fn build_list_item<T: ListItemLike>(
     index: usize,
     item: &T,
@@ -306,12 +389,14 @@ fn build_list_item<T: ListItemLike>(
     executing: &HashSet<String>,
     widths: &LayoutWidths,
     add_spacer: bool,
+    theme: &Theme,
+    keymap: &Keymap,
 ) -> ListItem<'static> {
     let pending = pending.get(&index);
     let subject = item.subject();
     let executing = executing.contains(subject.url.as_str());
     let base_style = base_notification_style(item.unread());
-    let style = base_style.patch(pending_style(pending));
+    let style = base_style.patch(pending_style(pending, theme));
 
     let marker = action_marker(item.unread(), executing);
     let indent = " ".repeat(widths.prefix);
@@ -408,8 +493,24 @@ fn build_list_item<T: ListItemLike>(
     ));
 
     let header = Line::from(header_spans);
-    let title_text = truncate_with_suffix(&subject.title, widths.title);
-    let title = Line::from(vec![Span::raw(indent), Span::raw(title_text)]);
+    let mut title_spans = vec![Span::raw(indent)];
+    let mut title_width = widths.title;
+    if let Some(actions) = pending {
+        let glyphs = pending_glyphs(actions, keymap, theme);
+        let glyphs_width: usize = glyphs.iter().map(|span| span.content.chars().count()).sum();
+        title_width = title_width.saturating_sub(glyphs_width);
+        title_spans.extend(glyphs);
+    }
+    if let Some(badge) = item.reason().and_then(reason_badge) {
+        let badge = format!("{} ", badge);
+        // Never let the badge squeeze the title below half its width.
+        if badge.chars().count() * 2 <= title_width {
+            title_width -= badge.chars().count();
+            title_spans.push(Span::styled(badge, Style::default().fg(Color::Magenta)));
+        }
+    }
+    title_spans.push(Span::raw(truncate_with_suffix(&subject.title, title_width)));
+    let title = Line::from(title_spans);
 
     let mut lines = vec![header, title];
     if add_spacer {
@@ -487,8 +588,9 @@ fn collect_layout_max(
     layout_max
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-enum NotificationBucket {
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum NotificationBucket {
+    Pinned,
     NeedsReview,
     NeedsAction,
     WaitingOnCi,
@@ -498,8 +600,36 @@ enum NotificationBucket {
 }
 
 impl NotificationBucket {
-    fn title(self) -> &'static str {
+    pub const ALL: [NotificationBucket; 7] = [
+        NotificationBucket::Pinned,
+        NotificationBucket::ReadyToMerge,
+        NotificationBucket::NeedsAction,
+        NotificationBucket::WaitingOnCi,
+        NotificationBucket::NeedsReview,
+        NotificationBucket::Other,
+        NotificationBucket::Draft,
+    ];
+
+    /// Name used in the config file and on the command line.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Pinned => "pinned",
+            Self::NeedsReview => "needs_review",
+            Self::NeedsAction => "needs_action",
+            Self::WaitingOnCi => "waiting_on_ci",
+            Self::ReadyToMerge => "ready_to_merge",
+            Self::Other => "other",
+            Self::Draft => "draft",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|bucket| bucket.name() == name)
+    }
+
+    pub fn title(self) -> &'static str {
         match self {
+            Self::Pinned => "Pinned",
             Self::NeedsReview => "Needs Review",
             Self::NeedsAction => "Needs Action",
             Self::WaitingOnCi => "Waiting on CI",
@@ -511,6 +641,7 @@ impl NotificationBucket {
 
     fn header_style(self) -> Style {
         let color = match self {
+            Self::Pinned => Color::Magenta,
             Self::NeedsReview => Color::Yellow,
             Self::NeedsAction => Color::Red,
             Self::WaitingOnCi => Color::LightBlue,
@@ -528,11 +659,13 @@ fn build_bucket_sections<'a>(
     notification_times: &'a [String],
     my_prs: &'a [MyPullRequest],
     my_pr_times: &'a [String],
+    filter: Option<&Filter>,
 ) -> Vec<BucketSection<'a>> {
     let mut next_index = 1usize;
     let mut sections = Vec::new();
 
     for (bucket, keys) in build_bucket_key_sections(notifications, my_prs) {
+        let total = keys.len();
         let entries: Vec<BucketEntry<'a>> = keys
             .into_iter()
             .filter_map(|key| {
@@ -542,21 +675,28 @@ fn build_bucket_sections<'a>(
                     item,
                     relative_time: relative_time_for_key(key, notification_times, my_pr_times),
                 };
+                // Hidden entries keep their number so indices match the unfiltered view.
                 next_index += 1;
-                Some(entry)
+                entry.item.visible(filter).then_some(entry)
             })
             .collect();
 
-        sections.push(BucketSection { bucket, entries });
+        sections.push(BucketSection {
+            bucket,
+            entries,
+            total: filter.map(|_| total),
+        });
     }
 
     sections
 }
 
-fn build_bucket_key_sections(
+/// Display keys grouped by bucket, in the order the sections are drawn.
+pub fn build_bucket_key_sections(
     notifications: &[Notification],
     my_prs: &[MyPullRequest],
 ) -> Vec<(NotificationBucket, Vec<DisplayEntryKey>)> {
+    let mut pinned = Vec::new();
     let mut needs_review = Vec::new();
     let mut needs_action = Vec::new();
     let mut waiting_on_ci = Vec::new();
@@ -568,6 +708,7 @@ fn build_bucket_key_sections(
         let entry = DisplayEntryKey::Notification(idx);
 
         match notification_bucket(&BucketItem::Notification(notification)) {
+            NotificationBucket::Pinned => pinned.push(entry),
             NotificationBucket::NeedsReview => needs_review.push(entry),
             NotificationBucket::NeedsAction => needs_action.push(entry),
             NotificationBucket::WaitingOnCi => waiting_on_ci.push(entry),
@@ -581,6 +722,7 @@ fn build_bucket_key_sections(
         let entry = DisplayEntryKey::MyPullRequest(idx);
 
         match notification_bucket(&BucketItem::MyPullRequest(pr)) {
+            NotificationBucket::Pinned => pinned.push(entry),
             NotificationBucket::NeedsReview => needs_review.push(entry),
             NotificationBucket::NeedsAction => needs_action.push(entry),
             NotificationBucket::WaitingOnCi => waiting_on_ci.push(entry),
@@ -590,12 +732,16 @@ fn build_bucket_key_sections(
         }
     }
 
-    let mut sections = vec![
+    let mut sections = Vec::new();
+    if !pinned.is_empty() {
+        sections.push((NotificationBucket::Pinned, pinned));
+    }
+    sections.extend([
         (NotificationBucket::ReadyToMerge, ready_to_merge),
         (NotificationBucket::NeedsAction, needs_action),
         (NotificationBucket::WaitingOnCi, waiting_on_ci),
         (NotificationBucket::NeedsReview, needs_review),
-    ];
+    ]);
     if !other.is_empty() {
         sections.push((NotificationBucket::Other, other));
     }
@@ -627,6 +773,11 @@ pub fn display_entry_key(
 }
 
 fn notification_bucket(item: &BucketItem<'_>) -> NotificationBucket {
+    // Pins win over every status so watched threads never move between sections.
+    if item.pinned() {
+        return NotificationBucket::Pinned;
+    }
+
     let subject = item.subject();
     if !subject.kind.eq_ignore_ascii_case("pullrequest") || is_terminal_pull_request(subject) {
         return NotificationBucket::Other;
@@ -678,15 +829,444 @@ fn is_waiting_on_ci(item: &BucketItem<'_>) -> bool {
         && matches!(item.subject().ci_status, Some(CiStatus::Pending))
 }
 
+/// Errors in whatever is being typed, what it would do, otherwise a reminder that a filter is hiding entries.
+fn prompt_status(app: &AppState) -> Option<String> {
+    if app.ex_input.is_some() {
+        return app
+            .ex_error
+            .as_ref()
+            .map(|err| format!("Invalid command: {}", err));
+    }
+    if app.filter_input.is_some() {
+        return app
+            .filter_error
+            .as_ref()
+            .map(|err| format!("Invalid filter: {}", err));
+    }
+    if let Some(search) = &app.history_search {
+        let missing = if search.found.is_none() && !search.query.is_empty() {
+            " (no match)"
+        } else {
+            ""
+        };
+        return Some(format!(
+            "reverse-i-search: {}{}  (Ctrl+R older, Enter keep, Esc cancel)",
+            search.query, missing
+        ));
+    }
+    if let Some(err) = &app.command_error {
+        return Some(format!("Invalid command: {}", err));
+    }
+    if let Some(summary) = pending_summary(&app.pending, &app.keymap) {
+        return Some(summary);
+    }
+    if app.status.is_some() {
+        return None;
+    }
+    app.filter.as_ref().map(|filter| {
+        format!(
+            "Filter: {}  (/ to edit, Esc on an empty line to clear)",
+            filter.query()
+        )
+    })
+}
+
 fn draw_command(f: &mut Frame, area: Rect, app: &AppState) {
     let chunks = Layout::default()
         .direction(Direction::Horizontal)
         .constraints([Constraint::Length(2), Constraint::Min(1)])
         .split(area);
 
-    let prompt = Paragraph::new("> ").style(Style::default().bg(Color::DarkGray));
+    let (prompt, input) = match (&app.ex_input, &app.filter_input) {
+        (Some(ex_input), _) => (": ", ex_input),
+        (None, Some(filter_input)) => ("/ ", filter_input),
+        (None, None) => ("> ", &app.input),
+    };
+    let prompt = Paragraph::new(prompt).style(Style::default().bg(Color::DarkGray));
     f.render_widget(prompt, chunks[0]);
-    f.render_widget(&app.input, chunks[1]);
+    f.render_widget(input, chunks[1]);
+}
+
+fn draw_ignore_manager(f: &mut Frame, size: Rect, app: &AppState, manager: &IgnoreManager) {
+    let area = centered_rect(size, 80, 70);
+    f.render_widget(Clear, area);
+
+    let now = chrono::Utc::now().naive_utc();
+    let candidates: Vec<IgnoreCandidate<'_>> = app
+        .hidden_notifications
+        .iter()
+        .map(IgnoreCandidate::from_notification)
+        .chain(
+            app.hidden_my_prs
+                .iter()
+                .map(IgnoreCandidate::from_pull_request),
+        )
+        .collect();
+    let counts = app.ignore_list.hidden_counts(&candidates, now);
+
+    let title = format!("Ignore list ({} rules)", app.ignore_list.len());
+    let block = Block::default()
+        .title(Line::from(Span::styled(
+            title,
+            Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+        )))
+        .borders(Borders::ALL);
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Min(1),
+            Constraint::Length(1),
+            Constraint::Length(1),
+        ])
+        .split(inner);
+
+    let items = build_ignore_manager_items(&app.ignore_list, &counts, manager, now);
+    let list = if items.is_empty() {
+        List::new(vec![ListItem::new("No ignore rules")])
+    } else {
+        List::new(items)
+    };
+    f.render_widget(list, chunks[0]);
+
+    if let Some(editor) = &manager.editor {
+        f.render_widget(editor, chunks[1]);
+    }
+    let help = if manager.editor.is_some() {
+        "Enter save  Esc cancel  (empty removes the rule)"
+    } else {
+        "j/k move  e edit  x remove  Esc close"
+    };
+    f.render_widget(
+        Paragraph::new(help).style(Style::default().fg(Color::DarkGray)),
+        chunks[2],
+    );
+}
+
+fn build_ignore_manager_items(
+    list: &IgnoreList,
+    counts: &[usize],
+    manager: &IgnoreManager,
+    now: chrono::NaiveDateTime,
+) -> Vec<ListItem<'static>> {
+    list.rules()
+        .iter()
+        .enumerate()
+        .map(|(idx, rule)| {
+            let selected = idx == manager.selected;
+            let hidden = counts.get(idx).copied().unwrap_or(0);
+            let mut header = vec![
+                Span::raw(if selected { "> " } else { "  " }),
+                Span::styled(
+                    rule.matcher.label(),
+                    Style::default().add_modifier(Modifier::BOLD),
+                ),
+                Span::styled(
+                    format!("  hides {}", hidden),
+                    Style::default().fg(Color::Yellow),
+                ),
+            ];
+            if let Some(expires) = rule.expires {
+                let (text, color) = if rule.is_active(now) {
+                    (format!("  until {}", expires), Color::Gray)
+                } else {
+                    (format!("  expired {}", expires), Color::DarkGray)
+                };
+                header.push(Span::styled(text, Style::default().fg(color)));
+            }
+
+            let mut detail = Vec::new();
+            if let Some(info) = manager.details.get(&rule.matcher.label()) {
+                detail.push(match info {
+                    RuleDetail::Loading => {
+                        Span::styled("loading...", Style::default().fg(Color::DarkGray))
+                    }
+                    RuleDetail::Loaded { title, state } => {
+                        Span::raw(format!("[{}] {}", state, title))
+                    }
+                    RuleDetail::Failed(message) => {
+                        Span::styled(message.clone(), Style::default().fg(Color::Red))
+                    }
+                });
+            }
+            if let Some(reason) = &rule.reason {
+                if !detail.is_empty() {
+                    detail.push(Span::raw("  "));
+                }
+                detail.push(Span::styled(
+                    format!("# {}", reason),
+                    Style::default().fg(Color::Gray),
+                ));
+            }
+
+            let mut lines = vec![Line::from(header)];
+            if !detail.is_empty() {
+                let mut spans = vec![Span::raw("    ")];
+                spans.extend(detail);
+                lines.push(Line::from(spans));
+            }
+
+            let mut item = ListItem::new(lines);
+            if selected {
+                item = item.style(Style::default().bg(Color::DarkGray));
+            }
+            item
+        })
+        .collect()
+}
+
+fn preview_area(size: Rect) -> Rect {
+    centered_rect(size, 80, 70)
+}
+
+/// Rows for items in the preview: the overlay minus its borders and footer.
+fn preview_list_height(size: Rect) -> u16 {
+    preview_area(size).height.saturating_sub(3)
+}
+
+/// Furthest the preview can scroll with `count` items before the last one leaves the bottom.
+pub fn preview_max_scroll(count: usize, size: Rect) -> u16 {
+    (count.min(u16::MAX as usize) as u16).saturating_sub(preview_list_height(size))
+}
+
+/// Key hints, plus how many items are off screen so nothing is confirmed unseen.
+fn preview_footer(count: usize, scroll: u16, height: u16) -> String {
+    let hints = "Enter/y run  Esc/n back to the command line";
+    let above = scroll as usize;
+    let below = count.saturating_sub(above + height as usize);
+    if above == 0 && below == 0 {
+        return hints.to_string();
+    }
+    format!(
+        "{}  j/k scroll ({} above, {} more below)",
+        hints, above, below
+    )
+}
+
+fn draw_preview(
+    f: &mut Frame,
+    size: Rect,
+    app: &AppState,
+    pending: &HashMap<usize, Vec<Action>>,
+    scroll: u16,
+) {
+    let area = preview_area(size);
+    f.render_widget(Clear, area);
+
+    let destructive = pending
+        .values()
+        .flatten()
+        .any(|action| is_destructive(*action, &app.custom_actions));
+    let title = format!(
+        "Run {} on {} item{}?",
+        if destructive {
+            "destructive batch"
+        } else {
+            "batch"
+        },
+        pending.len(),
+        if pending.len() == 1 { "" } else { "s" }
+    );
+    let block = Block::default()
+        .title(Line::from(Span::styled(
+            title,
+            Style::default()
+                .fg(Color::Yellow)
+                .add_modifier(Modifier::BOLD),
+        )))
+        .borders(Borders::ALL);
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(1), Constraint::Length(1)])
+        .split(inner);
+
+    let lines = build_preview_lines(
+        pending,
+        &app.notifications,
+        &app.my_prs,
+        &app.custom_actions,
+        &app.keymap,
+        chunks[0].width as usize,
+    );
+    let scroll = scroll.min(preview_max_scroll(lines.len(), size));
+    let footer = preview_footer(lines.len(), scroll, chunks[0].height);
+    let hidden_below = lines.len() > scroll as usize + chunks[0].height as usize;
+    let footer_style = if hidden_below {
+        Style::default().fg(Color::Yellow)
+    } else {
+        Style::default().fg(Color::DarkGray)
+    };
+    f.render_widget(Paragraph::new(lines).scroll((scroll, 0)), chunks[0]);
+    f.render_widget(Paragraph::new(footer).style(footer_style), chunks[1]);
+}
+
+/// One line per targeted item, in display order: number, repo and title, then its actions.
+fn build_preview_lines(
+    pending: &HashMap<usize, Vec<Action>>,
+    notifications: &[Notification],
+    my_prs: &[MyPullRequest],
+    custom_actions: &[CustomAction],
+    keymap: &Keymap,
+    width: usize,
+) -> Vec<Line<'static>> {
+    let mut indices: Vec<usize> = pending.keys().copied().collect();
+    indices.sort_unstable();
+    let index_width = indices.last().map_or(1, |index| index.to_string().len());
+
+    indices
+        .into_iter()
+        .filter_map(|index| {
+            let key = display_entry_key(index, notifications, my_prs)?;
+            let item = bucket_item(key, notifications, my_prs)?;
+            let actions = &pending[&index];
+            let mut spans = vec![Span::styled(
+                format!("{:>width$}  ", index, width = index_width),
+                Style::default().add_modifier(Modifier::BOLD),
+            )];
+            let mut used = index_width + 2;
+            for (pos, action) in actions.iter().enumerate() {
+                let label = if pos + 1 < actions.len() {
+                    format!("{}, ", keymap.action_name(*action))
+                } else {
+                    format!("{}  ", keymap.action_name(*action))
+                };
+                let style = if is_destructive(*action, custom_actions) {
+                    Style::default().fg(Color::Red).add_modifier(Modifier::BOLD)
+                } else {
+                    Style::default().fg(action_color(*action))
+                };
+                used += label.chars().count();
+                spans.push(Span::styled(label, style));
+            }
+            let label = format!("{}  {}", item.repo_full_name(), item.subject().title);
+            spans.push(Span::raw(truncate_with_suffix(
+                &label,
+                width.saturating_sub(used).max(1),
+            )));
+            Some(Line::from(spans))
+        })
+        .collect()
+}
+
+fn help_area(size: Rect) -> Rect {
+    centered_rect(size, 80, 80)
+}
+
+/// Furthest the help can scroll before its last line leaves the bottom of the overlay.
+pub fn help_max_scroll(keymap: &Keymap, macros: &BTreeMap<String, String>, size: Rect) -> u16 {
+    let count = build_help_lines(keymap, macros).len();
+    (count.min(u16::MAX as usize) as u16).saturating_sub(help_area(size).height.saturating_sub(2))
+}
+
+fn draw_help(f: &mut Frame, size: Rect, app: &AppState, scroll: u16) {
+    let area = help_area(size);
+    f.render_widget(Clear, area);
+
+    let block = Block::default()
+        .title(Line::from(Span::styled(
+            "Help (j/k scroll, Esc close)",
+            Style::default().add_modifier(Modifier::BOLD),
+        )))
+        .borders(Borders::ALL);
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    let lines = build_help_lines(&app.keymap, &app.macros);
+    let scroll = scroll.min(help_max_scroll(&app.keymap, &app.macros, size));
+    f.render_widget(Paragraph::new(lines).scroll((scroll, 0)), inner);
+}
+
+/// Help text built from the keymap and command registries, so rebound keys show as bound.
+pub fn build_help_lines(keymap: &Keymap, macros: &BTreeMap<String, String>) -> Vec<Line<'static>> {
+    let (actions, targets) = keymap.help_entries();
+    let key_rows = |entries: Vec<HelpEntry>| {
+        entries
+            .into_iter()
+            .map(|entry| {
+                (
+                    entry.key.to_string(),
+                    format!("{}: {}", entry.name, entry.description),
+                    format!("e.g. {}", entry.example),
+                )
+            })
+            .collect::<Vec<_>>()
+    };
+    let plain_rows = |rows: &[(&str, &str)]| {
+        rows.iter()
+            .map(|(key, description)| (key.to_string(), description.to_string(), String::new()))
+            .collect::<Vec<_>>()
+    };
+    let ex_rows = EX_COMMANDS
+        .iter()
+        .map(|(name, args, description)| {
+            let usage = if args.is_empty() {
+                format!(":{}", name)
+            } else {
+                format!(":{} {}", name, args)
+            };
+            (usage, description.to_string(), String::new())
+        })
+        .collect();
+
+    let mut sections = vec![
+        ("Actions", key_rows(actions)),
+        ("Targets", key_rows(targets)),
+        ("Target syntax", plain_rows(&TARGET_SYNTAX)),
+        ("Keys", plain_rows(&FIXED_KEYS)),
+        ("Colon commands", ex_rows),
+    ];
+    if !macros.is_empty() {
+        let rows = macros
+            .iter()
+            .map(|(name, body)| (format!(":{}", name), body.clone(), String::new()))
+            .collect();
+        sections.push(("Macros", rows));
+    }
+
+    let heading = Style::default()
+        .fg(Color::Yellow)
+        .add_modifier(Modifier::BOLD);
+    let mut lines = vec![Line::from(
+        "Type targets then actions, e.g. 1-3o; Enter runs the line.",
+    )];
+    for (title, rows) in sections {
+        let key_width = rows.iter().map(|(key, _, _)| key.len()).max().unwrap_or(0);
+        lines.push(Line::from(""));
+        lines.push(Line::from(Span::styled(title, heading)));
+        for (key, description, example) in rows {
+            let mut spans = vec![
+                Span::styled(
+                    format!("  {:<width$}  ", key, width = key_width),
+                    Style::default().add_modifier(Modifier::BOLD),
+                ),
+                Span::raw(description),
+            ];
+            if !example.is_empty() {
+                spans.push(Span::styled(
+                    format!("  {}", example),
+                    Style::default().fg(Color::DarkGray),
+                ));
+            }
+            lines.push(Line::from(spans));
+        }
+    }
+    lines
+}
+
+fn centered_rect(area: Rect, percent_x: u16, percent_y: u16) -> Rect {
+    let width = area.width.saturating_mul(percent_x) / 100;
+    let height = area.height.saturating_mul(percent_y) / 100;
+    Rect {
+        x: area.x + (area.width - width) / 2,
+        y: area.y + (area.height - height) / 2,
+        width,
+        height,
+    }
 }
 
 fn draw_status(f: &mut Frame, area: Rect, lines: Vec<String>) {
@@ -695,7 +1275,7 @@ fn draw_status(f: &mut Frame, area: Rect, lines: Vec<String>) {
     f.render_widget(paragraph, area);
 }
 
-fn build_status_lines(width: u16, status: Option<&str>) -> Vec<String> {
+fn build_status_lines(width: u16, status: Option<&str>, keymap: &Keymap) -> Vec<String> {
     let width = (width as usize).max(1);
     let mut lines = Vec::new();
 
@@ -706,25 +1286,27 @@ fn build_status_lines(width: u16, status: Option<&str>) -> Vec<String> {
         lines.push(String::new());
     }
 
-    lines.extend(select_legend_lines(width));
+    lines.extend(select_legend_lines(width, keymap));
     lines
 }
 
-fn select_legend_lines(width: usize) -> Vec<String> {
+fn select_legend_lines(width: usize, keymap: &Keymap) -> Vec<String> {
+    let variants = keymap.legend_variants();
     // Prefer the most descriptive legend that fits the width, even if it needs multiple lines.
-    for variant in LEGEND_VARIANTS {
-        let single = format!("{}  |  {}", variant.commands, variant.targets);
+    for (commands, targets) in &variants {
+        let single = format!("{}  |  {}", commands, targets);
         if single.chars().count() <= width {
             return vec![single];
         }
-        if variant.commands.chars().count() <= width && variant.targets.chars().count() <= width {
-            return vec![variant.commands.to_string(), variant.targets.to_string()];
+        if commands.chars().count() <= width && targets.chars().count() <= width {
+            return vec![commands.clone(), targets.clone()];
         }
     }
 
+    let (commands, targets) = variants.last().expect("legend variants are never empty");
     vec![
-        truncate_with_suffix(COMMANDS_TINY, width),
-        truncate_with_suffix(TARGETS_TINY, width),
+        truncate_with_suffix(commands, width),
+        truncate_with_suffix(targets, width),
     ]
 }
 
@@ -774,12 +1356,12 @@ impl ReviewIndicatorStatus {
         }
     }
 
-    fn target(self) -> char {
+    fn target(self) -> Target {
         match self {
-            Self::Conflicts => '!',
-            Self::Approved => 'a',
-            Self::ReviewRequired => '?',
-            Self::ChangesRequested => 'x',
+            Self::Conflicts => Target::Conflicts,
+            Self::Approved => Target::Approved,
+            Self::ReviewRequired => Target::ReviewRequired,
+            Self::ChangesRequested => Target::ChangesRequested,
         }
     }
 }
@@ -870,7 +1452,7 @@ fn effective_review_indicator_status(subject: &Subject) -> Option<ReviewIndicato
     })
 }
 
-fn pending_style(pending: Option<&Vec<Action>>) -> Style {
+fn pending_style(pending: Option<&Vec<Action>>, theme: &Theme) -> Style {
     let Some(actions) = pending else {
         return Style::default();
     };
@@ -878,12 +1460,53 @@ fn pending_style(pending: Option<&Vec<Action>>) -> Style {
     let color = actions
         .last()
         .copied()
-        .map(action_color)
+        .map(|action| theme.action_color(action))
         .unwrap_or(Color::Reset);
 
     Style::default().fg(color)
 }
 
+/// Every queued action as its key, e.g. `o r d `, so a stray target shows up before Enter.
+fn pending_glyphs(actions: &[Action], keymap: &Keymap, theme: &Theme) -> Vec<Span<'static>> {
+    let mut spans = Vec::new();
+    for action in actions {
+        let key = keymap.action_key(*action).unwrap_or('?');
+        spans.push(Span::styled(
+            key.to_string(),
+            Style::default()
+                .fg(theme.action_color(*action))
+                .add_modifier(Modifier::BOLD),
+        ));
+        spans.push(Span::raw(" "));
+    }
+    spans
+}
+
+/// "3 items: open, read" for the queued commands, with actions in the order they first appear.
+fn pending_summary(pending: &HashMap<usize, Vec<Action>>, keymap: &Keymap) -> Option<String> {
+    if pending.is_empty() {
+        return None;
+    }
+    let mut indices: Vec<usize> = pending.keys().copied().collect();
+    indices.sort_unstable();
+    let mut actions: Vec<Action> = Vec::new();
+    for action in indices.iter().flat_map(|index| &pending[index]) {
+        if !actions.contains(action) {
+            actions.push(*action);
+        }
+    }
+    let names: Vec<&str> = actions
+        .iter()
+        .map(|action| keymap.action_name(*action))
+        .collect();
+    Some(format!(
+        "{} item{}: {}",
+        pending.len(),
+        if pending.len() == 1 { "" } else { "s" },
+        names.join(", ")
+    ))
+}
+
 fn action_color(action: Action) -> Color {
     match action {
         Action::Open => Color::Blue,
@@ -895,6 +1518,9 @@ fn action_color(action: Action) -> Color {
         Action::Review => Color::Cyan,
         Action::ReviewNoAnalyze => Color::Cyan,
         Action::Branch => Color::LightBlue,
+        Action::Worktree => Color::LightCyan,
+        Action::Pin => Color::Magenta,
+        Action::Custom(_) => Color::LightGreen,
     }
 }
 
@@ -1115,6 +1741,24 @@ fn base_notification_style(unread: bool) -> Style {
     }
 }
 
+/// Short label for why a notification arrived. Subscriptions are the common case and get none.
+fn reason_badge(reason: &str) -> Option<&str> {
+    let badge = match reason {
+        "" | "subscribed" | "manual" => return None,
+        "mention" => "@me",
+        "team_mention" => "@team",
+        "review_requested" => "review",
+        "assign" => "assigned",
+        "ci_activity" => "ci",
+        "security_alert" => "security",
+        "state_change" => "state",
+        "approval_requested" => "approval",
+        "invitation" => "invite",
+        other => other,
+    };
+    Some(badge)
+}
+
 fn kind_color(kind: &str) -> Color {
     match kind.to_ascii_lowercase().as_str() {
         "pullrequest" => Color::Cyan,
@@ -1128,43 +1772,78 @@ fn kind_color(kind: &str) -> Color {
 fn build_target_map(
     notifications: &[Notification],
     my_prs: &[MyPullRequest],
-) -> HashMap<char, Vec<usize>> {
-    let mut targets: HashMap<char, Vec<usize>> = HashMap::new();
+    keymap: &Keymap,
+    filter: Option<&Filter>,
+) -> Targets {
+    let mut targets = Targets {
+        sections: NotificationBucket::ALL
+            .into_iter()
+            .map(|bucket| (bucket.name(), Vec::new()))
+            .collect(),
+        ..Targets::default()
+    };
 
-    for (display_idx, key) in display_order(notifications, my_prs).into_iter().enumerate() {
+    let keys = build_bucket_key_sections(notifications, my_prs)
+        .into_iter()
+        .flat_map(|(bucket, keys)| keys.into_iter().map(move |key| (bucket, key)));
+    for (display_idx, (bucket, key)) in keys.enumerate() {
         let index = display_idx + 1;
         let Some(item) = bucket_item(key, notifications, my_prs) else {
             continue;
         };
+        // Section positions count hidden entries too, matching the stable global numbering.
+        if let Some((_, members)) = targets
+            .sections
+            .iter_mut()
+            .find(|(name, _)| *name == bucket.name())
+        {
+            members.push(index);
+        }
+        if !item.visible(filter) {
+            continue;
+        }
+        targets.all.push(index);
+        if let Some(age) = item.age() {
+            targets.ages.push((index, age));
+        }
 
         if matches!(item, BucketItem::Notification(notification) if notification.unread) {
-            targets.entry('u').or_default().push(index);
+            targets.push_group(keymap.target_char(Target::Unread), index);
         }
 
         if is_waiting_on_ci(&item) {
-            targets.entry('w').or_default().push(index);
+            targets.push_group(keymap.target_char(Target::WaitingOnCi), index);
+        }
+
+        if item.pinned() {
+            targets.push_group(keymap.target_char(Target::Pinned), index);
+        }
+
+        if let Some(reason) = item.reason() {
+            targets.push_reason(reason, index);
+        }
+        targets.push_repo(item.repo_full_name(), index);
+        if let Some(author) = &item.subject().author {
+            targets.push_author(author, index);
         }
 
-        push_status_targets(&mut targets, index, item.subject());
+        push_status_targets(&mut targets, index, item.subject(), keymap);
     }
 
     targets
 }
 
-fn push_status_targets(targets: &mut HashMap<char, Vec<usize>>, index: usize, subject: &Subject) {
+fn push_status_targets(targets: &mut Targets, index: usize, subject: &Subject, keymap: &Keymap) {
     for status in ordered_statuses(subject) {
-        let key = match status {
-            SubjectStatus::Merged => 'm',
-            SubjectStatus::Closed => 'c',
-            SubjectStatus::Draft => 'f',
+        let target = match status {
+            SubjectStatus::Merged => Target::Merged,
+            SubjectStatus::Closed => Target::Closed,
+            SubjectStatus::Draft => Target::Draft,
         };
-        targets.entry(key).or_default().push(index);
+        targets.push_group(keymap.target_char(target), index);
     }
     if let Some(review_status) = effective_review_indicator_status(subject) {
-        targets
-            .entry(review_status.target())
-            .or_default()
-            .push(index);
+        targets.push_group(keymap.target_char(review_status.target()), index);
     }
 }
 
@@ -1172,23 +1851,62 @@ pub fn build_pending_map(
     input: &str,
     notifications: &[Notification],
     my_prs: &[MyPullRequest],
-) -> HashMap<usize, Vec<Action>> {
-    let targets = build_target_map(notifications, my_prs);
-    let parsed =
-        crate::commands::parse_commands(input, notifications.len() + my_prs.len(), &targets);
-
-    filter_pending_actions(parsed, notifications, my_prs)
+    keymap: &Keymap,
+    filter: Option<&Filter>,
+) -> Result<HashMap<usize, Vec<Action>>> {
+    let targets = build_target_map(notifications, my_prs, keymap, filter);
+    let parsed = crate::commands::parse_commands(
+        input,
+        notifications.len() + my_prs.len(),
+        &targets,
+        keymap,
+    )?;
+
+    Ok(filter_pending_actions(
+        parsed,
+        notifications,
+        my_prs,
+        filter,
+    ))
+}
+
+/// Resolves a target expression without actions, e.g. for `:snooze`, skipping hidden items.
+pub fn resolve_targets(
+    input: &str,
+    notifications: &[Notification],
+    my_prs: &[MyPullRequest],
+    keymap: &Keymap,
+    filter: Option<&Filter>,
+) -> Result<Vec<usize>> {
+    let targets = build_target_map(notifications, my_prs, keymap, filter);
+    let mut indices = crate::commands::parse_targets(
+        input,
+        notifications.len() + my_prs.len(),
+        &targets,
+        keymap,
+    )?;
+    indices.retain(|index| targets.all.contains(index));
+    Ok(indices)
 }
 
 fn filter_pending_actions(
     parsed: HashMap<usize, Vec<Action>>,
     notifications: &[Notification],
     my_prs: &[MyPullRequest],
+    filter: Option<&Filter>,
 ) -> HashMap<usize, Vec<Action>> {
     let mut filtered: HashMap<usize, Vec<Action>> = HashMap::new();
 
     for (index, actions) in parsed {
-        let entry = match display_entry_key(index, notifications, my_prs) {
+        let key = display_entry_key(index, notifications, my_prs);
+        // Explicit numbers of entries hidden by the `/` filter are dropped too.
+        if key
+            .and_then(|key| bucket_item(key, notifications, my_prs))
+            .is_some_and(|item| !item.visible(filter))
+        {
+            continue;
+        }
+        let entry = match key {
             Some(DisplayEntryKey::Notification(idx)) => {
                 notifications
                     .get(idx)
@@ -1229,7 +1947,10 @@ enum PendingEntry {
 fn action_allowed(action: &Action, entry: &PendingEntry) -> bool {
     match entry {
         PendingEntry::Notification { is_pull_request } => {
-            if matches!(action, Action::Branch | Action::PrettyYank) {
+            if matches!(
+                action,
+                Action::Branch | Action::PrettyYank | Action::Worktree
+            ) {
                 *is_pull_request
             } else {
                 true
@@ -1245,6 +1966,9 @@ fn action_allowed(action: &Action, entry: &PendingEntry) -> bool {
                 | Action::Review
                 | Action::ReviewNoAnalyze
                 | Action::Branch
+                | Action::Worktree
+                | Action::Pin
+                | Action::Custom(_)
         ),
     }
 }
@@ -1252,18 +1976,23 @@ fn action_allowed(action: &Action, entry: &PendingEntry) -> bool {
 #[cfg(test)]
 mod tests {
     use super::{
-        action_marker, base_notification_style, build_bucket_sections, build_pending_map,
-        build_status_lines, ci_indicator, collect_layout_max, kind_color, layout_widths,
-        notification_bucket, pending_style, render_repo_and_author, review_indicator,
-        select_legend_lines, split_bucket_area, status_prefixes, truncate_with_suffix, BucketItem,
-        LayoutMax, NotificationBucket, COMMANDS_FULL, READ_NOTIFICATION_COLOR, TARGETS_FULL,
+        action_marker, base_notification_style, build_bucket_sections, build_help_lines,
+        build_pending_map, build_preview_lines, build_status_lines, ci_indicator,
+        collect_layout_max, kind_color, layout_widths, notification_bucket, pending_glyphs,
+        pending_style, pending_summary, preview_footer, preview_max_scroll, reason_badge,
+        render_repo_and_author, review_indicator, select_legend_lines, split_bucket_area,
+        status_prefixes, truncate_with_suffix, BucketItem, LayoutMax, NotificationBucket, Theme,
+        READ_NOTIFICATION_COLOR,
     };
+    use crate::filter::Filter;
+    use crate::keymap::Keymap;
     use crate::types::{
         Action, CiStatus, MergeStateStatus, MyPullRequest, Notification, Repository, ReviewStatus,
         Subject, SubjectStatus,
     };
     use ratatui::layout::Rect;
     use ratatui::style::{Color, Modifier, Style};
+    use std::collections::{BTreeMap, HashMap};
 
     fn sample_bucket_notification(
         id: &str,
@@ -1285,6 +2014,7 @@ mod tests {
             node_id: format!("node-{id}"),
             subject_id: None,
             unread: true,
+            pinned: false,
             reason: reason.to_string(),
             updated_at: "2024-01-01T00:00:00Z".to_string(),
             subject: Subject {
@@ -1316,6 +2046,7 @@ mod tests {
         MyPullRequest {
             id: format!("pr-{id}"),
             updated_at: "2024-01-01T00:00:00Z".to_string(),
+            pinned: false,
             subject: Subject {
                 title: format!("My PR {id}"),
                 url: format!("https://github.com/acme/widgets/pull/{id}"),
@@ -1345,6 +2076,7 @@ mod tests {
                 node_id: "node-1".to_string(),
                 subject_id: None,
                 unread: true,
+                pinned: false,
                 reason: "mention".to_string(),
                 updated_at: "2024-01-01T00:00:00Z".to_string(),
                 subject: Subject {
@@ -1370,6 +2102,7 @@ mod tests {
                 node_id: "node-2".to_string(),
                 subject_id: None,
                 unread: true,
+                pinned: false,
                 reason: "mention".to_string(),
                 updated_at: "2024-01-01T00:00:00Z".to_string(),
                 subject: Subject {
@@ -1392,7 +2125,8 @@ mod tests {
             },
         ];
 
-        let map = build_pending_map("1o2r", &notifications, &my_prs);
+        let map =
+            build_pending_map("1o2r", &notifications, &my_prs, &Keymap::default(), None).unwrap();
         assert_eq!(map.get(&1), Some(&vec![Action::Open]));
         assert_eq!(map.get(&2), Some(&vec![Action::Read]));
     }
@@ -1403,6 +2137,7 @@ mod tests {
         let my_prs = vec![MyPullRequest {
             id: "pr-1".to_string(),
             updated_at: "2024-01-01T00:00:00Z".to_string(),
+            pinned: false,
             subject: Subject {
                 title: "My PR".to_string(),
                 url: "https://github.com/acme/widgets/pull/99".to_string(),
@@ -1422,10 +2157,12 @@ mod tests {
             url: "https://github.com/acme/widgets/pull/99".to_string(),
         }];
 
-        let map = build_pending_map("1p", &notifications, &my_prs);
+        let map =
+            build_pending_map("1p", &notifications, &my_prs, &Keymap::default(), None).unwrap();
         assert_eq!(map.get(&1), Some(&vec![Action::Review]));
 
-        let map = build_pending_map("1P", &notifications, &my_prs);
+        let map =
+            build_pending_map("1P", &notifications, &my_prs, &Keymap::default(), None).unwrap();
         assert_eq!(map.get(&1), Some(&vec![Action::ReviewNoAnalyze]));
     }
 
@@ -1438,6 +2175,7 @@ mod tests {
                 node_id: "node-1".to_string(),
                 subject_id: None,
                 unread: true,
+                pinned: false,
                 reason: "review_requested".to_string(),
                 updated_at: "2024-01-01T00:00:00Z".to_string(),
                 subject: Subject {
@@ -1463,6 +2201,7 @@ mod tests {
                 node_id: "node-2".to_string(),
                 subject_id: None,
                 unread: true,
+                pinned: false,
                 reason: "review_requested".to_string(),
                 updated_at: "2024-01-01T00:00:00Z".to_string(),
                 subject: Subject {
@@ -1488,6 +2227,7 @@ mod tests {
                 node_id: "node-3".to_string(),
                 subject_id: None,
                 unread: true,
+                pinned: false,
                 reason: "review_requested".to_string(),
                 updated_at: "2024-01-01T00:00:00Z".to_string(),
                 subject: Subject {
@@ -1513,6 +2253,7 @@ mod tests {
                 node_id: "node-4".to_string(),
                 subject_id: None,
                 unread: true,
+                pinned: false,
                 reason: "review_requested".to_string(),
                 updated_at: "2024-01-01T00:00:00Z".to_string(),
                 subject: Subject {
@@ -1538,6 +2279,7 @@ mod tests {
                 node_id: "node-5".to_string(),
                 subject_id: None,
                 unread: true,
+                pinned: false,
                 reason: "review_requested".to_string(),
                 updated_at: "2024-01-01T00:00:00Z".to_string(),
                 subject: Subject {
@@ -1560,15 +2302,18 @@ mod tests {
             },
         ];
 
-        let pending = build_pending_map("?o", &notifications, &my_prs);
+        let pending =
+            build_pending_map("?o", &notifications, &my_prs, &Keymap::default(), None).unwrap();
         assert_eq!(pending.get(&1), Some(&vec![Action::Open]));
         assert!(!pending.contains_key(&3));
         assert!(!pending.contains_key(&5));
 
-        let pending = build_pending_map("ao", &notifications, &my_prs);
+        let pending =
+            build_pending_map("ao", &notifications, &my_prs, &Keymap::default(), None).unwrap();
         assert_eq!(pending.get(&4), Some(&vec![Action::Open]));
 
-        let pending = build_pending_map("xo", &notifications, &my_prs);
+        let pending =
+            build_pending_map("xo", &notifications, &my_prs, &Keymap::default(), None).unwrap();
         assert_eq!(pending.get(&2), Some(&vec![Action::Open]));
     }
 
@@ -1596,7 +2341,8 @@ mod tests {
             ),
         ];
 
-        let pending = build_pending_map("!o", &notifications, &my_prs);
+        let pending =
+            build_pending_map("!o", &notifications, &my_prs, &Keymap::default(), None).unwrap();
         assert_eq!(pending.get(&1), Some(&vec![Action::Open]));
         assert_eq!(pending.get(&2), Some(&vec![Action::Open]));
     }
@@ -1609,6 +2355,7 @@ mod tests {
             node_id: "node-1".to_string(),
             subject_id: None,
             unread: true,
+            pinned: false,
             reason: "mention".to_string(),
             updated_at: "2024-01-01T00:00:00Z".to_string(),
             subject: Subject {
@@ -1630,7 +2377,8 @@ mod tests {
             url: "https://github.com/acme/widgets/issues/1".to_string(),
         }];
 
-        let pending = build_pending_map("1b", &notifications, &my_prs);
+        let pending =
+            build_pending_map("1b", &notifications, &my_prs, &Keymap::default(), None).unwrap();
         assert!(pending.is_empty());
     }
 
@@ -1642,6 +2390,7 @@ mod tests {
             node_id: "node-1".to_string(),
             subject_id: Some("pr-1".to_string()),
             unread: true,
+            pinned: false,
             reason: "mention".to_string(),
             updated_at: "2024-01-01T00:00:00Z".to_string(),
             subject: Subject {
@@ -1663,7 +2412,8 @@ mod tests {
             url: "https://github.com/acme/widgets/pull/1".to_string(),
         }];
 
-        let pending = build_pending_map("1b", &notifications, &my_prs);
+        let pending =
+            build_pending_map("1b", &notifications, &my_prs, &Keymap::default(), None).unwrap();
         assert_eq!(pending.get(&1), Some(&vec![Action::Branch]));
     }
 
@@ -1675,6 +2425,7 @@ mod tests {
             node_id: "node-1".to_string(),
             subject_id: None,
             unread: true,
+            pinned: false,
             reason: "mention".to_string(),
             updated_at: "2024-01-01T00:00:00Z".to_string(),
             subject: Subject {
@@ -1696,10 +2447,12 @@ mod tests {
             url: "https://github.com/acme/widgets/pull/1".to_string(),
         }];
 
-        let pending = build_pending_map("fo", &notifications, &my_prs);
+        let pending =
+            build_pending_map("fo", &notifications, &my_prs, &Keymap::default(), None).unwrap();
         assert_eq!(pending.get(&1), Some(&vec![Action::Open]));
 
-        let pending = build_pending_map("co", &notifications, &my_prs);
+        let pending =
+            build_pending_map("co", &notifications, &my_prs, &Keymap::default(), None).unwrap();
         assert_eq!(pending.get(&1), Some(&vec![Action::Open]));
     }
 
@@ -1712,6 +2465,7 @@ mod tests {
                 node_id: "node-1".to_string(),
                 subject_id: None,
                 unread: true,
+                pinned: false,
                 reason: "mention".to_string(),
                 updated_at: "2024-01-01T00:00:00Z".to_string(),
                 subject: Subject {
@@ -1737,6 +2491,7 @@ mod tests {
                 node_id: "node-2".to_string(),
                 subject_id: None,
                 unread: false,
+                pinned: false,
                 reason: "mention".to_string(),
                 updated_at: "2024-01-01T00:00:00Z".to_string(),
                 subject: Subject {
@@ -1759,7 +2514,8 @@ mod tests {
             },
         ];
 
-        let pending = build_pending_map("uo", &notifications, &my_prs);
+        let pending =
+            build_pending_map("uo", &notifications, &my_prs, &Keymap::default(), None).unwrap();
         assert_eq!(pending.get(&1), Some(&vec![Action::Open]));
         assert!(!pending.contains_key(&2));
     }
@@ -1771,6 +2527,7 @@ mod tests {
             node_id: "node-1".to_string(),
             subject_id: Some("pr-1".to_string()),
             unread: false,
+            pinned: false,
             reason: "mention".to_string(),
             updated_at: "2024-01-01T00:00:00Z".to_string(),
             subject: Subject {
@@ -1795,6 +2552,7 @@ mod tests {
         let my_prs = vec![MyPullRequest {
             id: "pr-2".to_string(),
             updated_at: "2024-01-01T00:00:00Z".to_string(),
+            pinned: false,
             subject: Subject {
                 title: "My PR".to_string(),
                 url: "https://github.com/acme/widgets/pull/2".to_string(),
@@ -1814,21 +2572,24 @@ mod tests {
             url: "https://github.com/acme/widgets/pull/2".to_string(),
         }];
 
-        let pending = build_pending_map("2dq", &notifications, &my_prs);
+        let pending =
+            build_pending_map("2dq", &notifications, &my_prs, &Keymap::default(), None).unwrap();
         assert_eq!(pending.get(&2), Some(&vec![Action::Unsubscribe]));
 
-        let pending = build_pending_map("2o", &notifications, &my_prs);
+        let pending =
+            build_pending_map("2o", &notifications, &my_prs, &Keymap::default(), None).unwrap();
         assert_eq!(pending.get(&2), Some(&vec![Action::Open]));
     }
 
     #[test]
-    fn build_pending_map_ignores_unknown_action_on_notification() {
+    fn build_pending_map_rejects_unknown_action_on_notification() {
         let my_prs = Vec::new();
         let notifications = vec![Notification {
             id: "thread-1".to_string(),
             node_id: "node-1".to_string(),
             subject_id: None,
             unread: false,
+            pinned: false,
             reason: "mention".to_string(),
             updated_at: "2024-01-01T00:00:00Z".to_string(),
             subject: Subject {
@@ -1850,8 +2611,9 @@ mod tests {
             url: "https://github.com/acme/widgets/issues/1".to_string(),
         }];
 
-        let pending = build_pending_map("1s", &notifications, &my_prs);
-        assert!(pending.is_empty());
+        let err =
+            build_pending_map("1s", &notifications, &my_prs, &Keymap::default(), None).unwrap_err();
+        assert_eq!(err.to_string(), "unknown key 's'");
     }
 
     #[test]
@@ -1883,7 +2645,8 @@ mod tests {
             Some(MergeStateStatus::Blocked),
         )];
 
-        let pending = build_pending_map("wo", &notifications, &my_prs);
+        let pending =
+            build_pending_map("wo", &notifications, &my_prs, &Keymap::default(), None).unwrap();
         assert_eq!(pending.get(&1), Some(&vec![Action::Open]));
         assert_eq!(pending.get(&2), Some(&vec![Action::Open]));
         assert_eq!(pending.get(&3), Some(&vec![Action::Open]));
@@ -2007,8 +2770,13 @@ mod tests {
         )];
         let my_pr_times = vec!["9m".to_string()];
 
-        let sections =
-            build_bucket_sections(&notifications, &notification_times, &my_prs, &my_pr_times);
+        let sections = build_bucket_sections(
+            &notifications,
+            &notification_times,
+            &my_prs,
+            &my_pr_times,
+            None,
+        );
 
         assert_eq!(sections.len(), 6);
         assert_eq!(sections[0].bucket, NotificationBucket::ReadyToMerge);
@@ -2028,6 +2796,196 @@ mod tests {
         assert!(sections[5].entries.is_empty());
     }
 
+    #[test]
+    fn build_pending_map_targets_reasons() {
+        let notifications = vec![
+            sample_bucket_notification("1", "ci_activity", "Issue", Vec::new(), None, None, None),
+            sample_bucket_notification("2", "mention", "Issue", Vec::new(), None, None, None),
+            sample_bucket_notification("3", "ci_activity", "Issue", Vec::new(), None, None, None),
+        ];
+        let pending = build_pending_map(
+            "#ci_activity d",
+            &notifications,
+            &[],
+            &Keymap::default(),
+            None,
+        )
+        .unwrap();
+        let mut targeted: Vec<usize> = pending.keys().copied().collect();
+        targeted.sort_unstable();
+        assert_eq!(targeted, vec![1, 3]);
+        assert_eq!(pending.get(&1), Some(&vec![Action::Done]));
+
+        assert_eq!(reason_badge("mention"), Some("@me"));
+        assert_eq!(reason_badge("subscribed"), None);
+        assert_eq!(reason_badge("your_activity"), Some("your_activity"));
+    }
+
+    #[test]
+    fn build_pending_map_targets_sections() {
+        let ready = || {
+            sample_bucket_notification(
+                "1",
+                "mention",
+                "PullRequest",
+                Vec::new(),
+                Some(CiStatus::Success),
+                Some(ReviewStatus::Approved),
+                Some(MergeStateStatus::Clean),
+            )
+        };
+        let notifications = vec![
+            sample_bucket_notification("3", "mention", "Issue", Vec::new(), None, None, None),
+            ready(),
+            ready(),
+        ];
+        let keymap = Keymap::default();
+
+        // Ready to Merge is drawn first, so its second item is #2 overall.
+        let pending = build_pending_map("%ready2 d", &notifications, &[], &keymap, None).unwrap();
+        assert_eq!(pending.keys().collect::<Vec<_>>(), vec![&2]);
+        let pending = build_pending_map("%other d", &notifications, &[], &keymap, None).unwrap();
+        assert_eq!(pending.keys().collect::<Vec<_>>(), vec![&3]);
+        let pending = build_pending_map("%draft d", &notifications, &[], &keymap, None).unwrap();
+        assert!(pending.is_empty());
+    }
+
+    #[test]
+    fn preview_scrolls_and_counts_hidden_items() {
+        let size = Rect::new(0, 0, 100, 20);
+        // 70% of 20 rows is 14, less two borders and the footer.
+        assert_eq!(preview_max_scroll(40, size), 29);
+        assert_eq!(preview_max_scroll(5, size), 0);
+        assert_eq!(
+            preview_footer(5, 0, 11),
+            "Enter/y run  Esc/n back to the command line"
+        );
+        assert_eq!(
+            preview_footer(40, 0, 11),
+            "Enter/y run  Esc/n back to the command line  j/k scroll (0 above, 29 more below)"
+        );
+        assert_eq!(
+            preview_footer(40, 29, 11),
+            "Enter/y run  Esc/n back to the command line  j/k scroll (29 above, 0 more below)"
+        );
+    }
+
+    #[test]
+    fn build_preview_lines_lists_targets_in_order() {
+        let notifications = vec![
+            sample_bucket_notification("1", "mention", "Issue", Vec::new(), None, None, None),
+            sample_bucket_notification("2", "mention", "Issue", Vec::new(), None, None, None),
+        ];
+        let pending = HashMap::from([
+            (2, vec![Action::Read, Action::Unsubscribe]),
+            (1, vec![Action::Open]),
+        ]);
+        let lines =
+            build_preview_lines(&pending, &notifications, &[], &[], &Keymap::default(), 200);
+        let text: Vec<String> = lines
+            .iter()
+            .map(|line| {
+                line.spans
+                    .iter()
+                    .map(|span| span.content.as_ref())
+                    .collect()
+            })
+            .collect();
+        assert_eq!(
+            text,
+            vec![
+                "1  open  acme/widgets  Notification 1",
+                "2  read, unsubscribe  acme/widgets  Notification 2",
+            ]
+        );
+        assert_ne!(lines[1].spans[1].style.fg, Some(Color::Red));
+        assert_eq!(lines[1].spans[2].style.fg, Some(Color::Red));
+    }
+
+    #[test]
+    fn build_help_lines_follow_the_keymap() {
+        let keymap = Keymap::from_overrides(
+            &BTreeMap::from([("unsubscribe".to_string(), "Q".to_string())]),
+            &BTreeMap::new(),
+            &[],
+        )
+        .unwrap();
+        let macros = BTreeMap::from([("triage".to_string(), "md cd".to_string())]);
+        let text: Vec<String> = build_help_lines(&keymap, &macros)
+            .iter()
+            .map(|line| {
+                line.spans
+                    .iter()
+                    .map(|span| span.content.as_ref())
+                    .collect()
+            })
+            .collect();
+        let row = |prefix: &str| {
+            text.iter()
+                .find(|line| line.trim_start().starts_with(prefix))
+                .cloned()
+                .unwrap_or_else(|| panic!("no help row for {prefix}"))
+        };
+
+        assert!(row("Q ").contains("unsub/ignore"));
+        assert!(row("Q ").ends_with("e.g. 6Q"));
+        assert!(!text.iter().any(|line| line.trim_start().starts_with("q ")));
+        assert!(row("F1").contains("help"));
+        assert!(row(":snooze").contains("<targets> <duration>"));
+        assert!(row(":triage").ends_with("md cd"));
+        for title in [
+            "Actions",
+            "Targets",
+            "Target syntax",
+            "Keys",
+            "Colon commands",
+            "Macros",
+        ] {
+            assert!(text.iter().any(|line| line == title), "{title}");
+        }
+    }
+
+    #[test]
+    fn filter_hides_entries_without_renumbering() {
+        let notifications = vec![
+            sample_bucket_notification(
+                "1",
+                "review_requested",
+                "PullRequest",
+                Vec::new(),
+                None,
+                Some(ReviewStatus::ReviewRequired),
+                None,
+            ),
+            sample_bucket_notification("2", "mention", "Issue", Vec::new(), None, None, None),
+            sample_bucket_notification("3", "mention", "Issue", Vec::new(), None, None, None),
+        ];
+        let times = vec!["1m".to_string(); 3];
+        let filter = Filter::parse("reason:mention").unwrap();
+
+        let sections = build_bucket_sections(&notifications, &times, &[], &[], Some(&filter));
+        let other = sections
+            .iter()
+            .find(|section| section.bucket == NotificationBucket::Other)
+            .unwrap();
+        let indices: Vec<usize> = other.entries.iter().map(|entry| entry.index).collect();
+        assert_eq!(indices, vec![2, 3]);
+        assert_eq!(other.total, Some(2));
+        assert!(sections[3].entries.is_empty());
+        assert_eq!(sections[3].total, Some(1));
+
+        // Group targets and explicit numbers only reach visible entries.
+        let keymap = Keymap::default();
+        let pending = build_pending_map("uo", &notifications, &[], &keymap, Some(&filter)).unwrap();
+        let mut targeted: Vec<usize> = pending.keys().copied().collect();
+        targeted.sort_unstable();
+        assert_eq!(targeted, vec![2, 3]);
+        let pending =
+            build_pending_map("1-3r", &notifications, &[], &keymap, Some(&filter)).unwrap();
+        assert!(!pending.contains_key(&1));
+        assert_eq!(pending.len(), 2);
+    }
+
     #[test]
     fn split_bucket_area_prioritizes_earlier_sections() {
         let notifications = vec![
@@ -2094,8 +3052,13 @@ mod tests {
         )];
         let my_pr_times = vec!["9m".to_string()];
 
-        let sections =
-            build_bucket_sections(&notifications, &notification_times, &my_prs, &my_pr_times);
+        let sections = build_bucket_sections(
+            &notifications,
+            &notification_times,
+            &my_prs,
+            &my_pr_times,
+            None,
+        );
         let heights: Vec<u16> = split_bucket_area(
             Rect {
                 x: 0,
@@ -2178,8 +3141,13 @@ mod tests {
         )];
         let my_pr_times = vec!["9m".to_string()];
 
-        let sections =
-            build_bucket_sections(&notifications, &notification_times, &my_prs, &my_pr_times);
+        let sections = build_bucket_sections(
+            &notifications,
+            &notification_times,
+            &my_prs,
+            &my_pr_times,
+            None,
+        );
         let heights: Vec<u16> = split_bucket_area(
             Rect {
                 x: 0,
@@ -2240,6 +3208,61 @@ mod tests {
         );
     }
 
+    #[test]
+    fn notification_bucket_places_pinned_items_first() {
+        let mut notification = sample_bucket_notification(
+            "8",
+            "mention",
+            "PullRequest",
+            Vec::new(),
+            Some(CiStatus::Failure),
+            Some(ReviewStatus::Approved),
+            Some(MergeStateStatus::Clean),
+        );
+        notification.pinned = true;
+        let mut my_pr = sample_bucket_my_pr("9", None, Some(ReviewStatus::Approved), None);
+        my_pr.pinned = true;
+
+        assert_eq!(
+            notification_bucket(&BucketItem::Notification(&notification)),
+            NotificationBucket::Pinned
+        );
+
+        let notifications = vec![sample_bucket_notification(
+            "1",
+            "mention",
+            "PullRequest",
+            Vec::new(),
+            None,
+            Some(ReviewStatus::Approved),
+            Some(MergeStateStatus::Clean),
+        )];
+        let times = vec!["1m".to_string()];
+        let my_prs = vec![my_pr];
+        let my_pr_times = vec!["2m".to_string()];
+        let sections = build_bucket_sections(&notifications, &times, &my_prs, &my_pr_times, None);
+        assert_eq!(sections[0].bucket, NotificationBucket::Pinned);
+        assert_eq!(sections[0].entries[0].index, 1);
+        assert_eq!(sections[1].bucket, NotificationBucket::ReadyToMerge);
+        assert_eq!(sections[1].entries[0].index, 2);
+    }
+
+    #[test]
+    fn build_pending_map_targets_pinned() {
+        let mut pinned =
+            sample_bucket_notification("2", "mention", "Issue", Vec::new(), None, None, None);
+        pinned.pinned = true;
+        let notifications = vec![
+            sample_bucket_notification("1", "mention", "Issue", Vec::new(), None, None, None),
+            pinned,
+        ];
+
+        let pending =
+            build_pending_map("Tr", &notifications, &[], &Keymap::default(), None).unwrap();
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending.get(&1), Some(&vec![Action::Read]));
+    }
+
     #[test]
     fn truncate_with_suffix_respects_max() {
         assert_eq!(truncate_with_suffix("short", 10), "short");
@@ -2315,6 +3338,7 @@ mod tests {
             node_id: "node-1".to_string(),
             subject_id: None,
             unread: true,
+            pinned: false,
             reason: "mention".to_string(),
             updated_at: "2024-01-01T00:00:00Z".to_string(),
             subject: Subject {
@@ -2338,6 +3362,7 @@ mod tests {
         let my_prs = vec![MyPullRequest {
             id: "pr-1".to_string(),
             updated_at: "2024-01-01T00:00:00Z".to_string(),
+            pinned: false,
             subject: Subject {
                 title: "A much longer title from My PRs".to_string(),
                 url: "https://github.com/acme/widgets/pull/99".to_string(),
@@ -2389,6 +3414,7 @@ mod tests {
             node_id: "node-3".to_string(),
             subject_id: None,
             unread: true,
+            pinned: false,
             reason: "review_requested".to_string(),
             updated_at: "2024-01-01T00:00:00Z".to_string(),
             subject: Subject {
@@ -2421,6 +3447,7 @@ mod tests {
             node_id: "node-3b".to_string(),
             subject_id: None,
             unread: true,
+            pinned: false,
             reason: "review_requested".to_string(),
             updated_at: "2024-01-01T00:00:00Z".to_string(),
             subject: Subject {
@@ -2457,6 +3484,7 @@ mod tests {
             node_id: "node-4".to_string(),
             subject_id: None,
             unread: true,
+            pinned: false,
             reason: "review_requested".to_string(),
             updated_at: "2024-01-01T00:00:00Z".to_string(),
             subject: Subject {
@@ -2488,6 +3516,7 @@ mod tests {
             node_id: "node-5".to_string(),
             subject_id: None,
             unread: true,
+            pinned: false,
             reason: "review_requested".to_string(),
             updated_at: "2024-01-01T00:00:00Z".to_string(),
             subject: Subject {
@@ -2515,7 +3544,7 @@ mod tests {
     #[test]
     fn status_lines_fit_widths() {
         for width in [20u16, 40, 80, 120] {
-            let lines = build_status_lines(width, None);
+            let lines = build_status_lines(width, None, &Keymap::default());
             assert!(!lines.is_empty());
             for line in lines {
                 assert!(line.chars().count() <= width as usize);
@@ -2526,7 +3555,7 @@ mod tests {
     #[test]
     fn status_lines_append_status_when_space_allows() {
         let status = "Executed 3 actions";
-        let lines = build_status_lines(400, Some(status));
+        let lines = build_status_lines(400, Some(status), &Keymap::default());
         assert!(lines.len() >= 2);
         assert_eq!(lines[0], status);
     }
@@ -2535,7 +3564,7 @@ mod tests {
     fn status_lines_truncate_status_when_too_long() {
         let status = "Executed 123 actions with a very long error summary";
         let width = 20u16;
-        let lines = build_status_lines(width, Some(status));
+        let lines = build_status_lines(width, Some(status), &Keymap::default());
         let first = lines.first().expect("status line");
         assert!(first.chars().count() <= width as usize);
         assert!(first.starts_with("Executed"));
@@ -2543,26 +3572,84 @@ mod tests {
 
     #[test]
     fn legend_includes_ignore_label() {
-        let lines = build_status_lines(200, None);
+        let lines = build_status_lines(200, None, &Keymap::default());
         let joined = lines.join(" ");
         assert!(joined.contains("unsub/ignore"));
     }
 
     #[test]
     fn select_legend_lines_prefers_full_when_width_allows() {
-        let combined = format!("{}  |  {}", COMMANDS_FULL, TARGETS_FULL);
+        let keymap = Keymap::default();
+        let (commands, targets) = keymap.legend_variants()[0].clone();
+        let combined = format!("{}  |  {}", commands, targets);
         let width = combined.chars().count();
-        let lines = select_legend_lines(width);
+        let lines = select_legend_lines(width, &keymap);
         assert_eq!(lines, vec![combined]);
     }
 
     #[test]
     fn pending_style_uses_last_action() {
         let actions = vec![Action::Read, Action::Unsubscribe];
-        let style = pending_style(Some(&actions));
+        let style = pending_style(Some(&actions), &Theme::default());
         assert_eq!(style, Style::default().fg(Color::Red));
     }
 
+    #[test]
+    fn pending_glyphs_show_every_queued_action() {
+        let actions = vec![Action::Open, Action::Read, Action::Done];
+        let glyphs = pending_glyphs(&actions, &Keymap::default(), &Theme::default());
+        let text: String = glyphs.iter().map(|span| span.content.as_ref()).collect();
+        assert_eq!(text, "o r d ");
+        assert_eq!(glyphs[4].style.fg, Some(Color::Green));
+    }
+
+    #[test]
+    fn pending_summary_counts_items_and_lists_actions() {
+        let keymap = Keymap::default();
+        assert_eq!(pending_summary(&HashMap::new(), &keymap), None);
+        let pending = HashMap::from([
+            (3, vec![Action::Read]),
+            (1, vec![Action::Open]),
+            (2, vec![Action::Open, Action::Read]),
+        ]);
+        assert_eq!(
+            pending_summary(&pending, &keymap).as_deref(),
+            Some("3 items: open, read")
+        );
+        let single = HashMap::from([(4, vec![Action::Read])]);
+        assert_eq!(
+            pending_summary(&single, &keymap).as_deref(),
+            Some("1 item: read")
+        );
+    }
+
+    #[test]
+    fn theme_overrides_action_and_bucket_colors() {
+        let mut theme = Theme::default();
+        theme
+            .set_action_color("unsubscribe", Color::Yellow)
+            .unwrap();
+        theme.set_bucket_color("draft", Color::Blue).unwrap();
+        assert!(theme.set_action_color("merge", Color::Red).is_err());
+        assert!(theme.set_bucket_color("soon", Color::Red).is_err());
+
+        let actions = vec![Action::Unsubscribe];
+        assert_eq!(
+            pending_style(Some(&actions), &theme),
+            Style::default().fg(Color::Yellow)
+        );
+        assert_eq!(
+            theme.header_style(NotificationBucket::Draft),
+            Style::default()
+                .fg(Color::Blue)
+                .add_modifier(Modifier::BOLD)
+        );
+        assert_eq!(
+            theme.header_style(NotificationBucket::Pinned),
+            NotificationBucket::Pinned.header_style()
+        );
+    }
+
     #[test]
     fn action_marker_shows_spinner_when_executing() {
         let marker = action_marker(true, true);
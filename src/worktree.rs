@@ -0,0 +1,228 @@
+use std::{
+    fs::{self, OpenOptions},
+    io::Write,
+    path::{Component, Path, PathBuf},
+    process::{Command, Stdio},
+};
+
+use anyhow::{anyhow, Context, Result};
+
+use crate::repos::base_remote;
+
+/// A pull request to check out into its own worktree.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WorktreeRequest {
+    pub repo_full_name: String,
+    pub number: i64,
+}
+
+impl WorktreeRequest {
+    /// Namespaced so it never collides with, or resets, a branch the user created.
+    pub fn local_branch(&self) -> String {
+        format!("pr-{}", self.number)
+    }
+
+    /// GitHub keeps every PR head here on the base repository, forks included, even deleted ones.
+    pub fn pull_ref(&self) -> String {
+        format!("refs/pull/{}/head", self.number)
+    }
+}
+
+pub fn worktree_path(repo_dir: &Path, dir_name: &str, number: i64) -> PathBuf {
+    repo_dir.join(dir_name).join(format!("pr-{}", number))
+}
+
+/// Creates the worktree, or fast-forwards it if it already exists, and returns its path.
+pub fn prepare_worktree(
+    repo_dir: &Path,
+    dir_name: &str,
+    request: &WorktreeRequest,
+) -> Result<PathBuf> {
+    let path = worktree_path(repo_dir, dir_name, request.number);
+    // Fetch through the clone's own remote so its credentials apply and nothing prompts.
+    let remote = base_remote(repo_dir, &request.repo_full_name)?;
+    let pull_ref = request.pull_ref();
+
+    if path.is_dir() {
+        // FETCH_HEAD is per worktree, so fetch from inside the existing one.
+        git(&path, &["fetch", &remote, &pull_ref])?;
+        git(&path, &["merge", "--ff-only", "FETCH_HEAD"])?;
+        return Ok(path);
+    }
+
+    exclude_worktree_dir(repo_dir, dir_name)?;
+    git(repo_dir, &["fetch", &remote, &pull_ref])?;
+
+    let branch = request.local_branch();
+    let branch_ref = format!("refs/heads/{}", branch);
+    let path_arg = path.to_string_lossy();
+    if git_succeeds(repo_dir, &["rev-parse", "--verify", "--quiet", &branch_ref])? {
+        // Left behind by `git worktree remove`: fast-forward it if it's only behind the PR,
+        // and otherwise keep its local commits as they are.
+        if git_succeeds(
+            repo_dir,
+            &["merge-base", "--is-ancestor", &branch_ref, "FETCH_HEAD"],
+        )? {
+            git(repo_dir, &["branch", "--force", &branch, "FETCH_HEAD"])?;
+        }
+        git(repo_dir, &["worktree", "add", &path_arg, &branch])?;
+    } else {
+        git(
+            repo_dir,
+            &["worktree", "add", "-b", &branch, &path_arg, "FETCH_HEAD"],
+        )?;
+    }
+    Ok(path)
+}
+
+fn git(dir: &Path, args: &[&str]) -> Result<()> {
+    let status = Command::new("git")
+        .current_dir(dir)
+        .args(args)
+        .status()
+        .context("failed to run git")?;
+    if !status.success() {
+        return Err(anyhow!("git {} exited with {}", args[0], status));
+    }
+    Ok(())
+}
+
+/// Runs a query quietly and reports whether it succeeded.
+fn git_succeeds(dir: &Path, args: &[&str]) -> Result<bool> {
+    let status = Command::new("git")
+        .current_dir(dir)
+        .args(args)
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .context("failed to run git")?;
+    Ok(status.success())
+}
+
+/// Keeps worktrees nested in the repository out of `git status`.
+fn exclude_worktree_dir(repo_dir: &Path, dir_name: &str) -> Result<()> {
+    let info_dir = repo_dir.join(".git").join("info");
+    let nested = Path::new(dir_name)
+        .components()
+        .all(|component| matches!(component, Component::Normal(_)));
+    if !repo_dir.join(".git").is_dir() || !nested {
+        return Ok(());
+    }
+
+    let exclude = info_dir.join("exclude");
+    let pattern = format!("/{}/", dir_name.trim_matches('/'));
+    let existing = fs::read_to_string(&exclude).unwrap_or_default();
+    if existing.lines().any(|line| line.trim() == pattern) {
+        return Ok(());
+    }
+
+    fs::create_dir_all(&info_dir)
+        .with_context(|| format!("failed to create {}", info_dir.display()))?;
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&exclude)
+        .with_context(|| format!("failed to open {}", exclude.display()))?;
+    if !existing.is_empty() && !existing.ends_with('\n') {
+        writeln!(file)?;
+    }
+    writeln!(file, "{}", pattern)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{exclude_worktree_dir, prepare_worktree, worktree_path, WorktreeRequest};
+    use std::fs;
+    use std::path::Path;
+    use std::process::Command;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    fn sample_request() -> WorktreeRequest {
+        WorktreeRequest {
+            repo_full_name: "acme/widgets".to_string(),
+            number: 42,
+        }
+    }
+
+    #[test]
+    fn requests_use_namespaced_branches_and_pull_refs() {
+        let request = sample_request();
+        assert_eq!(request.local_branch(), "pr-42");
+        assert_eq!(request.pull_ref(), "refs/pull/42/head");
+    }
+
+    #[test]
+    fn existing_branches_are_reused() {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_nanos();
+        let root = std::env::temp_dir().join(format!("ghn-worktree-branch-{}", now));
+        let upstream = root.join("upstream");
+        let clone = root.join("clone");
+        fs::create_dir_all(&upstream).unwrap();
+        let git = |dir: &Path, args: &[&str]| -> String {
+            let output = Command::new("git")
+                .current_dir(dir)
+                .args(["-c", "user.name=t", "-c", "user.email=t@example.com"])
+                .args(args)
+                .output()
+                .unwrap();
+            assert!(output.status.success(), "git {:?}", args);
+            String::from_utf8(output.stdout).unwrap().trim().to_string()
+        };
+        let commit = |dir: &Path, message: &str| {
+            git(dir, &["commit", "-q", "--allow-empty", "-m", message]);
+            git(dir, &["rev-parse", "HEAD"])
+        };
+
+        git(&upstream, &["init", "-q"]);
+        let base = commit(&upstream, "base");
+        git(&root, &["clone", "-q", "upstream", "clone"]);
+        let head = commit(&upstream, "pr head");
+        git(&upstream, &["update-ref", "refs/pull/42/head", &head]);
+        let worktree = clone.join(".worktrees/pr-42");
+
+        // Behind the PR, e.g. after `git worktree remove`: fast-forwarded.
+        git(&clone, &["branch", "pr-42", &base]);
+        prepare_worktree(&clone, ".worktrees", &sample_request()).unwrap();
+        assert_eq!(git(&worktree, &["rev-parse", "HEAD"]), head);
+
+        // With local commits: checked out as-is, never reset.
+        let local = commit(&worktree, "local fixup");
+        git(&clone, &["worktree", "remove", ".worktrees/pr-42"]);
+        prepare_worktree(&clone, ".worktrees", &sample_request()).unwrap();
+        assert_eq!(git(&worktree, &["rev-parse", "HEAD"]), local);
+
+        let _ = fs::remove_dir_all(root);
+    }
+
+    #[test]
+    fn worktree_path_is_nested_under_repo() {
+        assert_eq!(
+            worktree_path(Path::new("/src/acme/widgets"), ".worktrees", 42),
+            Path::new("/src/acme/widgets/.worktrees/pr-42")
+        );
+    }
+
+    #[test]
+    fn exclude_is_written_once() {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_nanos();
+        let repo = std::env::temp_dir().join(format!("ghn-worktree-{}", now));
+        fs::create_dir_all(repo.join(".git/info")).unwrap();
+        fs::write(repo.join(".git/info/exclude"), "*.log").unwrap();
+
+        exclude_worktree_dir(&repo, ".worktrees").unwrap();
+        exclude_worktree_dir(&repo, ".worktrees/").unwrap();
+        assert_eq!(
+            fs::read_to_string(repo.join(".git/info/exclude")).unwrap(),
+            "*.log\n/.worktrees/\n"
+        );
+
+        let _ = fs::remove_dir_all(repo);
+    }
+}